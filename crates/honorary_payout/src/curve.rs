@@ -0,0 +1,135 @@
+use crate::math::mul_div_floor_u128;
+
+pub const MAX_CURVE_BREAKPOINTS: usize = 4;
+
+pub struct EligibilityCurveKind;
+impl EligibilityCurveKind {
+    /// `min(locked / y0, max_share)`.
+    pub const LINEAR: u8 = 0u8;
+    /// Share of the highest breakpoint whose `locked_bps` has been reached.
+    pub const STEP: u8 = 1u8;
+    /// Linear interpolation between breakpoints, starting from `(0, 0)`.
+    pub const PIECEWISE_LINEAR: u8 = 2u8;
+    /// `max_share * (locked / y0)^2`.
+    pub const CONVEX: u8 = 3u8;
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CurveBreakpoint {
+    /// Locked fraction of `y0`, in basis points.
+    pub locked_bps: u16,
+    /// Investor share once `locked_bps` is reached, in basis points.
+    pub share_bps: u16,
+}
+
+/// Maps the locked fraction of `y0` to the eligible investor share.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EligibilityCurve {
+    pub kind: u8,
+    pub breakpoint_count: u8,
+    pub breakpoints: [CurveBreakpoint; MAX_CURVE_BREAKPOINTS],
+}
+
+impl EligibilityCurve {
+    pub fn active_breakpoints(&self) -> &[CurveBreakpoint] {
+        let count = (self.breakpoint_count as usize).min(MAX_CURVE_BREAKPOINTS);
+        &self.breakpoints[..count]
+    }
+}
+
+pub struct IneligibilityReason;
+impl IneligibilityReason {
+    pub const NONE: u8 = 0u8;
+    pub const BELOW_MIN_LOCKED: u8 = 1u8;
+    pub const STREAM_TOO_YOUNG: u8 = 2u8;
+    pub const LOCK_ENDS_TOO_SOON: u8 = 3u8;
+}
+
+/// Per-stream thresholds a stream must meet to earn fees; zero disables a rule.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EligibilityRules {
+    /// Seconds left until the stream's schedule `end_time`.
+    pub min_remaining_lock_seconds: u64,
+    /// Seconds elapsed since the stream's `start_time`.
+    pub min_stream_age_seconds: u64,
+    pub min_locked_amount: u64,
+}
+
+/// First eligibility rule a stream with `locked` tokens fails at `now`, or
+/// `IneligibilityReason::NONE`. Streams with nothing locked are not reported.
+pub fn ineligibility_reason(
+    rules: &EligibilityRules,
+    locked: u64,
+    start_time: u64,
+    end_time: u64,
+    now: u64,
+) -> u8 {
+    if locked == 0 {
+        return IneligibilityReason::NONE;
+    }
+    if locked < rules.min_locked_amount {
+        return IneligibilityReason::BELOW_MIN_LOCKED;
+    }
+    if now.saturating_sub(start_time) < rules.min_stream_age_seconds {
+        return IneligibilityReason::STREAM_TOO_YOUNG;
+    }
+    if end_time.saturating_sub(now) < rules.min_remaining_lock_seconds {
+        return IneligibilityReason::LOCK_ENDS_TOO_SOON;
+    }
+    IneligibilityReason::NONE
+}
+
+/// Investor share in basis points for `locked_total` out of `y0`, shaped by `curve` and
+/// clamped to `max_share_bps`.
+pub fn eligible_share_bps(
+    locked_total: u128,
+    y0: u64,
+    max_share_bps: u16,
+    curve: &EligibilityCurve,
+) -> u16 {
+    if y0 == 0 || locked_total == 0 {
+        return 0;
+    }
+
+    let ratio = locked_total
+        .saturating_mul(10_000u128)
+        .checked_div(y0 as u128)
+        .unwrap_or(0);
+    let locked_bps = ratio.min(10_000) as u16;
+
+    let share = match curve.kind {
+        EligibilityCurveKind::STEP => curve
+            .active_breakpoints()
+            .iter()
+            .rev()
+            .find(|point| locked_bps >= point.locked_bps)
+            .map_or(0, |point| point.share_bps as u128),
+        EligibilityCurveKind::PIECEWISE_LINEAR => {
+            interpolate_breakpoints(curve.active_breakpoints(), locked_bps)
+        }
+        EligibilityCurveKind::CONVEX => {
+            let squared = (locked_bps as u128) * (locked_bps as u128);
+            mul_div_floor_u128(max_share_bps as u128, squared, 100_000_000).unwrap_or(0)
+        }
+        _ => ratio,
+    };
+    share.min(max_share_bps as u128) as u16
+}
+
+fn interpolate_breakpoints(points: &[CurveBreakpoint], locked_bps: u16) -> u128 {
+    let mut lower = CurveBreakpoint::default();
+    for point in points {
+        if locked_bps < point.locked_bps {
+            let span = point.locked_bps.saturating_sub(lower.locked_bps) as u128;
+            let rise = point.share_bps.saturating_sub(lower.share_bps) as u128;
+            let progressed = locked_bps.saturating_sub(lower.locked_bps) as u128;
+            let step = (rise * progressed).checked_div(span).unwrap_or(0);
+            return lower.share_bps as u128 + step;
+        }
+        lower = *point;
+    }
+    lower.share_bps as u128
+}
@@ -0,0 +1,23 @@
+use core::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PayoutError {
+    /// An intermediate or final amount does not fit its integer type.
+    ArithmeticOverflow,
+    /// A ratio was taken over a zero denominator.
+    DivisionByZero,
+}
+
+impl fmt::Display for PayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ArithmeticOverflow => f.write_str("arithmetic overflow"),
+            Self::DivisionByZero => f.write_str("division by zero"),
+        }
+    }
+}
+
+impl core::error::Error for PayoutError {}
+
+pub type Result<T> = core::result::Result<T, PayoutError>;
@@ -0,0 +1,28 @@
+//! Distribution math shared by the `honorary_quote_fee` program and off-chain tooling.
+//!
+//! Everything here is `no_std` + `alloc` and free of Solana types, so a backend can size
+//! shares and payouts exactly as the crank does. Enable the `serde` feature to
+//! (de)serialize inputs and outputs.
+#![no_std]
+
+extern crate alloc;
+
+pub mod curve;
+pub mod error;
+pub mod math;
+pub mod plan;
+pub mod stream;
+
+#[cfg(test)]
+mod tests;
+
+pub use curve::{
+    eligible_share_bps, ineligibility_reason, CurveBreakpoint, EligibilityCurve,
+    EligibilityCurveKind, EligibilityRules, IneligibilityReason, MAX_CURVE_BREAKPOINTS,
+};
+pub use error::{PayoutError, Result};
+pub use plan::{
+    build_payout_plan, creator_quote_at_close, investor_entitlement_quote, CapReserve, DayState,
+    PayoutPlan, PayoutTerms, MAX_BASIS_POINTS,
+};
+pub use stream::{locked_amount, LinearStream};
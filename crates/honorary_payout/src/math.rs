@@ -0,0 +1,123 @@
+use alloc::{vec, vec::Vec};
+
+use crate::error::{PayoutError, Result};
+
+#[allow(
+    clippy::manual_div_ceil,
+    clippy::assign_op_pattern,
+    clippy::ptr_offset_with_cast
+)]
+mod u256 {
+    uint::construct_uint! {
+        pub struct U256(4);
+    }
+}
+pub use u256::U256;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    Floor,
+    Ceil,
+    /// Round half up.
+    Nearest,
+}
+
+/// Computes `a * b / denominator` with a 256-bit intermediate product.
+///
+/// Only fails when the denominator is zero or the rounded result exceeds `u128`.
+pub fn mul_div_u128(a: u128, b: u128, denominator: u128, rounding: Rounding) -> Result<u128> {
+    if denominator == 0 {
+        return Err(PayoutError::DivisionByZero);
+    }
+    let denominator = U256::from(denominator);
+    let product = U256::from(a) * U256::from(b);
+    let (quotient, remainder) = product.div_mod(denominator);
+
+    let round_up = match rounding {
+        Rounding::Floor => false,
+        Rounding::Ceil => !remainder.is_zero(),
+        Rounding::Nearest => remainder >= denominator - remainder,
+    };
+    let result = if round_up {
+        quotient + U256::one()
+    } else {
+        quotient
+    };
+
+    if result > U256::from(u128::MAX) {
+        return Err(PayoutError::ArithmeticOverflow);
+    }
+    Ok(result.as_u128())
+}
+
+pub fn mul_div_floor_u128(a: u128, b: u128, denominator: u128) -> Result<u128> {
+    mul_div_u128(a, b, denominator, Rounding::Floor)
+}
+
+pub fn mul_div_ceil_u128(a: u128, b: u128, denominator: u128) -> Result<u128> {
+    mul_div_u128(a, b, denominator, Rounding::Ceil)
+}
+
+pub fn mul_div_nearest_u128(a: u128, b: u128, denominator: u128) -> Result<u128> {
+    mul_div_u128(a, b, denominator, Rounding::Nearest)
+}
+
+/// Splits `total` across `weights` so that the shares sum to `total` exactly.
+///
+/// Every entry first receives `floor(total * weight / sum)`; the leftover units go one
+/// each to the entries with the largest remainders, ties broken by lowest index.
+/// Returns all zeros when every weight is zero.
+pub fn allocate_largest_remainder(total: u64, weights: &[u64]) -> Result<Vec<u64>> {
+    let weight_sum: u128 = weights.iter().map(|weight| *weight as u128).sum();
+    if weight_sum == 0 || total == 0 {
+        return Ok(vec![0; weights.len()]);
+    }
+
+    let mut shares = Vec::with_capacity(weights.len());
+    let mut remainders = Vec::with_capacity(weights.len());
+    let mut allocated: u64 = 0;
+    for (index, weight) in weights.iter().enumerate() {
+        let numerator = (total as u128)
+            .checked_mul(*weight as u128)
+            .ok_or(PayoutError::ArithmeticOverflow)?;
+        let share = u128_to_u64(numerator / weight_sum)?;
+        allocated = allocated
+            .checked_add(share)
+            .ok_or(PayoutError::ArithmeticOverflow)?;
+        shares.push(share);
+        remainders.push((numerator % weight_sum, index));
+    }
+
+    let leftover = usize::try_from(total.saturating_sub(allocated))
+        .map_err(|_| PayoutError::ArithmeticOverflow)?;
+    if leftover > 0 {
+        remainders.sort_unstable_by(|lhs, rhs| rhs.0.cmp(&lhs.0).then(lhs.1.cmp(&rhs.1)));
+        for (_, index) in remainders.into_iter().take(leftover) {
+            shares[index] = shares[index].saturating_add(1);
+        }
+    }
+
+    Ok(shares)
+}
+
+pub fn u128_to_u64(value: u128) -> Result<u64> {
+    u64::try_from(value).map_err(|_| PayoutError::ArithmeticOverflow)
+}
+
+pub fn saturating_sub_u64(lhs: u64, rhs: u64) -> u64 {
+    lhs.saturating_sub(rhs)
+}
+
+/// Share of `total` for part `index` of `parts`, with the remainder assigned to the last part.
+pub fn split_evenly(total: u64, parts: u32, index: u32) -> u64 {
+    if parts == 0 {
+        return 0;
+    }
+
+    let base = total / u64::from(parts);
+    if index.saturating_add(1) == parts {
+        total.saturating_sub(base.saturating_mul(u64::from(parts - 1)))
+    } else {
+        base
+    }
+}
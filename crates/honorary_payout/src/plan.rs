@@ -0,0 +1,181 @@
+use alloc::vec::Vec;
+
+use crate::{
+    curve::{eligible_share_bps, EligibilityCurve},
+    error::{PayoutError, Result},
+    math::{allocate_largest_remainder, mul_div_floor_u128, saturating_sub_u64, u128_to_u64},
+};
+
+pub const MAX_BASIS_POINTS: u16 = 10_000;
+
+/// Investor quote retained across days: the excess over the daily cap plus
+/// `retention_bps` of each day's investor entitlement, released on later days
+/// while the cap leaves room.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CapReserve {
+    pub enabled: bool,
+    pub retention_bps: u16,
+    /// Reserve balance when the day opened.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub balance: u64,
+}
+
+impl CapReserve {
+    /// Investor target for the day and the reserve balance after it closes.
+    ///
+    /// With no eligible investors the reserve is released to the creator along with carry.
+    pub fn split(&self, entitlement: u64, cap: u64, share_bps: u16) -> Result<(u64, u64)> {
+        let capped = |amount: u64| if cap > 0 { amount.min(cap) } else { amount };
+        if !self.enabled {
+            return Ok((capped(entitlement), self.balance));
+        }
+        if share_bps == 0 {
+            return Ok((0, 0));
+        }
+
+        let retained = u128_to_u64(mul_div_floor_u128(
+            entitlement as u128,
+            self.retention_bps as u128,
+            MAX_BASIS_POINTS as u128,
+        )?)?;
+        let releasable = entitlement
+            .checked_sub(retained)
+            .and_then(|amount| amount.checked_add(self.balance))
+            .ok_or(PayoutError::ArithmeticOverflow)?;
+        let target = capped(releasable);
+        let reserve_after = self
+            .balance
+            .checked_add(entitlement)
+            .and_then(|amount| amount.checked_sub(target))
+            .ok_or(PayoutError::ArithmeticOverflow)?;
+        Ok((target, reserve_after))
+    }
+}
+
+/// Investors' uncapped share of `claimed_quote`.
+pub fn investor_entitlement_quote(claimed_quote: u64, share_bps: u16) -> Result<u64> {
+    u128_to_u64(mul_div_floor_u128(
+        claimed_quote as u128,
+        share_bps as u128,
+        MAX_BASIS_POINTS as u128,
+    )?)
+}
+
+/// Creator transfer when a day closes: whatever was claimed or released from the reserve
+/// that is neither the investor target nor kept in the reserve.
+pub fn creator_quote_at_close(
+    claimed_quote: u64,
+    target_investor_quote: u64,
+    reserve_before: u64,
+    reserve_after: u64,
+) -> Result<u64> {
+    let available = claimed_quote
+        .checked_add(reserve_before)
+        .ok_or(PayoutError::ArithmeticOverflow)?;
+    Ok(saturating_sub_u64(
+        available,
+        target_investor_quote.saturating_add(reserve_after),
+    ))
+}
+
+/// Policy parameters that shape a day's payouts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PayoutTerms {
+    pub y0: u64,
+    pub investor_fee_share_bps: u16,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub eligibility_curve: EligibilityCurve,
+    /// Cap on the day's investor target; `0` disables it.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub daily_cap_quote: u64,
+    /// Payouts below this are kept as carry instead of transferred.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub min_payout_lamports: u64,
+}
+
+/// Where the open day stands before a page is paid.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DayState {
+    pub claimed_quote: u64,
+    pub investor_distributed: u64,
+    pub carry_quote: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PayoutPlan {
+    /// One payout per input investor, in input order; zero below `min_payout_lamports`.
+    pub payouts: Vec<u64>,
+    pub share_bps: u16,
+    pub total_paid: u64,
+    pub target_investor_quote: u64,
+    /// Carry released to the creator because nobody is eligible.
+    pub carry_for_creator: u64,
+    pub carry_quote_after: u64,
+    /// Cap reserve balance once the day closes with this plan's target.
+    pub reserve_quote_after: u64,
+}
+
+/// Pays one page of investors, weighted by `locked_amounts`, out of the day's unpaid
+/// target plus carry. The share is sized from this page's locked total.
+pub fn build_payout_plan(
+    locked_amounts: &[u64],
+    day: &DayState,
+    terms: &PayoutTerms,
+    cap_reserve: CapReserve,
+) -> Result<PayoutPlan> {
+    let total_locked: u128 = locked_amounts.iter().map(|locked| *locked as u128).sum();
+    let share_bps = eligible_share_bps(
+        total_locked,
+        terms.y0,
+        terms.investor_fee_share_bps,
+        &terms.eligibility_curve,
+    );
+
+    let entitlement = investor_entitlement_quote(day.claimed_quote, share_bps)?;
+    let (target_investor_quote, reserve_quote_after) =
+        cap_reserve.split(entitlement, terms.daily_cap_quote, share_bps)?;
+
+    let mut available_to_pay = target_investor_quote
+        .saturating_sub(day.investor_distributed)
+        .saturating_add(day.carry_quote);
+    let mut carry_for_creator = 0u64;
+    if share_bps == 0 {
+        carry_for_creator = day.carry_quote;
+        available_to_pay = 0;
+    }
+
+    let allocations = allocate_largest_remainder(available_to_pay, locked_amounts)?;
+    let mut payouts = Vec::with_capacity(allocations.len());
+    let mut total_paid: u64 = 0;
+    for allocation in allocations {
+        let payout = if allocation < terms.min_payout_lamports {
+            0
+        } else {
+            allocation
+        };
+        payouts.push(payout);
+        total_paid = total_paid
+            .checked_add(payout)
+            .ok_or(PayoutError::ArithmeticOverflow)?;
+    }
+
+    let carry_quote_after = if share_bps == 0 {
+        0
+    } else {
+        available_to_pay.saturating_sub(total_paid)
+    };
+
+    Ok(PayoutPlan {
+        payouts,
+        share_bps,
+        total_paid,
+        target_investor_quote,
+        carry_for_creator,
+        carry_quote_after,
+        reserve_quote_after,
+    })
+}
@@ -0,0 +1,83 @@
+use crate::error::{PayoutError, Result};
+
+/// Tokens still locked in a stream that has released `withdrawn + available` so far.
+pub fn locked_amount(
+    net_amount_deposited: u64,
+    amount_withdrawn: u64,
+    available_to_claim: u64,
+) -> Result<u64> {
+    let unlocked = amount_withdrawn
+        .checked_add(available_to_claim)
+        .ok_or(PayoutError::ArithmeticOverflow)?;
+    Ok(net_amount_deposited.saturating_sub(unlocked.min(net_amount_deposited)))
+}
+
+/// A Streamflow-style schedule without pauses, rate changes or fees: `cliff_amount`
+/// releases at `cliff` (when set), then `amount_per_period` at the end of every `period`.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LinearStream {
+    pub start_time: u64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub cliff: u64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub cliff_amount: u64,
+    pub period: u64,
+    pub amount_per_period: u64,
+    pub net_amount_deposited: u64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub amount_withdrawn: u64,
+}
+
+impl LinearStream {
+    /// When periodic releases start: the cliff if one is set, otherwise `start_time`.
+    pub fn release_start(&self) -> u64 {
+        if self.cliff > 0 {
+            self.cliff
+        } else {
+            self.start_time
+        }
+    }
+
+    /// First timestamp at which everything has been released.
+    pub fn end_time(&self) -> u64 {
+        let streamed = self.net_amount_deposited.saturating_sub(self.cliff_amount);
+        let periods = if self.amount_per_period == 0 {
+            0
+        } else {
+            streamed.div_ceil(self.amount_per_period)
+        };
+        self.release_start()
+            .saturating_add(periods.saturating_mul(self.period))
+    }
+
+    /// Released amount at `now` that has not been withdrawn yet.
+    pub fn available_to_claim(&self, now: u64) -> u64 {
+        let start = self.release_start();
+        if now < start || self.net_amount_deposited <= self.amount_withdrawn {
+            return 0;
+        }
+        if now >= self.end_time() {
+            return self.net_amount_deposited - self.amount_withdrawn;
+        }
+        let cliff_released = if now >= self.cliff {
+            self.cliff_amount
+        } else {
+            0
+        };
+        let periods = (now - start).checked_div(self.period).unwrap_or(0);
+        let streamed = periods.saturating_mul(self.amount_per_period);
+        cliff_released
+            .saturating_add(streamed)
+            .min(self.net_amount_deposited)
+            .saturating_sub(self.amount_withdrawn)
+    }
+
+    pub fn locked_at(&self, now: u64) -> Result<u64> {
+        locked_amount(
+            self.net_amount_deposited,
+            self.amount_withdrawn,
+            self.available_to_claim(now),
+        )
+    }
+}
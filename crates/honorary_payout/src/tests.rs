@@ -0,0 +1,130 @@
+#![cfg(test)]
+extern crate std;
+
+use alloc::vec;
+use std::println;
+
+use super::*;
+use crate::math::{mul_div_u128, u128_to_u64, Rounding};
+
+#[test]
+fn test_payout_errors() {
+    println!("Testing payout error reporting...");
+
+    // Test 1: Zero denominators and oversized results are distinct errors
+    assert_eq!(
+        mul_div_u128(1, 1, 0, Rounding::Floor),
+        Err(PayoutError::DivisionByZero)
+    );
+    assert_eq!(
+        u128_to_u64(u64::MAX as u128 + 1),
+        Err(PayoutError::ArithmeticOverflow)
+    );
+    println!("✓ Test 1 passed: error variants distinguished");
+
+    // Test 2: Overflowing carry is reported rather than wrapped
+    let plan = build_payout_plan(
+        &[1, 1],
+        &DayState {
+            claimed_quote: u64::MAX,
+            investor_distributed: 0,
+            carry_quote: u64::MAX,
+        },
+        &PayoutTerms {
+            y0: 1,
+            investor_fee_share_bps: MAX_BASIS_POINTS,
+            ..PayoutTerms::default()
+        },
+        CapReserve {
+            enabled: true,
+            retention_bps: 0,
+            balance: u64::MAX,
+        },
+    );
+    assert_eq!(plan, Err(PayoutError::ArithmeticOverflow));
+    println!("✓ Test 2 passed: overflow surfaced");
+
+    println!("✅ All payout error tests passed\n");
+}
+
+#[test]
+fn test_linear_stream_schedule() {
+    println!("Testing linear stream schedule...");
+
+    let stream = LinearStream {
+        start_time: 1_000,
+        cliff: 0,
+        cliff_amount: 0,
+        period: 100,
+        amount_per_period: 30,
+        net_amount_deposited: 100,
+        amount_withdrawn: 0,
+    };
+
+    // Test 1: Four periods cover 100 at 30 per period
+    assert_eq!(stream.end_time(), 1_400);
+    assert_eq!(stream.locked_at(999).unwrap(), 100);
+    assert_eq!(stream.locked_at(1_199).unwrap(), 70);
+    assert_eq!(stream.locked_at(1_399).unwrap(), 10);
+    assert_eq!(stream.locked_at(1_400).unwrap(), 0);
+    println!("✓ Test 1 passed: periodic release");
+
+    // Test 2: A cliff releases its amount and starts the periods
+    let cliffed = LinearStream {
+        cliff: 1_500,
+        cliff_amount: 40,
+        ..stream
+    };
+    assert_eq!(cliffed.end_time(), 1_700);
+    assert_eq!(cliffed.locked_at(1_499).unwrap(), 100);
+    assert_eq!(cliffed.locked_at(1_500).unwrap(), 60);
+    assert_eq!(cliffed.locked_at(1_650).unwrap(), 30);
+    println!("✓ Test 2 passed: cliff release");
+
+    // Test 3: Withdrawals count as unlocked
+    let withdrawn = LinearStream {
+        amount_withdrawn: 30,
+        ..stream
+    };
+    assert_eq!(withdrawn.locked_at(1_150).unwrap(), 70);
+    assert_eq!(locked_amount(100, 90, 50).unwrap(), 0);
+    println!("✓ Test 3 passed: withdrawals unlocked");
+
+    println!("✅ All linear stream tests passed\n");
+}
+
+#[test]
+fn test_payout_plan_accounts_for_every_unit() {
+    println!("Testing payout plan conservation...");
+
+    let terms = PayoutTerms {
+        y0: 1_000,
+        investor_fee_share_bps: 6_000,
+        min_payout_lamports: 10,
+        ..PayoutTerms::default()
+    };
+    let day = DayState {
+        claimed_quote: 1_001,
+        investor_distributed: 0,
+        carry_quote: 7,
+    };
+
+    // Test 1: Share is clamped to the policy maximum and payouts follow locked weight
+    let plan = build_payout_plan(&[500, 300, 10], &day, &terms, CapReserve::default()).unwrap();
+    assert_eq!(plan.share_bps, 6_000);
+    assert_eq!(plan.target_investor_quote, 600);
+    assert_eq!(plan.payouts, vec![375, 225, 0]);
+    assert_eq!(plan.total_paid + plan.carry_quote_after, 600 + 7);
+    println!("✓ Test 1 passed: dust below min payout stays in carry");
+
+    // Test 2: Nobody locked sends carry to the creator
+    let plan = build_payout_plan(&[0, 0], &day, &terms, CapReserve::default()).unwrap();
+    assert_eq!(plan.share_bps, 0);
+    assert_eq!(plan.payouts, vec![0, 0]);
+    assert_eq!(plan.carry_for_creator, 7);
+    assert_eq!(plan.carry_quote_after, 0);
+    assert_eq!(creator_quote_at_close(1_001, 0, 0, 0).unwrap(), 1_001);
+    println!("✓ Test 2 passed: all unlocked routes to creator");
+
+    println!("✅ All payout plan tests passed\n");
+}
@@ -0,0 +1,73 @@
+//! `payout-simulator <season.json | -> [--json]`
+//!
+//! Prints one line per day and the season totals per investor, or the full report as JSON.
+
+use std::{env, fs, io::Read, process::ExitCode};
+
+use payout_simulator::{simulate_season, SeasonInput};
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let json_output = args.iter().any(|arg| arg == "--json");
+    let Some(path) = args.iter().find(|arg| !arg.starts_with("--")) else {
+        eprintln!("usage: payout-simulator <season.json | -> [--json]");
+        return ExitCode::FAILURE;
+    };
+
+    let input = if path == "-" {
+        let mut input = String::new();
+        std::io::stdin().read_to_string(&mut input).map(|_| input)
+    } else {
+        fs::read_to_string(path)
+    };
+    let season: SeasonInput = match input
+        .map_err(|error| error.to_string())
+        .and_then(|input| serde_json::from_str(&input).map_err(|error| error.to_string()))
+    {
+        Ok(season) => season,
+        Err(error) => {
+            eprintln!("failed to read season {path}: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let report = match simulate_season(&season) {
+        Ok(report) => report,
+        Err(error) => {
+            eprintln!("simulation failed: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if json_output {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).expect("report serializes")
+        );
+        return ExitCode::SUCCESS;
+    }
+
+    println!(
+        "{:>12} {:>14} {:>6} {:>14} {:>14} {:>10} {:>10}",
+        "ts", "claimed", "bps", "investors", "creator", "carry", "reserve"
+    );
+    for day in report.days.iter() {
+        println!(
+            "{:>12} {:>14} {:>6} {:>14} {:>14} {:>10} {:>10}",
+            day.ts,
+            day.claimed_quote,
+            day.share_bps,
+            day.investor_quote_paid,
+            day.creator_quote_paid,
+            day.carry_quote,
+            day.reserve_quote
+        );
+    }
+    println!();
+    for investor in report.investors.iter() {
+        println!("{:<24} {:>14}", investor.name, investor.quote_paid);
+    }
+    println!("{:<24} {:>14}", "creator", report.creator_quote_paid);
+    println!("{:<24} {:>14}", "carry (unpaid)", report.carry_quote);
+    println!("{:<24} {:>14}", "reserve (unpaid)", report.reserve_quote);
+    ExitCode::SUCCESS
+}
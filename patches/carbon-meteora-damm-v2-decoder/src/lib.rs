@@ -170,4 +170,42 @@ pub mod accounts {
             }
         }
     }
+
+    pub mod position {
+        use super::super::{types::*, Pubkey};
+        use borsh::BorshDeserialize;
+
+        #[derive(BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+        pub struct Position {
+            pub pool: Pubkey,
+            pub nft_mint: Pubkey,
+            pub fee_a_per_token_checkpoint: [u8; 32],
+            pub fee_b_per_token_checkpoint: [u8; 32],
+            pub fee_a_pending: u64,
+            pub fee_b_pending: u64,
+            pub unlocked_liquidity: u128,
+            pub vested_liquidity: u128,
+            pub permanent_locked_liquidity: u128,
+            pub metrics: PositionMetrics,
+            pub reward_infos: [UserRewardInfo; 2],
+            pub padding: [u128; 6],
+        }
+
+        impl Position {
+            const DISCRIMINATOR: [u8; 8] = [0xaa, 0xbc, 0x8f, 0xe4, 0x7a, 0x40, 0xf7, 0xd0];
+
+            pub fn deserialize(data: &[u8]) -> Option<Self> {
+                if data.len() < Self::DISCRIMINATOR.len() {
+                    return None;
+                }
+
+                let (disc, rest) = data.split_at(Self::DISCRIMINATOR.len());
+                if disc != Self::DISCRIMINATOR {
+                    return None;
+                }
+
+                <Self as BorshDeserialize>::try_from_slice(rest).ok()
+            }
+        }
+    }
 }
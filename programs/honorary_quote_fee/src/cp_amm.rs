@@ -3,14 +3,14 @@ use anchor_lang::{
     solana_program::{
         account_info::AccountInfo,
         instruction::{AccountMeta, Instruction},
-        program::invoke_signed,
+        program::{invoke, invoke_signed},
     },
 };
 use carbon_meteora_damm_v2_decoder::accounts::pool::Pool as DammPoolAccount;
 
 use crate::{
     errors::HonoraryQuoteFeeError,
-    state::{HonoraryPosition, HONORARY_POSITION_SEED},
+    state::{FeeCaptureMode, HonoraryPosition, HONORARY_POSITION_SEED},
 };
 
 #[allow(dead_code)]
@@ -30,7 +30,73 @@ impl CollectFeeMode {
     }
 }
 
-const CLAIM_POSITION_FEE_DISCRIMINATOR: [u8; 8] = [0xd3, 0xa2, 0x21, 0x85, 0x11, 0x9a, 0x26, 0xb4];
+/// DAMM v2 `Pool::pool_status` value for an enabled pool.
+const POOL_STATUS_ENABLED: u8 = 0;
+
+pub struct DammActivationType;
+impl DammActivationType {
+    pub const SLOT: u8 = 0u8;
+    pub const TIMESTAMP: u8 = 1u8;
+}
+
+pub struct PoolInactiveReason;
+impl PoolInactiveReason {
+    pub const DISABLED: u8 = 1u8;
+    pub const NOT_ACTIVATED: u8 = 2u8;
+}
+
+/// Status and activation fields of a DAMM v2 pool, plus the clock value they compare to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoolActivity {
+    pub pool_status: u8,
+    pub activation_type: u8,
+    pub activation_point: u64,
+    pub current_point: u64,
+}
+
+impl PoolActivity {
+    pub fn new(pool_status: u8, activation_type: u8, activation_point: u64, clock: &Clock) -> Self {
+        let current_point = match activation_type {
+            DammActivationType::SLOT => clock.slot,
+            DammActivationType::TIMESTAMP => u64::try_from(clock.unix_timestamp).unwrap_or(0),
+            // Unknown activation types only count as activated from point zero.
+            _ => 0,
+        };
+        Self {
+            pool_status,
+            activation_type,
+            activation_point,
+            current_point,
+        }
+    }
+
+    /// Why DAMM would reject a fee claim right now, if it would.
+    pub fn inactive_reason(&self) -> Option<u8> {
+        if self.pool_status != POOL_STATUS_ENABLED {
+            Some(PoolInactiveReason::DISABLED)
+        } else if self.current_point < self.activation_point {
+            Some(PoolInactiveReason::NOT_ACTIVATED)
+        } else {
+            None
+        }
+    }
+}
+
+pub fn read_pool_activity(pool: &AccountInfo<'_>, clock: &Clock) -> Result<PoolActivity> {
+    let pool_data = pool.try_borrow_data()?;
+    let pool = DammPoolAccount::deserialize(&pool_data)
+        .ok_or_else(|| error!(HonoraryQuoteFeeError::InvalidPoolAccount))?;
+    Ok(PoolActivity::new(
+        pool.pool_status,
+        pool.activation_type,
+        pool.activation_point,
+        clock,
+    ))
+}
+
+const CLAIM_POSITION_FEE_DISCRIMINATOR: [u8; 8] = [0xb4, 0x26, 0x9a, 0x11, 0x85, 0x21, 0xa2, 0xd3];
+const CLAIM_PARTNER_FEE_DISCRIMINATOR: [u8; 8] = [0x61, 0xce, 0x27, 0x69, 0x5e, 0x5e, 0x7e, 0x94];
+const CREATE_POSITION_DISCRIMINATOR: [u8; 8] = [0x30, 0xd7, 0xc5, 0x99, 0x60, 0xcb, 0xb4, 0x85];
 
 pub fn assert_quote_only_pool(
     pool: &DammPoolAccount,
@@ -121,3 +187,221 @@ pub fn invoke_claim_position_fee<'info>(
 
     Ok(())
 }
+
+/// DAMM accounts shared by the position-fee and partner-fee claims of one policy.
+pub struct DammClaimAccounts<'info> {
+    pub cp_amm_program: AccountInfo<'info>,
+    pub pool: AccountInfo<'info>,
+    pub pool_authority: AccountInfo<'info>,
+    pub position: AccountInfo<'info>,
+    pub position_nft_account: AccountInfo<'info>,
+    pub base_fee_check: AccountInfo<'info>,
+    pub quote_treasury: AccountInfo<'info>,
+    pub base_vault: AccountInfo<'info>,
+    pub quote_vault: AccountInfo<'info>,
+    pub base_mint: AccountInfo<'info>,
+    pub quote_mint: AccountInfo<'info>,
+    pub token_program_a: AccountInfo<'info>,
+    pub token_program_b: AccountInfo<'info>,
+    pub event_authority: AccountInfo<'info>,
+}
+
+/// Claims every fee source enabled by `fee_capture_mode` into the policy treasuries.
+#[inline(never)]
+pub fn claim_policy_fees<'info>(
+    policy_key: Pubkey,
+    fee_capture_mode: u8,
+    honorary_position: &Account<'info, HonoraryPosition>,
+    accounts: &DammClaimAccounts<'info>,
+) -> Result<()> {
+    let honorary_position_info = honorary_position.to_account_info();
+
+    if fee_capture_mode & FeeCaptureMode::POSITION_FEES != 0 {
+        invoke_claim_position_fee(
+            policy_key,
+            honorary_position,
+            &accounts.cp_amm_program,
+            &accounts.pool,
+            &accounts.pool_authority,
+            &accounts.position,
+            &accounts.base_fee_check,
+            &accounts.quote_treasury,
+            &accounts.base_vault,
+            &accounts.quote_vault,
+            &accounts.base_mint,
+            &accounts.quote_mint,
+            &accounts.position_nft_account,
+            &honorary_position_info,
+            &accounts.token_program_a,
+            &accounts.token_program_b,
+            &accounts.event_authority,
+        )?;
+    }
+
+    if fee_capture_mode & FeeCaptureMode::PARTNER_FEES != 0 {
+        assert_partner_is_honorary(&accounts.pool, honorary_position.key())?;
+        invoke_claim_partner_fee(
+            policy_key,
+            honorary_position,
+            &accounts.cp_amm_program,
+            &accounts.pool,
+            &accounts.pool_authority,
+            &accounts.base_fee_check,
+            &accounts.quote_treasury,
+            &accounts.base_vault,
+            &accounts.quote_vault,
+            &accounts.base_mint,
+            &accounts.quote_mint,
+            &honorary_position_info,
+            &accounts.token_program_a,
+            &accounts.token_program_b,
+            &accounts.event_authority,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Claims the pool's accrued partner quote fees into `token_b_account`.
+///
+/// Only token B (quote) is requested; any base partner fee stays in the pool.
+#[allow(clippy::too_many_arguments)]
+pub fn invoke_claim_partner_fee<'info>(
+    policy_key: Pubkey,
+    honorary_position: &Account<'info, HonoraryPosition>,
+    cp_amm_program: &AccountInfo<'info>,
+    pool: &AccountInfo<'info>,
+    pool_authority: &AccountInfo<'info>,
+    token_a_account: &AccountInfo<'info>,
+    token_b_account: &AccountInfo<'info>,
+    token_a_vault: &AccountInfo<'info>,
+    token_b_vault: &AccountInfo<'info>,
+    token_a_mint: &AccountInfo<'info>,
+    token_b_mint: &AccountInfo<'info>,
+    honorary_position_info: &AccountInfo<'info>,
+    token_program_a: &AccountInfo<'info>,
+    token_program_b: &AccountInfo<'info>,
+    event_authority: &AccountInfo<'info>,
+) -> Result<()> {
+    let accounts = vec![
+        AccountMeta::new_readonly(*pool_authority.key, false),
+        AccountMeta::new(*pool.key, false),
+        AccountMeta::new(*token_a_account.key, false),
+        AccountMeta::new(*token_b_account.key, false),
+        AccountMeta::new(*token_a_vault.key, false),
+        AccountMeta::new(*token_b_vault.key, false),
+        AccountMeta::new_readonly(*token_a_mint.key, false),
+        AccountMeta::new_readonly(*token_b_mint.key, false),
+        AccountMeta::new_readonly(honorary_position.key(), true),
+        AccountMeta::new_readonly(*token_program_a.key, false),
+        AccountMeta::new_readonly(*token_program_b.key, false),
+        AccountMeta::new_readonly(*event_authority.key, false),
+        AccountMeta::new_readonly(*cp_amm_program.key, false),
+    ];
+
+    let mut data = CLAIM_PARTNER_FEE_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.extend_from_slice(&u64::MAX.to_le_bytes());
+
+    let ix = Instruction {
+        program_id: *cp_amm_program.key,
+        accounts,
+        data,
+    };
+
+    let bump_seed = [honorary_position.bump];
+    let seeds: [&[u8]; 3] = [HONORARY_POSITION_SEED, policy_key.as_ref(), &bump_seed];
+    let signer_seeds: &[&[&[u8]]] = &[&seeds];
+
+    let account_infos = vec![
+        pool_authority.clone(),
+        pool.clone(),
+        token_a_account.clone(),
+        token_b_account.clone(),
+        token_a_vault.clone(),
+        token_b_vault.clone(),
+        token_a_mint.clone(),
+        token_b_mint.clone(),
+        honorary_position_info.clone(),
+        token_program_a.clone(),
+        token_program_b.clone(),
+        event_authority.clone(),
+        cp_amm_program.clone(),
+    ];
+
+    invoke_signed(&ix, &account_infos, signer_seeds)?;
+
+    Ok(())
+}
+
+/// Requires the pool's partner to be the policy's honorary PDA.
+pub fn assert_partner_is_honorary(pool: &AccountInfo<'_>, honorary_position: Pubkey) -> Result<()> {
+    let pool_partner = {
+        let pool_data = pool.try_borrow_data()?;
+        let pool = DammPoolAccount::deserialize(&pool_data)
+            .ok_or_else(|| error!(HonoraryQuoteFeeError::InvalidPoolAccount))?;
+        Pubkey::new_from_array(pool.partner.to_bytes())
+    };
+
+    require_keys_eq!(
+        pool_partner,
+        honorary_position,
+        HonoraryQuoteFeeError::PartnerMismatch
+    );
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn invoke_create_position<'info>(
+    cp_amm_program: &AccountInfo<'info>,
+    owner: &AccountInfo<'info>,
+    position_nft_mint: &AccountInfo<'info>,
+    position_nft_account: &AccountInfo<'info>,
+    pool: &AccountInfo<'info>,
+    position: &AccountInfo<'info>,
+    pool_authority: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    position_token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    event_authority: &AccountInfo<'info>,
+) -> Result<()> {
+    let accounts = vec![
+        AccountMeta::new_readonly(*owner.key, false),
+        AccountMeta::new(*position_nft_mint.key, true),
+        AccountMeta::new(*position_nft_account.key, false),
+        AccountMeta::new(*pool.key, false),
+        AccountMeta::new(*position.key, false),
+        AccountMeta::new_readonly(*pool_authority.key, false),
+        AccountMeta::new(*payer.key, true),
+        AccountMeta::new_readonly(*position_token_program.key, false),
+        AccountMeta::new_readonly(*system_program.key, false),
+        AccountMeta::new_readonly(*event_authority.key, false),
+        AccountMeta::new_readonly(*cp_amm_program.key, false),
+    ];
+
+    let ix = Instruction {
+        program_id: *cp_amm_program.key,
+        accounts,
+        data: CREATE_POSITION_DISCRIMINATOR.to_vec(),
+    };
+
+    let account_infos = vec![
+        owner.clone(),
+        position_nft_mint.clone(),
+        position_nft_account.clone(),
+        pool.clone(),
+        position.clone(),
+        pool_authority.clone(),
+        payer.clone(),
+        position_token_program.clone(),
+        system_program.clone(),
+        event_authority.clone(),
+        cp_amm_program.clone(),
+    ];
+
+    // The owner is only recorded by DAMM; the NFT mint keypair and payer sign the outer tx.
+    invoke(&ix, &account_infos)?;
+
+    Ok(())
+}
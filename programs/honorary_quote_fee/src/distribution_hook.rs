@@ -0,0 +1,132 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        account_info::AccountInfo,
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+    },
+};
+
+use crate::{errors::HonoraryQuoteFeeError, state::HOOK_AUTHORITY_SEED};
+
+/// `sha256("global:on_day_closed")[..8]`, so Anchor hook programs can expose an
+/// `on_day_closed(args: DayClosedHookArgs)` instruction directly.
+pub const ON_DAY_CLOSED_DISCRIMINATOR: [u8; 8] = [0x81, 0xf7, 0x12, 0x96, 0x00, 0xae, 0xe8, 0xee];
+
+pub struct HookFailureMode;
+impl HookFailureMode {
+    /// A missing or mismatched hook account fails the closing crank page.
+    pub const FATAL: u8 = 0u8;
+    /// A missing or mismatched hook account is logged and reported in
+    /// `DistributionHookFailed` and the day still closes. A hook that fails inside the CPI
+    /// aborts the transaction in either mode; the runtime gives the caller no error to
+    /// ignore.
+    pub const IGNORE: u8 = 1u8;
+}
+
+/// Instruction data following the discriminator, Borsh encoded.
+///
+/// Accounts passed to the hook, in order:
+/// 0. `policy` – readonly
+/// 1. `hook_authority` – readonly signer, the `["hook", policy]` PDA; proves the call
+///    comes from this program without lending the hook the honorary PDA's authority
+/// 2. the crank's trailing `hook_account_count` remaining accounts, with their
+///    signer/writable flags unchanged
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DayClosedHookArgs {
+    pub policy: Pubkey,
+    pub day_start_ts: i64,
+    pub day_count: u32,
+    pub claimed_quote: u64,
+    pub investor_quote_paid: u64,
+    pub creator_quote_paid: u64,
+    pub share_bps: u16,
+}
+
+impl DayClosedHookArgs {
+    pub fn instruction_data(&self) -> Result<Vec<u8>> {
+        let mut data = ON_DAY_CLOSED_DISCRIMINATOR.to_vec();
+        self.serialize(&mut data)
+            .map_err(|_| error!(HonoraryQuoteFeeError::InvalidDistributionHook))?;
+        Ok(data)
+    }
+}
+
+/// Address and bump of the PDA that signs distribution hook calls for `policy`.
+pub fn hook_authority_address(policy: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[HOOK_AUTHORITY_SEED, policy.as_ref()], &crate::ID)
+}
+
+/// Hook program and signing PDA supplied to the crank, checked against the policy.
+pub struct DistributionHook<'a, 'info> {
+    program: &'a AccountInfo<'info>,
+    authority: &'a AccountInfo<'info>,
+    bump: u8,
+}
+
+/// Checks the hook accounts before any CPI; these are the only hook failures
+/// `HookFailureMode::IGNORE` can skip.
+pub fn resolve_distribution_hook<'a, 'info>(
+    hook_program_id: Pubkey,
+    hook_program: Option<&'a AccountInfo<'info>>,
+    policy: &Pubkey,
+    hook_authority: Option<&'a AccountInfo<'info>>,
+) -> Result<DistributionHook<'a, 'info>> {
+    let program = hook_program.ok_or(HonoraryQuoteFeeError::InvalidDistributionHook)?;
+    let authority = hook_authority.ok_or(HonoraryQuoteFeeError::InvalidDistributionHook)?;
+    let (hook_authority_key, bump) = hook_authority_address(policy);
+    require_keys_eq!(
+        authority.key(),
+        hook_authority_key,
+        HonoraryQuoteFeeError::InvalidDistributionHook
+    );
+    require_keys_eq!(
+        program.key(),
+        hook_program_id,
+        HonoraryQuoteFeeError::InvalidDistributionHook
+    );
+    require!(
+        program.executable,
+        HonoraryQuoteFeeError::InvalidDistributionHook
+    );
+    Ok(DistributionHook {
+        program,
+        authority,
+        bump,
+    })
+}
+
+pub fn invoke_distribution_hook<'info>(
+    hook: &DistributionHook<'_, 'info>,
+    policy: &AccountInfo<'info>,
+    extra_accounts: &[AccountInfo<'info>],
+    args: &DayClosedHookArgs,
+) -> Result<()> {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(policy.key(), false),
+        AccountMeta::new_readonly(hook.authority.key(), true),
+    ];
+    accounts.extend(extra_accounts.iter().map(|account| AccountMeta {
+        pubkey: account.key(),
+        is_signer: account.is_signer,
+        is_writable: account.is_writable,
+    }));
+
+    let ix = Instruction {
+        program_id: hook.program.key(),
+        accounts,
+        data: args.instruction_data()?,
+    };
+
+    let bump_seed = [hook.bump];
+    let seeds: [&[u8]; 3] = [HOOK_AUTHORITY_SEED, args.policy.as_ref(), &bump_seed];
+    let signer_seeds: &[&[&[u8]]] = &[&seeds];
+
+    let mut account_infos = vec![policy.clone(), hook.authority.clone()];
+    account_infos.extend(extra_accounts.iter().cloned());
+    account_infos.push(hook.program.clone());
+
+    invoke_signed(&ix, &account_infos, signer_seeds)?;
+
+    Ok(())
+}
@@ -76,4 +76,111 @@ pub enum HonoraryQuoteFeeError {
     TreasuryMintMismatch,
     #[msg("Treasury account owner mismatch")]
     TreasuryOwnerMismatch,
+    #[msg("Force-close grace period must be non-negative")]
+    InvalidForceCloseGrace,
+    #[msg("Unknown force-close destination")]
+    InvalidForceCloseDestination,
+    #[msg("Open day cannot be force-closed before the grace period elapses")]
+    ForceCloseNotReady,
+    #[msg("Policy day history account must be supplied")]
+    DayHistoryMissing,
+    #[msg("Invalid day history account")]
+    InvalidDayHistoryAccount,
+    #[msg("Fee capture mode must select position fees, partner fees or both")]
+    InvalidFeeCaptureMode,
+    #[msg("Partner fee capture requires the honorary PDA to be the pool partner")]
+    PartnerMismatch,
+    #[msg("Policy is already part of a group")]
+    PolicyAlreadyGrouped,
+    #[msg("Policy group has no free member slots")]
+    PolicyGroupFull,
+    #[msg("Grouped policies must share the primary quote mint")]
+    GroupQuoteMintMismatch,
+    #[msg("Policy is not a member of this group")]
+    NotGroupMember,
+    #[msg("Policy group account must be supplied")]
+    PolicyGroupMissing,
+    #[msg("Grouped member policies are distributed by the group's primary policy")]
+    GroupedPolicyCrank,
+    #[msg("Every group member must claim fees before a grouped day opens")]
+    GroupMembersNotClaimed,
+    #[msg("Deposit amount must be greater than zero")]
+    InvalidDepositAmount,
+    #[msg("Eligibility curve kind or breakpoints are invalid")]
+    InvalidEligibilityCurve,
+    #[msg("Blocklist has no free slots")]
+    BlocklistFull,
+    #[msg("Recipient is not on the blocklist")]
+    RecipientNotBlocked,
+    #[msg("Policy blocklist and forfeiture account must be supplied")]
+    BlocklistMissing,
+    #[msg("Invalid blocklist or forfeiture account")]
+    InvalidBlocklistAccount,
+    #[msg("Reserve retention must be <= 10,000 bps and requires the cap reserve")]
+    InvalidReserveRetention,
+    #[msg("Cranker allowlist has no free slots")]
+    CrankerAllowlistFull,
+    #[msg("Policy cranker allowlist account must be supplied")]
+    CrankerAllowlistMissing,
+    #[msg("Invalid cranker allowlist account")]
+    InvalidCrankerAllowlist,
+    #[msg("Cranker is not on the policy allowlist")]
+    CrankerNotAllowed,
+    #[msg("Minimum page interval must be non-negative")]
+    InvalidPageInterval,
+    #[msg("Crank page submitted before the minimum page interval elapsed")]
+    PageRateLimited,
+    #[msg("Invalid distribution hook program or failure mode")]
+    InvalidDistributionHook,
+    #[msg("Timelock must be non-negative")]
+    InvalidTimelock,
+    #[msg("Policy change must modify at least one field")]
+    EmptyPolicyChange,
+    #[msg("New creator quote account must be supplied and hold the policy quote mint")]
+    InvalidCreatorQuoteAccount,
+    #[msg("Policy change timelock has not elapsed")]
+    PolicyChangeNotReady,
+    #[msg("Policy changes can only be executed between distribution days")]
+    PolicyChangeDuringDay,
+    #[msg("Rescue amount must be greater than zero")]
+    InvalidRescueAmount,
+    #[msg("Rescue would move quote committed to investors")]
+    RescueExceedsAvailable,
+    #[msg(
+        "Vesting schedule needs a non-negative start, a positive duration of at most 366 days and a cliff within it"
+    )]
+    InvalidVestingSchedule,
+    #[msg("Policy creator escrow and vault accounts must be supplied")]
+    CreatorEscrowMissing,
+    #[msg("Invalid creator escrow or escrow vault account")]
+    InvalidCreatorEscrow,
+    #[msg("No vested creator quote is available to withdraw")]
+    NothingVested,
+    #[msg("Policy requires an investor snapshot commitment when a day opens")]
+    InvestorSnapshotMissing,
+    #[msg("Quote treasury cannot be rescued while a distribution day is open")]
+    RescueDuringDay,
+    #[msg("Creator escrow has no free vesting tranche")]
+    CreatorEscrowFull,
+    #[msg("Division by zero")]
+    DivisionByZero,
+    #[msg("Snapshot pages are committed whole; max_investors or max_page_cursor would split it")]
+    SnapshotPageTruncated,
+    #[msg("Crank page does not match the investor snapshot committed at day open")]
+    InvestorSnapshotMismatch,
+    #[msg("Policy and progress accounts already have the current layout")]
+    AccountAlreadyMigrated,
+    #[msg("Policy accounts cannot be migrated while a distribution day is open")]
+    MigrationDuringDay,
+}
+
+impl From<honorary_payout::PayoutError> for HonoraryQuoteFeeError {
+    fn from(error: honorary_payout::PayoutError) -> Self {
+        match error {
+            honorary_payout::PayoutError::ArithmeticOverflow => {
+                HonoraryQuoteFeeError::ArithmeticOverflow
+            }
+            honorary_payout::PayoutError::DivisionByZero => HonoraryQuoteFeeError::DivisionByZero,
+        }
+    }
 }
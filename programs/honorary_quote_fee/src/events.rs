@@ -1,23 +1,143 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{
+    event::EVENT_IX_TAG_LE,
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+    },
+    Event,
+};
+
+use crate::state::PolicyChange;
+
+/// Schema version carried by every event. Bump it whenever an event's fields change so
+/// indexers can pick the matching decoder.
+pub const EVENT_VERSION: u8 = 3;
+
+/// Seed of the PDA that signs self-CPI events; matches the account `#[event_cpi]` adds.
+pub const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+
+/// Emits events through the same self-CPI as `emit_cpi!`, for helpers that only receive
+/// the event authority rather than the instruction `Context`.
+pub struct EventEmitter<'a, 'info> {
+    authority: &'a AccountInfo<'info>,
+    bump: u8,
+}
+
+impl<'a, 'info> EventEmitter<'a, 'info> {
+    pub fn new(authority: &'a AccountInfo<'info>, bump: u8) -> Self {
+        Self { authority, bump }
+    }
+
+    pub fn emit<E: Event>(&self, event: &E) -> Result<()> {
+        let ix = Instruction::new_with_bytes(
+            crate::ID,
+            &event_cpi_data(event),
+            vec![AccountMeta::new_readonly(*self.authority.key, true)],
+        );
+        invoke_signed(
+            &ix,
+            core::slice::from_ref(self.authority),
+            &[&[EVENT_AUTHORITY_SEED, &[self.bump]]],
+        )
+        .map_err(Into::into)
+    }
+}
+
+/// Instruction data of an event self-CPI: the event tag followed by the event's own
+/// discriminator and Borsh body.
+pub fn event_cpi_data<E: Event>(event: &E) -> Vec<u8> {
+    let mut data = EVENT_IX_TAG_LE.to_vec();
+    data.extend(event.data());
+    data
+}
+
+pub struct PageRejectionReason;
+impl PageRejectionReason {
+    /// `expected_page_cursor` did not match progress (a replayed or racing crank).
+    pub const STALE_CURSOR: u8 = 1u8;
+    /// The page arrived before `min_page_interval_seconds` elapsed.
+    pub const RATE_LIMITED: u8 = 2u8;
+    /// The page's streams differ from the investor snapshot committed at day open.
+    pub const SNAPSHOT_MISMATCH: u8 = 3u8;
+}
+
+#[event]
+pub struct PolicyInitialized {
+    pub version: u8,
+    pub policy: Pubkey,
+    pub authority: Pubkey,
+    pub pool: Pubkey,
+    pub quote_mint: Pubkey,
+    pub vesting_mint: Pubkey,
+    pub policy_index: u16,
+    pub investor_fee_share_bps: u16,
+    pub y0: u64,
+    pub daily_cap_quote: u64,
+    pub min_payout_lamports: u64,
+    pub quote_decimals: u8,
+    pub vesting_decimals: u8,
+}
+
+#[event]
+pub struct DayOpened {
+    pub version: u8,
+    pub policy: Pubkey,
+    pub day_start_ts: i64,
+    pub day_count: u32,
+    pub carry_quote: u64,
+    pub reserve_quote: u64,
+}
+
+#[event]
+pub struct PageRejected {
+    pub version: u8,
+    pub policy: Pubkey,
+    pub cranker: Pubkey,
+    pub reason: u8,
+    pub expected_page_cursor: u32,
+    pub page_cursor: u32,
+    pub last_page_ts: i64,
+}
 
 #[event]
 pub struct HonoraryPositionInitialized {
+    pub version: u8,
     pub policy: Pubkey,
     pub position: Pubkey,
     pub quote_treasury: Pubkey,
 }
 
+#[event]
+pub struct DayHistoryInitialized {
+    pub version: u8,
+    pub policy: Pubkey,
+    pub day_history: Pubkey,
+}
+
 #[event]
 pub struct QuoteFeesClaimed {
+    pub version: u8,
     pub policy: Pubkey,
     pub day_start_ts: i64,
     pub quote_fees_claimed: u64,
     pub cumulative_claimed: u64,
     pub eligible_share_bps: u16,
+    pub source: u8,
+}
+
+#[event]
+pub struct QuoteDeposited {
+    pub version: u8,
+    pub policy: Pubkey,
+    pub depositor: Pubkey,
+    pub amount: u64,
+    pub pending_deposit_quote: u64,
 }
 
 #[event]
 pub struct InvestorPayoutPage {
+    pub version: u8,
     pub policy: Pubkey,
     pub day_start_ts: i64,
     pub page_start: u32,
@@ -26,12 +146,203 @@ pub struct InvestorPayoutPage {
     pub carry_quote: u64,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ExcludedStream {
+    pub stream: Pubkey,
+    pub reason: u8,
+    pub locked_amount: u64,
+}
+
+#[event]
+pub struct InvestorsExcluded {
+    pub version: u8,
+    pub policy: Pubkey,
+    pub day_start_ts: i64,
+    pub page_start: u32,
+    pub excluded: Vec<ExcludedStream>,
+}
+
 #[event]
 pub struct CreatorPayoutDayClosed {
+    pub version: u8,
     pub policy: Pubkey,
     pub day_start_ts: i64,
     pub creator_quote_paid: u64,
     pub investor_quote_paid: u64,
     pub claimed_quote: u64,
     pub share_bps: u16,
+    pub reserve_quote: u64,
+}
+
+#[event]
+pub struct DayForceClosed {
+    pub version: u8,
+    pub policy: Pubkey,
+    pub closed_by: Pubkey,
+    pub day_start_ts: i64,
+    pub creator_quote_paid: u64,
+    pub investor_quote_paid: u64,
+    pub unpaid_investor_quote: u64,
+    pub carry_quote: u64,
+    pub released_carry: u64,
+}
+
+#[event]
+pub struct PolicyGroupInitialized {
+    pub version: u8,
+    pub group: Pubkey,
+    pub primary_policy: Pubkey,
+    pub quote_mint: Pubkey,
+}
+
+#[event]
+pub struct PolicyAddedToGroup {
+    pub version: u8,
+    pub group: Pubkey,
+    pub policy: Pubkey,
+    pub member_count: u8,
+}
+
+#[event]
+pub struct GroupMemberFeesForwarded {
+    pub version: u8,
+    pub group: Pubkey,
+    pub policy: Pubkey,
+    pub quote_forwarded: u64,
+    pub pending_quote: u64,
+}
+
+#[event]
+pub struct BlocklistInitialized {
+    pub version: u8,
+    pub policy: Pubkey,
+    pub blocklist: Pubkey,
+    pub forfeiture_ata: Pubkey,
+}
+
+#[event]
+pub struct BlocklistUpdated {
+    pub version: u8,
+    pub policy: Pubkey,
+    pub recipient: Pubkey,
+    pub blocked: bool,
+}
+
+#[event]
+pub struct PayoutForfeited {
+    pub version: u8,
+    pub policy: Pubkey,
+    pub day_start_ts: i64,
+    pub stream: Pubkey,
+    pub recipient: Pubkey,
+    pub forfeiture_ata: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PoolInactive {
+    pub version: u8,
+    pub policy: Pubkey,
+    pub pool: Pubkey,
+    pub reason: u8,
+    pub pool_status: u8,
+    pub activation_type: u8,
+    pub activation_point: u64,
+    pub current_point: u64,
+}
+
+#[event]
+pub struct CrankerAllowlistUpdated {
+    pub version: u8,
+    pub policy: Pubkey,
+    pub cranker: Pubkey,
+    pub allowed: bool,
+    pub permissionless: bool,
+}
+
+#[event]
+pub struct DistributionHookConfigured {
+    pub version: u8,
+    pub policy: Pubkey,
+    pub hook_program: Pubkey,
+    pub failure_mode: u8,
+}
+
+#[event]
+pub struct DistributionHookFailed {
+    pub version: u8,
+    pub policy: Pubkey,
+    pub hook_program: Pubkey,
+    pub day_start_ts: i64,
+}
+
+#[event]
+pub struct PolicyChangeQueued {
+    pub version: u8,
+    pub policy: Pubkey,
+    pub change: PolicyChange,
+    pub eta: i64,
+}
+
+#[event]
+pub struct PolicyChangeExecuted {
+    pub version: u8,
+    pub policy: Pubkey,
+    pub change: PolicyChange,
+    pub executed_by: Pubkey,
+}
+
+#[event]
+pub struct PolicyChangeCancelled {
+    pub version: u8,
+    pub policy: Pubkey,
+    pub change: PolicyChange,
+    pub eta: i64,
+}
+
+#[event]
+pub struct TokensRescued {
+    pub version: u8,
+    pub policy: Pubkey,
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct CreatorEscrowInitialized {
+    pub version: u8,
+    pub policy: Pubkey,
+    pub escrow: Pubkey,
+    pub vault: Pubkey,
+}
+
+#[event]
+pub struct CreatorQuoteEscrowed {
+    pub version: u8,
+    pub policy: Pubkey,
+    pub day_start_ts: i64,
+    pub amount: u64,
+    pub total_deposited: u64,
+}
+
+#[event]
+pub struct CreatorVestedWithdrawn {
+    pub version: u8,
+    pub policy: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub total_withdrawn: u64,
+    pub total_deposited: u64,
+}
+
+#[event]
+pub struct PolicyAccountsMigrated {
+    pub version: u8,
+    pub policy: Pubkey,
+    pub progress: Pubkey,
+    pub vesting_mint: Pubkey,
+    pub policy_len: u32,
+    pub progress_len: u32,
 }
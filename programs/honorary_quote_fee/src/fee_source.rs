@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Transfer};
+
+use crate::{
+    cp_amm::{claim_policy_fees, read_pool_activity, DammClaimAccounts},
+    errors::HonoraryQuoteFeeError,
+    events::{EventEmitter, PoolInactive, EVENT_VERSION},
+    state::HonoraryPosition,
+    token_account_amount,
+};
+
+pub struct FeeSourceKind;
+impl FeeSourceKind {
+    pub const DAMM_V2: u8 = 0u8;
+    pub const MANUAL_DEPOSIT: u8 = 1u8;
+    pub const POLICY_GROUP: u8 = 2u8;
+}
+
+/// Revenue that lands in a policy's quote treasury for distribution.
+pub trait FeeSource {
+    /// Tag reported in `QuoteFeesClaimed::source`.
+    fn kind(&self) -> u8;
+
+    /// Moves the source's quote into the treasury and returns the amount received.
+    fn collect(&self) -> Result<u64>;
+}
+
+/// LP (and optionally partner) fees claimed from DAMM v2 by the honorary PDA.
+pub struct DammV2FeeSource<'a, 'info> {
+    pub policy_key: Pubkey,
+    pub fee_capture_mode: u8,
+    pub honorary_position: &'a Account<'info, HonoraryPosition>,
+    pub accounts: DammClaimAccounts<'info>,
+    pub clock: &'a Clock,
+    pub events: &'a EventEmitter<'a, 'info>,
+}
+
+impl FeeSource for DammV2FeeSource<'_, '_> {
+    fn kind(&self) -> u8 {
+        FeeSourceKind::DAMM_V2
+    }
+
+    /// Claims nothing from a disabled or not-yet-activated pool, so carry distribution
+    /// and day close can proceed without an opaque DAMM error.
+    #[inline(never)]
+    fn collect(&self) -> Result<u64> {
+        let activity = read_pool_activity(&self.accounts.pool, self.clock)?;
+        if let Some(reason) = activity.inactive_reason() {
+            self.events.emit(&PoolInactive {
+                version: EVENT_VERSION,
+                policy: self.policy_key,
+                pool: self.accounts.pool.key(),
+                reason,
+                pool_status: activity.pool_status,
+                activation_type: activity.activation_type,
+                activation_point: activity.activation_point,
+                current_point: activity.current_point,
+            })?;
+            return Ok(0);
+        }
+
+        let quote_before = token_account_amount(&self.accounts.quote_treasury)?;
+        let base_before = token_account_amount(&self.accounts.base_fee_check)?;
+
+        claim_policy_fees(
+            self.policy_key,
+            self.fee_capture_mode,
+            self.honorary_position,
+            &self.accounts,
+        )?;
+
+        let quote_after = token_account_amount(&self.accounts.quote_treasury)?;
+        let base_after = token_account_amount(&self.accounts.base_fee_check)?;
+
+        require_eq!(
+            base_after,
+            base_before,
+            HonoraryQuoteFeeError::BaseFeeDetected
+        );
+        Ok(quote_after
+            .checked_sub(quote_before)
+            .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?)
+    }
+}
+
+/// Quote revenue transferred into the treasury by hand (e.g. protocol revenue).
+pub struct ManualDepositSource<'info> {
+    pub depositor: AccountInfo<'info>,
+    pub depositor_quote_account: AccountInfo<'info>,
+    pub quote_treasury: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub amount: u64,
+}
+
+impl FeeSource for ManualDepositSource<'_> {
+    fn kind(&self) -> u8 {
+        FeeSourceKind::MANUAL_DEPOSIT
+    }
+
+    #[inline(never)]
+    fn collect(&self) -> Result<u64> {
+        let quote_before = token_account_amount(&self.quote_treasury)?;
+
+        token::transfer(
+            CpiContext::new(
+                self.token_program.clone(),
+                Transfer {
+                    from: self.depositor_quote_account.clone(),
+                    to: self.quote_treasury.clone(),
+                    authority: self.depositor.clone(),
+                },
+            ),
+            self.amount,
+        )?;
+
+        let quote_after = token_account_amount(&self.quote_treasury)?;
+        Ok(quote_after
+            .checked_sub(quote_before)
+            .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?)
+    }
+}
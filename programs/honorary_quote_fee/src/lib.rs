@@ -1,39 +1,69 @@
 #![allow(unexpected_cfgs, deprecated)]
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::compute_units::sol_remaining_compute_units};
 use anchor_spl::{
     associated_token::AssociatedToken,
     token::{self, Mint, Token, TokenAccount, Transfer},
 };
-use carbon_meteora_damm_v2_decoder::{
-    accounts::pool::Pool as DammPoolAccount, types::position::Position as DammPosition,
+use carbon_meteora_damm_v2_decoder::accounts::{
+    pool::Pool as DammPoolAccount, position::Position as DammPosition,
 };
 
 mod cp_amm;
-mod errors;
-mod events;
-mod math;
-mod state;
+pub mod distribution_hook;
+pub mod errors;
+pub mod events;
+mod fee_source;
+pub mod math;
+pub mod state;
 mod streamflow_utils;
 
 #[cfg(test)]
 mod tests;
 
 use cp_amm::{assert_quote_only_pool, CollectFeeMode};
+use distribution_hook::{
+    invoke_distribution_hook, resolve_distribution_hook, DayClosedHookArgs, HookFailureMode,
+};
 use errors::HonoraryQuoteFeeError;
 use events::{
-    CreatorPayoutDayClosed, HonoraryPositionInitialized, InvestorPayoutPage, QuoteFeesClaimed,
+    BlocklistInitialized, BlocklistUpdated, CrankerAllowlistUpdated, CreatorEscrowInitialized,
+    CreatorPayoutDayClosed, CreatorQuoteEscrowed, CreatorVestedWithdrawn, DayForceClosed,
+    DayHistoryInitialized, DayOpened, DistributionHookConfigured, DistributionHookFailed,
+    EventEmitter, ExcludedStream, GroupMemberFeesForwarded, HonoraryPositionInitialized,
+    InvestorPayoutPage, InvestorsExcluded, PageRejected, PageRejectionReason, PayoutForfeited,
+    PolicyAccountsMigrated, PolicyAddedToGroup, PolicyChangeCancelled, PolicyChangeExecuted,
+    PolicyChangeQueued, PolicyGroupInitialized, PolicyInitialized, QuoteDeposited,
+    QuoteFeesClaimed, TokensRescued, EVENT_VERSION,
 };
-use math::{mul_div_floor_u128, saturating_sub_u64, u128_to_u64};
+pub use fee_source::FeeSourceKind;
+use fee_source::{DammV2FeeSource, FeeSource, ManualDepositSource};
+pub use honorary_payout::CapReserve;
+use honorary_payout::{build_payout_plan, DayState, PayoutTerms};
+use math::{saturating_sub_u64, split_evenly, whole_tokens_to_base_units};
 use state::{
-    DistributionProgress, HonoraryPosition, Policy, HONORARY_POSITION_SEED, POLICY_SEED,
-    PROGRESS_SEED,
+    Blocklist, CrankerAllowlist, CreatorEscrow, DayHistory, DaySummary, DistributionProgress,
+    EligibilityCurve, EligibilityRules, FeeCaptureMode, ForceCloseDestination, HonoraryPosition,
+    IneligibilityReason, PendingPolicyChange, Policy, PolicyChange, PolicyGroup, BLOCKLIST_SEED,
+    CRANKER_ALLOWLIST_SEED, CREATOR_ESCROW_SEED, DAY_HISTORY_SEED, HONORARY_POSITION_SEED,
+    MAX_GROUP_MEMBERS, POLICY_CHANGE_SEED, POLICY_GROUP_SEED, POLICY_SEED, PROGRESS_SEED,
+};
+pub use streamflow_utils::{
+    collect_investors, eligible_share_bps, investor_page_digest, investor_snapshot_commitment,
+    investor_snapshot_leaf, investor_snapshot_link, InvestorEntry,
 };
-pub use streamflow_utils::{collect_investors, eligible_share_bps, InvestorEntry};
 
 declare_id!("7YupTAYp9uHuv5UJdGGVfX1dr1WNd71ezW43r3UxbxMk");
 
 pub const DAY_SECONDS: i64 = 86_400;
+/// Compute units a crank page keeps back for the fee claim, day close and hook.
+pub const CRANK_COMPUTE_RESERVE: u64 = 120_000;
+/// Compute units budgeted to pay one collected investor.
+pub const INVESTOR_PAYOUT_COMPUTE_UNITS: u64 = 12_000;
 pub const MAX_BASIS_POINTS: u16 = 10_000;
+/// Upper bound on the number of missed days folded into a single catch-up day.
+pub const MAX_CATCH_UP_DAYS: u32 = 30;
+/// Sentinel stored in `Policy::last_day_close_ts` before the first day closes.
+pub const NEVER_CLOSED_TS: i64 = -4_611_686_018_427_387_904;
 
 #[program]
 pub mod honorary_quote_fee {
@@ -48,6 +78,32 @@ pub mod honorary_quote_fee {
             HonoraryQuoteFeeError::InvalidInvestorShare
         );
         require!(params.y0 > 0, HonoraryQuoteFeeError::InvalidY0);
+        require!(
+            params.force_close_grace_seconds >= 0,
+            HonoraryQuoteFeeError::InvalidForceCloseGrace
+        );
+        require!(
+            params.timelock_seconds >= 0,
+            HonoraryQuoteFeeError::InvalidTimelock
+        );
+        require!(
+            params.min_page_interval_seconds >= 0,
+            HonoraryQuoteFeeError::InvalidPageInterval
+        );
+        require!(
+            params.force_close_destination <= ForceCloseDestination::CREATOR,
+            HonoraryQuoteFeeError::InvalidForceCloseDestination
+        );
+        require!(
+            params.fee_capture_mode != 0 && params.fee_capture_mode & !FeeCaptureMode::ALL == 0,
+            HonoraryQuoteFeeError::InvalidFeeCaptureMode
+        );
+        params.eligibility_curve.validate()?;
+        require!(
+            params.reserve_retention_bps <= MAX_BASIS_POINTS
+                && (params.cap_reserve_enabled || params.reserve_retention_bps == 0),
+            HonoraryQuoteFeeError::InvalidReserveRetention
+        );
 
         let policy = &mut ctx.accounts.policy;
         let (pool_partner, pool_token_a_mint, pool_token_a_vault, pool_token_b_vault) = {
@@ -69,11 +125,19 @@ pub mod honorary_quote_fee {
             )
         };
 
-        require_keys_eq!(
-            pool_partner,
-            Pubkey::default(),
-            HonoraryQuoteFeeError::UnsupportedPartnerPool
-        );
+        if params.fee_capture_mode & FeeCaptureMode::PARTNER_FEES != 0 {
+            require_keys_eq!(
+                Pubkey::new_from_array(pool_partner.to_bytes()),
+                honorary_position_address(&policy.key()),
+                HonoraryQuoteFeeError::PartnerMismatch
+            );
+        } else {
+            require_keys_eq!(
+                pool_partner,
+                Pubkey::default(),
+                HonoraryQuoteFeeError::UnsupportedPartnerPool
+            );
+        }
         require_keys_eq!(
             ctx.accounts.creator_quote_ata.mint,
             ctx.accounts.quote_mint.key(),
@@ -105,22 +169,59 @@ pub mod honorary_quote_fee {
         policy.cp_amm_program = ctx.accounts.damm_program.key();
         policy.quote_mint = ctx.accounts.quote_mint.key();
         policy.base_mint = ctx.accounts.base_mint.key();
+        policy.vesting_mint = ctx.accounts.vesting_mint.key();
+        policy.quote_decimals = ctx.accounts.quote_mint.decimals;
+        policy.vesting_decimals = ctx.accounts.vesting_mint.decimals;
         policy.quote_vault = ctx.accounts.quote_vault.key();
         policy.base_vault = ctx.accounts.base_vault.key();
         policy.position = Pubkey::default();
+        policy.day_history = Pubkey::default();
+        policy.group = Pubkey::default();
+        policy.blocklist = Pubkey::default();
+        policy.cranker_allowlist = Pubkey::default();
+        policy.distribution_hook = Pubkey::default();
         policy.position_nft_mint = Pubkey::default();
         policy.position_nft_account = Pubkey::default();
         policy.quote_treasury = Pubkey::default();
         policy.base_fee_check = Pubkey::default();
         policy.creator_quote_ata = ctx.accounts.creator_quote_ata.key();
-        policy.y0 = params.y0;
+        let mut eligibility_rules = params.eligibility_rules;
+        let (y0, daily_cap_quote, min_payout_lamports) = if params.whole_token_amounts {
+            let quote_decimals = policy.quote_decimals;
+            let vesting_decimals = policy.vesting_decimals;
+            eligibility_rules.min_locked_amount =
+                whole_tokens_to_base_units(eligibility_rules.min_locked_amount, vesting_decimals)?;
+            (
+                whole_tokens_to_base_units(params.y0, vesting_decimals)?,
+                whole_tokens_to_base_units(params.daily_cap_quote, quote_decimals)?,
+                whole_tokens_to_base_units(params.min_payout_lamports, quote_decimals)?,
+            )
+        } else {
+            (
+                params.y0,
+                params.daily_cap_quote,
+                params.min_payout_lamports,
+            )
+        };
+        policy.policy_index = params.policy_index;
+        policy.y0 = y0;
         policy.investor_fee_share_bps = params.investor_fee_share_bps;
-        policy.daily_cap_quote = params.daily_cap_quote;
-        policy.min_payout_lamports = params.min_payout_lamports;
+        policy.eligibility_curve = params.eligibility_curve;
+        policy.eligibility_rules = eligibility_rules;
+        policy.cap_reserve_enabled = params.cap_reserve_enabled;
+        policy.investor_snapshot_required = params.investor_snapshot_required;
+        policy.reserve_retention_bps = params.reserve_retention_bps;
+        policy.daily_cap_quote = daily_cap_quote;
+        policy.min_payout_lamports = min_payout_lamports;
+        policy.force_close_grace_seconds = params.force_close_grace_seconds;
+        policy.min_page_interval_seconds = params.min_page_interval_seconds;
+        policy.timelock_seconds = params.timelock_seconds;
+        policy.force_close_destination = params.force_close_destination;
+        policy.fee_capture_mode = params.fee_capture_mode;
         policy.bump = ctx.bumps.policy;
         // Intentionally initialize to a large negative sentinel value without triggering
         // arithmetic lints at runtime by using a literal constant.
-        policy.last_day_close_ts = -4_611_686_018_427_387_904;
+        policy.last_day_close_ts = NEVER_CLOSED_TS;
         policy.status = 0u8;
 
         let progress = &mut ctx.accounts.progress;
@@ -130,8 +231,28 @@ pub mod honorary_quote_fee {
         progress.claimed_quote = 0;
         progress.investor_distributed = 0;
         progress.carry_quote = 0;
+        progress.target_investor_quote = 0;
+        progress.pending_deposit_quote = 0;
+        progress.day_count = 0;
+        progress.share_bps = 0;
         progress.day_open = false;
 
+        emit_cpi!(PolicyInitialized {
+            version: EVENT_VERSION,
+            policy: policy.key(),
+            authority: policy.authority,
+            pool: policy.pool,
+            quote_mint: policy.quote_mint,
+            vesting_mint: policy.vesting_mint,
+            policy_index: policy.policy_index,
+            investor_fee_share_bps: policy.investor_fee_share_bps,
+            y0: policy.y0,
+            daily_cap_quote: policy.daily_cap_quote,
+            min_payout_lamports: policy.min_payout_lamports,
+            quote_decimals: policy.quote_decimals,
+            vesting_decimals: policy.vesting_decimals,
+        });
+
         Ok(())
     }
 
@@ -237,7 +358,75 @@ pub mod honorary_quote_fee {
         policy.base_fee_check = ctx.accounts.base_fee_check.key();
         policy.status |= state::PolicyStatus::HONORARY_READY;
 
-        emit!(HonoraryPositionInitialized {
+        emit_cpi!(HonoraryPositionInitialized {
+            version: EVENT_VERSION,
+            policy: policy.key(),
+            position: policy.position,
+            quote_treasury: policy.quote_treasury,
+        });
+
+        Ok(())
+    }
+
+    pub fn create_honorary_position(ctx: Context<CreateHonoraryPosition>) -> Result<()> {
+        let policy = &mut ctx.accounts.policy;
+        require_keys_eq!(
+            policy.authority,
+            ctx.accounts.authority.key(),
+            HonoraryQuoteFeeError::Unauthorized
+        );
+        require_eq!(
+            policy.position,
+            Pubkey::default(),
+            HonoraryQuoteFeeError::HonoraryPositionAlreadyConfigured
+        );
+
+        cp_amm::invoke_create_position(
+            &ctx.accounts.cp_amm_program.to_account_info(),
+            &ctx.accounts.honorary_position.to_account_info(),
+            &ctx.accounts.position_nft_mint.to_account_info(),
+            &ctx.accounts.position_nft_account.to_account_info(),
+            &ctx.accounts.damm_pool.to_account_info(),
+            &ctx.accounts.position.to_account_info(),
+            &ctx.accounts.pool_authority.to_account_info(),
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.position_token_program.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.damm_event_authority.to_account_info(),
+        )?;
+
+        let (position_pool, position_nft_mint) = {
+            let position_data = ctx.accounts.position.try_borrow_data()?;
+            let position = DammPosition::deserialize(&position_data)
+                .ok_or_else(|| error!(HonoraryQuoteFeeError::InvalidPositionAccount))?;
+
+            (position.pool, position.nft_mint)
+        };
+
+        require_keys_eq!(
+            position_pool,
+            policy.pool,
+            HonoraryQuoteFeeError::PositionPoolMismatch
+        );
+        require_keys_eq!(
+            position_nft_mint,
+            ctx.accounts.position_nft_mint.key(),
+            HonoraryQuoteFeeError::InvalidPositionNft
+        );
+
+        let honorary_position = &mut ctx.accounts.honorary_position;
+        honorary_position.policy = policy.key();
+        honorary_position.bump = ctx.bumps.honorary_position;
+
+        policy.position = ctx.accounts.position.key();
+        policy.position_nft_mint = ctx.accounts.position_nft_mint.key();
+        policy.position_nft_account = ctx.accounts.position_nft_account.key();
+        policy.quote_treasury = ctx.accounts.quote_treasury.key();
+        policy.base_fee_check = ctx.accounts.base_fee_check.key();
+        policy.status |= state::PolicyStatus::HONORARY_READY;
+
+        emit_cpi!(HonoraryPositionInitialized {
+            version: EVENT_VERSION,
             policy: policy.key(),
             position: policy.position,
             quote_treasury: policy.quote_treasury,
@@ -246,10 +435,158 @@ pub mod honorary_quote_fee {
         Ok(())
     }
 
+    pub fn initialize_day_history(ctx: Context<InitializeDayHistory>) -> Result<()> {
+        let policy = &mut ctx.accounts.policy;
+        require_keys_eq!(
+            policy.authority,
+            ctx.accounts.authority.key(),
+            HonoraryQuoteFeeError::Unauthorized
+        );
+
+        let history = &mut ctx.accounts.day_history;
+        history.policy = policy.key();
+        history.bump = ctx.bumps.day_history;
+
+        policy.day_history = history.key();
+
+        emit_cpi!(DayHistoryInitialized {
+            version: EVENT_VERSION,
+            policy: policy.key(),
+            day_history: history.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn initialize_blocklist(ctx: Context<InitializeBlocklist>) -> Result<()> {
+        let policy = &mut ctx.accounts.policy;
+        let blocklist = &mut ctx.accounts.blocklist;
+        blocklist.policy = policy.key();
+        blocklist.forfeiture_ata = ctx.accounts.forfeiture_ata.key();
+        blocklist.bump = ctx.bumps.blocklist;
+
+        policy.blocklist = blocklist.key();
+
+        emit_cpi!(BlocklistInitialized {
+            version: EVENT_VERSION,
+            policy: policy.key(),
+            blocklist: blocklist.key(),
+            forfeiture_ata: blocklist.forfeiture_ata,
+        });
+
+        Ok(())
+    }
+
+    pub fn update_blocklist(
+        ctx: Context<UpdateBlocklist>,
+        recipient: Pubkey,
+        blocked: bool,
+    ) -> Result<()> {
+        let blocklist = &mut ctx.accounts.blocklist;
+        if blocked {
+            blocklist.insert(recipient)?;
+        } else {
+            blocklist.remove(&recipient)?;
+        }
+
+        emit_cpi!(BlocklistUpdated {
+            version: EVENT_VERSION,
+            policy: ctx.accounts.policy.key(),
+            recipient,
+            blocked,
+        });
+
+        Ok(())
+    }
+
+    pub fn initialize_cranker_allowlist(
+        ctx: Context<InitializeCrankerAllowlist>,
+        permissionless: bool,
+    ) -> Result<()> {
+        let policy = &mut ctx.accounts.policy;
+        let allowlist = &mut ctx.accounts.cranker_allowlist;
+        allowlist.policy = policy.key();
+        allowlist.permissionless = permissionless;
+        allowlist.bump = ctx.bumps.cranker_allowlist;
+
+        policy.cranker_allowlist = allowlist.key();
+
+        emit_cpi!(CrankerAllowlistUpdated {
+            version: EVENT_VERSION,
+            policy: policy.key(),
+            cranker: Pubkey::default(),
+            allowed: permissionless,
+            permissionless,
+        });
+
+        Ok(())
+    }
+
+    pub fn update_cranker_allowlist(
+        ctx: Context<UpdateCrankerAllowlist>,
+        cranker: Pubkey,
+        allowed: bool,
+    ) -> Result<()> {
+        let allowlist = &mut ctx.accounts.cranker_allowlist;
+        allowlist.set_allowed(cranker, allowed)?;
+
+        emit_cpi!(CrankerAllowlistUpdated {
+            version: EVENT_VERSION,
+            policy: ctx.accounts.policy.key(),
+            cranker,
+            allowed,
+            permissionless: allowlist.permissionless,
+        });
+
+        Ok(())
+    }
+
+    pub fn configure_distribution_hook(
+        ctx: Context<ConfigureDistributionHook>,
+        hook_program: Pubkey,
+        failure_mode: u8,
+    ) -> Result<()> {
+        require!(
+            failure_mode <= HookFailureMode::IGNORE && hook_program != crate::ID,
+            HonoraryQuoteFeeError::InvalidDistributionHook
+        );
+
+        let policy = &mut ctx.accounts.policy;
+        policy.distribution_hook = hook_program;
+        policy.hook_failure_mode = failure_mode;
+
+        emit_cpi!(DistributionHookConfigured {
+            version: EVENT_VERSION,
+            policy: policy.key(),
+            hook_program,
+            failure_mode,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_permissionless_crank(
+        ctx: Context<UpdateCrankerAllowlist>,
+        permissionless: bool,
+    ) -> Result<()> {
+        let allowlist = &mut ctx.accounts.cranker_allowlist;
+        allowlist.permissionless = permissionless;
+
+        emit_cpi!(CrankerAllowlistUpdated {
+            version: EVENT_VERSION,
+            policy: ctx.accounts.policy.key(),
+            cranker: Pubkey::default(),
+            allowed: permissionless,
+            permissionless,
+        });
+
+        Ok(())
+    }
+
     pub fn crank_quote_fee_distribution(
         ctx: Context<CrankQuoteFeeDistribution>,
         params: CrankQuoteFeeParams,
-    ) -> Result<()> {
+    ) -> Result<CrankPageResult> {
         // Work around lifetime issues by transmuting the context
         let ctx: Context<'_, '_, '_, '_, CrankQuoteFeeDistribution<'_>> =
             unsafe { std::mem::transmute(ctx) };
@@ -270,69 +607,222 @@ pub mod honorary_quote_fee {
             HonoraryQuoteFeeError::DayNotOpen
         );
 
-        if !progress.day_open {
+        check_cranker_allowed(
+            policy,
+            ctx.accounts.cranker_allowlist.as_deref(),
+            &ctx.accounts.cranker.key(),
+        )?;
+
+        let opening_day = !progress.day_open;
+        let committed_snapshot = if opening_day {
+            params.investor_snapshot
+        } else {
+            progress.investor_snapshot
+        };
+        if policy.investor_snapshot_required {
+            require!(
+                committed_snapshot != [0u8; 32],
+                HonoraryQuoteFeeError::InvestorSnapshotMissing
+            );
+        }
+        // Snapshot policies weigh every page of the day by its locked amounts at day open.
+        let locked_at_ts = if policy.investor_snapshot_required && !opening_day {
+            progress.day_start_ts
+        } else {
+            now_ts
+        };
+
+        let hook_account_start = ctx
+            .remaining_accounts
+            .len()
+            .checked_sub(params.hook_account_count as usize)
+            .ok_or(HonoraryQuoteFeeError::InvalidInvestorAccount)?;
+        let (investor_accounts, hook_accounts) =
+            ctx.remaining_accounts.split_at(hook_account_start);
+        let supplied_count = investor_accounts.len() / 2;
+
+        // The page takes as many supplied investors as `max_investors`, `max_page_cursor`
+        // and the compute budget allow; the rest are left for the next page. Snapshot
+        // pages are committed whole, so they are never cut short for compute and a limit
+        // that would split one is an error.
+        let current_page_cursor = if opening_day { 0 } else { progress.page_cursor };
+        let max_cursor = if params.max_page_cursor == 0 {
+            u32::MAX
+        } else {
+            params.max_page_cursor
+        };
+        let mut page_limit = max_cursor.saturating_sub(current_page_cursor) as usize;
+        if params.max_investors > 0 {
+            page_limit = page_limit.min(params.max_investors as usize);
+        }
+        require!(
+            !policy.investor_snapshot_required || page_limit == 0 || page_limit >= supplied_count,
+            HonoraryQuoteFeeError::SnapshotPageTruncated
+        );
+        let investors = collect_investors(
+            locked_at_ts as u64,
+            investor_accounts,
+            policy.vesting_mint,
+            policy.quote_mint,
+            policy.pool,
+            &policy.eligibility_rules,
+            page_limit,
+            |count| policy.investor_snapshot_required || page_has_compute_for(count),
+        )?;
+        let closes_day = params.is_last_page && investors.len() == supplied_count;
+
+        // Stale, premature or tampered pages fail. The rejection is emitted first so it
+        // is in the failed transaction's logs and inner instructions; nothing is committed.
+        let rejection = if check_page_spacing(
+            opening_day,
+            progress.last_page_ts,
+            policy.min_page_interval_seconds,
+            now_ts,
+        )
+        .is_err()
+        {
+            Some((
+                PageRejectionReason::RATE_LIMITED,
+                HonoraryQuoteFeeError::PageRateLimited,
+            ))
+        } else if params.expected_page_cursor != current_page_cursor {
+            Some((
+                PageRejectionReason::STALE_CURSOR,
+                HonoraryQuoteFeeError::UnexpectedPageCursor,
+            ))
+        } else if policy.investor_snapshot_required
+            && !snapshot_page_matches(
+                &committed_snapshot,
+                &investors,
+                &params.remaining_snapshot,
+                closes_day,
+            )
+        {
+            Some((
+                PageRejectionReason::SNAPSHOT_MISMATCH,
+                HonoraryQuoteFeeError::InvestorSnapshotMismatch,
+            ))
+        } else {
+            None
+        };
+        if let Some((reason, error)) = rejection {
+            msg!("Crank page rejected: reason {}", reason);
+            emit_cpi!(PageRejected {
+                version: EVENT_VERSION,
+                policy: policy.key(),
+                cranker: ctx.accounts.cranker.key(),
+                reason,
+                expected_page_cursor: params.expected_page_cursor,
+                page_cursor: current_page_cursor,
+                last_page_ts: progress.last_page_ts,
+            });
+            return Err(error.into());
+        }
+        progress.last_page_ts = now_ts;
+        if opening_day {
             require!(
                 now_ts >= policy.last_day_close_ts + DAY_SECONDS,
                 HonoraryQuoteFeeError::DayNotReady
             );
             progress.day_open = true;
             progress.day_start_ts = now_ts;
+            progress.day_count = elapsed_day_count(policy.last_day_close_ts, now_ts);
             progress.page_cursor = 0;
             progress.claimed_quote = 0;
             progress.investor_distributed = 0;
-        }
-
-        require!(
-            params.expected_page_cursor == progress.page_cursor,
-            HonoraryQuoteFeeError::UnexpectedPageCursor
-        );
+            progress.target_investor_quote = 0;
+            progress.share_bps = 0;
 
-        let quote_before = token_account_amount(&ctx.accounts.quote_treasury)?;
-        let base_before = token_account_amount(&ctx.accounts.base_fee_check)?;
-
-        cp_amm::invoke_claim_position_fee(
-            policy.key(),
-            &ctx.accounts.honorary_position,
-            &ctx.accounts.cp_amm_program.to_account_info(),
-            &ctx.accounts.pool.to_account_info(),
-            &ctx.accounts.pool_authority.to_account_info(),
-            &ctx.accounts.position.to_account_info(),
-            &ctx.accounts.base_fee_check.to_account_info(),
-            &ctx.accounts.quote_treasury.to_account_info(),
-            &ctx.accounts.base_vault.to_account_info(),
-            &ctx.accounts.quote_vault.to_account_info(),
-            &ctx.accounts.base_mint.to_account_info(),
-            &ctx.accounts.quote_mint.to_account_info(),
-            &ctx.accounts.position_nft_account.to_account_info(),
-            &ctx.accounts.honorary_position.to_account_info(),
-            &ctx.accounts.token_program_a.to_account_info(),
-            &ctx.accounts.token_program_b.to_account_info(),
-            &ctx.accounts.event_authority.to_account_info(),
-        )?;
+            emit_cpi!(DayOpened {
+                version: EVENT_VERSION,
+                policy: policy.key(),
+                day_start_ts: progress.day_start_ts,
+                day_count: progress.day_count,
+                carry_quote: progress.carry_quote,
+                reserve_quote: progress.reserve_quote,
+            });
+        }
 
-        let quote_after = token_account_amount(&ctx.accounts.quote_treasury)?;
-        let base_after = token_account_amount(&ctx.accounts.base_fee_check)?;
+        let events = EventEmitter::new(&ctx.accounts.event_authority, ctx.bumps.event_authority);
+        let damm_source = DammV2FeeSource {
+            policy_key: policy.key(),
+            fee_capture_mode: policy.fee_capture_mode,
+            honorary_position: &ctx.accounts.honorary_position,
+            accounts: cp_amm::DammClaimAccounts {
+                cp_amm_program: ctx.accounts.cp_amm_program.to_account_info(),
+                pool: ctx.accounts.pool.to_account_info(),
+                pool_authority: ctx.accounts.pool_authority.to_account_info(),
+                position: ctx.accounts.position.to_account_info(),
+                position_nft_account: ctx.accounts.position_nft_account.to_account_info(),
+                base_fee_check: ctx.accounts.base_fee_check.to_account_info(),
+                quote_treasury: ctx.accounts.quote_treasury.to_account_info(),
+                base_vault: ctx.accounts.base_vault.to_account_info(),
+                quote_vault: ctx.accounts.quote_vault.to_account_info(),
+                base_mint: ctx.accounts.base_mint.to_account_info(),
+                quote_mint: ctx.accounts.quote_mint.to_account_info(),
+                token_program_a: ctx.accounts.token_program_a.to_account_info(),
+                token_program_b: ctx.accounts.token_program_b.to_account_info(),
+                event_authority: ctx.accounts.damm_event_authority.to_account_info(),
+            },
+            clock: &clock,
+            events: &events,
+        };
+        let damm_claimed = damm_source.collect()?;
+        // Manual deposits made since the previous page count exactly like claimed fees.
+        let deposited = core::mem::take(&mut progress.pending_deposit_quote);
 
-        let quote_claimed = quote_after
-            .checked_sub(quote_before)
+        let group_forwarded = if policy.group == Pubkey::default() {
+            0
+        } else {
+            let group = ctx
+                .accounts
+                .policy_group
+                .as_mut()
+                .ok_or(HonoraryQuoteFeeError::PolicyGroupMissing)?;
+            require_keys_eq!(
+                group.key(),
+                policy.group,
+                HonoraryQuoteFeeError::NotGroupMember
+            );
+            take_group_pending_quote(group, policy.key(), opening_day)?
+        };
+        let claimed_by_source = [
+            (damm_source.kind(), damm_claimed),
+            (FeeSourceKind::MANUAL_DEPOSIT, deposited),
+            (FeeSourceKind::POLICY_GROUP, group_forwarded),
+        ];
+        let quote_claimed = damm_claimed
+            .checked_add(deposited)
+            .and_then(|total| total.checked_add(group_forwarded))
             .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?;
-        require_eq!(
-            base_after,
-            base_before,
-            HonoraryQuoteFeeError::BaseFeeDetected
-        );
 
         progress.claimed_quote = progress
             .claimed_quote
             .checked_add(quote_claimed)
             .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?;
 
-        let investors = collect_investors(
-            now_ts as u64,
-            ctx.remaining_accounts,
-            policy.quote_mint,
-            policy.pool,
+        let excluded: Vec<ExcludedStream> = investors
+            .iter()
+            .filter(|entry| entry.ineligible_reason != IneligibilityReason::NONE)
+            .map(|entry| ExcludedStream {
+                stream: entry.stream,
+                reason: entry.ineligible_reason,
+                locked_amount: entry.raw_locked_amount,
+            })
+            .collect();
+        let blocklist = resolve_blocklist(
+            policy,
+            ctx.accounts.blocklist.as_deref(),
+            ctx.accounts.forfeiture_ata.as_deref(),
         )?;
+        let forfeitures = blocklist
+            .map(|list| forfeited_investors(&investors, list))
+            .unwrap_or_default();
+
+        // A catch-up day accumulates fees for every missed day, so the cap scales with it.
+        let daily_cap_quote = policy
+            .daily_cap_quote
+            .saturating_mul(u64::from(progress.day_count.max(1)));
 
         let plan = build_investor_payout_plan(
             investors,
@@ -341,8 +831,10 @@ pub mod honorary_quote_fee {
             progress.carry_quote,
             policy.y0,
             policy.investor_fee_share_bps,
-            policy.daily_cap_quote,
+            &policy.eligibility_curve,
+            daily_cap_quote,
             policy.min_payout_lamports,
+            cap_reserve(policy, &progress),
         )?;
         let InvestorPayoutPlan {
             transfers,
@@ -352,23 +844,24 @@ pub mod honorary_quote_fee {
             target_investor_quote,
             carry_for_creator,
             carry_quote_after,
+            reserve_quote_after,
         } = plan;
 
         require!(
-            investor_count > 0 || params.is_last_page,
-            HonoraryQuoteFeeError::EmptyPageWithoutLastFlag
+            investor_count > 0 || supplied_count == 0,
+            HonoraryQuoteFeeError::PageOverflow
         );
-        let max_cursor = if params.max_page_cursor == 0 {
-            u32::MAX
-        } else {
-            params.max_page_cursor
-        };
         require!(
-            investor_count + progress.page_cursor <= max_cursor,
-            HonoraryQuoteFeeError::PageOverflow
+            investor_count > 0 || params.is_last_page,
+            HonoraryQuoteFeeError::EmptyPageWithoutLastFlag
         );
 
         progress.carry_quote = carry_quote_after;
+        progress.target_investor_quote = target_investor_quote;
+        progress.share_bps = share_bps;
+        if policy.investor_snapshot_required {
+            progress.investor_snapshot = params.remaining_snapshot;
+        }
         progress.investor_distributed = progress
             .investor_distributed
             .checked_add(total_paid)
@@ -385,18 +878,36 @@ pub mod honorary_quote_fee {
             if *amount == 0 {
                 continue;
             }
+            let forfeiture = forfeitures
+                .iter()
+                .find(|(index, _, _)| index == token_account_index);
+            let to = match (forfeiture, ctx.accounts.forfeiture_ata.as_ref()) {
+                (Some(_), Some(forfeiture_ata)) => forfeiture_ata.to_account_info(),
+                _ => ctx.remaining_accounts[*token_account_index].clone(),
+            };
             token::transfer(
                 CpiContext::new_with_signer(
                     ctx.accounts.token_program.to_account_info(),
                     Transfer {
                         from: ctx.accounts.quote_treasury.to_account_info(),
-                        to: ctx.remaining_accounts[*token_account_index].clone(),
+                        to: to.clone(),
                         authority: ctx.accounts.honorary_position.to_account_info(),
                     },
                     signer,
                 ),
                 *amount,
             )?;
+            if let Some((_, stream, recipient)) = forfeiture {
+                emit_cpi!(PayoutForfeited {
+                    version: EVENT_VERSION,
+                    policy: policy_key,
+                    day_start_ts: progress.day_start_ts,
+                    stream: *stream,
+                    recipient: *recipient,
+                    forfeiture_ata: to.key(),
+                    amount: *amount,
+                });
+            }
         }
 
         progress.page_cursor = progress
@@ -404,15 +915,23 @@ pub mod honorary_quote_fee {
             .checked_add(investor_count)
             .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?;
 
-        emit!(QuoteFeesClaimed {
-            policy: policy.key(),
-            day_start_ts: progress.day_start_ts,
-            quote_fees_claimed: quote_claimed,
-            cumulative_claimed: progress.claimed_quote,
-            eligible_share_bps: share_bps,
-        });
+        for (source, amount) in claimed_by_source {
+            if amount == 0 && source != FeeSourceKind::DAMM_V2 {
+                continue;
+            }
+            emit_cpi!(QuoteFeesClaimed {
+                version: EVENT_VERSION,
+                policy: policy.key(),
+                day_start_ts: progress.day_start_ts,
+                quote_fees_claimed: amount,
+                cumulative_claimed: progress.claimed_quote,
+                eligible_share_bps: share_bps,
+                source,
+            });
+        }
 
-        emit!(InvestorPayoutPage {
+        emit_cpi!(InvestorPayoutPage {
+            version: EVENT_VERSION,
             policy: policy.key(),
             day_start_ts: progress.day_start_ts,
             page_start: params.expected_page_cursor,
@@ -420,12 +939,24 @@ pub mod honorary_quote_fee {
             total_paid_quote: total_paid,
             carry_quote: progress.carry_quote,
         });
+        if !excluded.is_empty() {
+            emit_cpi!(InvestorsExcluded {
+                version: EVENT_VERSION,
+                policy: policy.key(),
+                day_start_ts: progress.day_start_ts,
+                page_start: params.expected_page_cursor,
+                excluded,
+            });
+        }
 
-        if params.is_last_page {
-            let mut creator_transfer = saturating_sub_u64(
-                saturating_sub_u64(progress.claimed_quote, target_investor_quote),
-                0,
-            );
+        if closes_day {
+            let mut creator_transfer = creator_quote_at_close(
+                progress.claimed_quote,
+                target_investor_quote,
+                progress.reserve_quote,
+                reserve_quote_after,
+            )?;
+            progress.reserve_quote = reserve_quote_after;
             if share_bps == 0 {
                 creator_transfer = creator_transfer
                     .checked_add(carry_for_creator)
@@ -433,42 +964,726 @@ pub mod honorary_quote_fee {
                 progress.carry_quote = 0;
             }
 
+            let (creator_destination, escrowed_total) = creator_payout_destination(
+                policy,
+                &ctx.accounts.creator_quote_ata,
+                ctx.accounts.creator_escrow.as_deref_mut(),
+                ctx.accounts.creator_escrow_vault.as_deref(),
+                creator_transfer,
+                now_ts,
+            )?;
             if creator_transfer > 0 {
                 token::transfer(
                     CpiContext::new_with_signer(
                         ctx.accounts.token_program.to_account_info(),
                         Transfer {
                             from: ctx.accounts.quote_treasury.to_account_info(),
-                            to: ctx.accounts.creator_quote_ata.to_account_info(),
+                            to: creator_destination.clone(),
                             authority: ctx.accounts.honorary_position.to_account_info(),
                         },
                         signer,
                     ),
                     creator_transfer,
                 )?;
+                if let Some(total_deposited) = escrowed_total {
+                    emit_cpi!(CreatorQuoteEscrowed {
+                        version: EVENT_VERSION,
+                        policy: policy_key,
+                        day_start_ts: progress.day_start_ts,
+                        amount: creator_transfer,
+                        total_deposited,
+                    });
+                }
             }
 
-            emit!(CreatorPayoutDayClosed {
-                policy: policy.key(),
-                day_start_ts: progress.day_start_ts,
-                creator_quote_paid: creator_transfer,
-                investor_quote_paid: progress.investor_distributed,
-                claimed_quote: progress.claimed_quote,
-                share_bps: share_bps,
-            });
+            record_day_close(
+                &events,
+                policy,
+                policy.key(),
+                ctx.accounts.day_history.as_ref(),
+                &progress,
+                creator_transfer,
+                share_bps,
+            )?;
+
+            if policy.distribution_hook != Pubkey::default() {
+                let args = DayClosedHookArgs {
+                    policy: policy_key,
+                    day_start_ts: progress.day_start_ts,
+                    day_count: progress.day_count.max(1),
+                    claimed_quote: progress.claimed_quote,
+                    investor_quote_paid: progress.investor_distributed,
+                    creator_quote_paid: creator_transfer,
+                    share_bps,
+                };
+                let hook = resolve_distribution_hook(
+                    policy.distribution_hook,
+                    ctx.accounts
+                        .distribution_hook_program
+                        .as_ref()
+                        .map(|program| program.as_ref()),
+                    &policy_key,
+                    ctx.accounts
+                        .distribution_hook_authority
+                        .as_ref()
+                        .map(|authority| authority.as_ref()),
+                );
+                match hook {
+                    Ok(hook) => invoke_distribution_hook(
+                        &hook,
+                        &policy.to_account_info(),
+                        hook_accounts,
+                        &args,
+                    )?,
+                    // Only the account checks can be skipped: a hook failing inside the CPI
+                    // aborts the whole transaction before control returns here.
+                    Err(error) if policy.hook_failure_mode == HookFailureMode::IGNORE => {
+                        msg!("Distribution hook skipped: {}", error);
+                        emit_cpi!(DistributionHookFailed {
+                            version: EVENT_VERSION,
+                            policy: policy_key,
+                            hook_program: policy.distribution_hook,
+                            day_start_ts: progress.day_start_ts,
+                        });
+                    }
+                    Err(error) => return Err(error),
+                }
+            }
 
             policy.last_day_close_ts = progress.day_start_ts;
             progress.day_open = false;
             progress.claimed_quote = 0;
             progress.investor_distributed = 0;
             progress.page_cursor = 0;
+            progress.day_count = 0;
+            progress.target_investor_quote = 0;
+            progress.share_bps = 0;
         }
 
         store_progress(&ctx.accounts.progress, &progress)?;
 
+        Ok(CrankPageResult {
+            investors_processed: investor_count,
+            next_page_cursor: progress.page_cursor,
+            day_closed: closes_day,
+        })
+    }
+
+    pub fn initialize_policy_group(ctx: Context<InitializePolicyGroup>) -> Result<()> {
+        let policy = &mut ctx.accounts.policy;
+        require!(
+            (policy.status & state::PolicyStatus::HONORARY_READY) != 0,
+            HonoraryQuoteFeeError::HonoraryPositionNotReady
+        );
+        require_keys_eq!(
+            policy.group,
+            Pubkey::default(),
+            HonoraryQuoteFeeError::PolicyAlreadyGrouped
+        );
+
+        let group = &mut ctx.accounts.policy_group;
+        group.primary_policy = policy.key();
+        group.quote_mint = policy.quote_mint;
+        group.quote_treasury = policy.quote_treasury;
+        group.members = [Pubkey::default(); MAX_GROUP_MEMBERS];
+        group.pending_quote = 0;
+        group.member_count = 0;
+        group.claimed_mask = 0;
+        group.bump = ctx.bumps.policy_group;
+
+        policy.group = group.key();
+
+        emit_cpi!(PolicyGroupInitialized {
+            version: EVENT_VERSION,
+            group: group.key(),
+            primary_policy: policy.key(),
+            quote_mint: group.quote_mint,
+        });
+
         Ok(())
     }
-}
+
+    pub fn add_policy_to_group(ctx: Context<AddPolicyToGroup>) -> Result<()> {
+        let group = &mut ctx.accounts.policy_group;
+        let member = &mut ctx.accounts.member_policy;
+
+        require!(
+            (member.status & state::PolicyStatus::HONORARY_READY) != 0,
+            HonoraryQuoteFeeError::HonoraryPositionNotReady
+        );
+        require_keys_eq!(
+            member.group,
+            Pubkey::default(),
+            HonoraryQuoteFeeError::PolicyAlreadyGrouped
+        );
+        require_keys_eq!(
+            member.quote_mint,
+            group.quote_mint,
+            HonoraryQuoteFeeError::GroupQuoteMintMismatch
+        );
+        require!(
+            (group.member_count as usize) < MAX_GROUP_MEMBERS,
+            HonoraryQuoteFeeError::PolicyGroupFull
+        );
+
+        let slot = group.member_count as usize;
+        group.members[slot] = member.key();
+        group.member_count = group
+            .member_count
+            .checked_add(1)
+            .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?;
+        member.group = group.key();
+
+        emit_cpi!(PolicyAddedToGroup {
+            version: EVENT_VERSION,
+            group: group.key(),
+            policy: member.key(),
+            member_count: group.member_count,
+        });
+
+        Ok(())
+    }
+
+    pub fn claim_group_member_fees(ctx: Context<ClaimGroupMemberFees>) -> Result<()> {
+        let policy = &ctx.accounts.policy;
+        let group = &mut ctx.accounts.policy_group;
+        let member_index = group
+            .member_index(&policy.key())
+            .ok_or(HonoraryQuoteFeeError::NotGroupMember)?;
+
+        let events = EventEmitter::new(&ctx.accounts.event_authority, ctx.bumps.event_authority);
+        let quote_claimed = DammV2FeeSource {
+            policy_key: policy.key(),
+            fee_capture_mode: policy.fee_capture_mode,
+            honorary_position: &ctx.accounts.honorary_position,
+            accounts: cp_amm::DammClaimAccounts {
+                cp_amm_program: ctx.accounts.cp_amm_program.to_account_info(),
+                pool: ctx.accounts.pool.to_account_info(),
+                pool_authority: ctx.accounts.pool_authority.to_account_info(),
+                position: ctx.accounts.position.to_account_info(),
+                position_nft_account: ctx.accounts.position_nft_account.to_account_info(),
+                base_fee_check: ctx.accounts.base_fee_check.to_account_info(),
+                quote_treasury: ctx.accounts.quote_treasury.to_account_info(),
+                base_vault: ctx.accounts.base_vault.to_account_info(),
+                quote_vault: ctx.accounts.quote_vault.to_account_info(),
+                base_mint: ctx.accounts.base_mint.to_account_info(),
+                quote_mint: ctx.accounts.quote_mint.to_account_info(),
+                token_program_a: ctx.accounts.token_program_a.to_account_info(),
+                token_program_b: ctx.accounts.token_program_b.to_account_info(),
+                event_authority: ctx.accounts.damm_event_authority.to_account_info(),
+            },
+            clock: &Clock::get()?,
+            events: &events,
+        }
+        .collect()?;
+
+        if quote_claimed > 0 {
+            let bump_seed = [ctx.accounts.honorary_position.bump];
+            let policy_key = policy.key();
+            let seeds: [&[u8]; 3] = [HONORARY_POSITION_SEED, policy_key.as_ref(), &bump_seed];
+            let signer: &[&[&[u8]]] = &[&seeds];
+
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.quote_treasury.to_account_info(),
+                        to: ctx.accounts.group_quote_treasury.to_account_info(),
+                        authority: ctx.accounts.honorary_position.to_account_info(),
+                    },
+                    signer,
+                ),
+                quote_claimed,
+            )?;
+        }
+
+        group.pending_quote = group
+            .pending_quote
+            .checked_add(quote_claimed)
+            .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?;
+        group.claimed_mask |= 1u8 << member_index;
+
+        emit_cpi!(GroupMemberFeesForwarded {
+            version: EVENT_VERSION,
+            group: group.key(),
+            policy: policy.key(),
+            quote_forwarded: quote_claimed,
+            pending_quote: group.pending_quote,
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit_quote_for_distribution(
+        ctx: Context<DepositQuoteForDistribution>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, HonoraryQuoteFeeError::InvalidDepositAmount);
+
+        let policy = &ctx.accounts.policy;
+        require!(
+            (policy.status & state::PolicyStatus::HONORARY_READY) != 0,
+            HonoraryQuoteFeeError::HonoraryPositionNotReady
+        );
+        if policy.group != Pubkey::default() {
+            let group = ctx
+                .accounts
+                .policy_group
+                .as_ref()
+                .ok_or(HonoraryQuoteFeeError::PolicyGroupMissing)?;
+            require_keys_eq!(
+                group.key(),
+                policy.group,
+                HonoraryQuoteFeeError::NotGroupMember
+            );
+            require_keys_eq!(
+                group.primary_policy,
+                policy.key(),
+                HonoraryQuoteFeeError::GroupedPolicyCrank
+            );
+        }
+
+        let deposited = ManualDepositSource {
+            depositor: ctx.accounts.depositor.to_account_info(),
+            depositor_quote_account: ctx.accounts.depositor_quote_account.to_account_info(),
+            quote_treasury: ctx.accounts.quote_treasury.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            amount,
+        }
+        .collect()?;
+
+        let progress = &mut ctx.accounts.progress;
+        progress.pending_deposit_quote = progress
+            .pending_deposit_quote
+            .checked_add(deposited)
+            .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?;
+
+        emit_cpi!(QuoteDeposited {
+            version: EVENT_VERSION,
+            policy: policy.key(),
+            depositor: ctx.accounts.depositor.key(),
+            amount: deposited,
+            pending_deposit_quote: progress.pending_deposit_quote,
+        });
+
+        Ok(())
+    }
+
+    pub fn force_close_day(ctx: Context<ForceCloseDay>) -> Result<()> {
+        let clock = Clock::get()?;
+        let now_ts = clock.unix_timestamp;
+
+        let policy = &mut ctx.accounts.policy;
+        let progress = &mut ctx.accounts.progress;
+
+        require!(progress.day_open, HonoraryQuoteFeeError::DayNotOpen);
+        if ctx.accounts.caller.key() != policy.authority {
+            let force_close_after = progress
+                .day_start_ts
+                .checked_add(DAY_SECONDS)
+                .and_then(|ts| ts.checked_add(policy.force_close_grace_seconds))
+                .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?;
+            require!(
+                now_ts >= force_close_after,
+                HonoraryQuoteFeeError::ForceCloseNotReady
+            );
+        }
+
+        let unpaid_investor_quote = saturating_sub_u64(
+            progress.target_investor_quote,
+            progress.investor_distributed,
+        );
+        let released_carry = force_close_released_carry(
+            progress.share_bps,
+            policy.force_close_destination,
+            unpaid_investor_quote,
+            progress.carry_quote,
+        );
+        let entitlement = investor_entitlement_quote(progress.claimed_quote, progress.share_bps)?;
+        let (_, reserve_quote_after) = cap_reserve(policy, progress)
+            .split(
+                entitlement,
+                policy
+                    .daily_cap_quote
+                    .saturating_mul(u64::from(progress.day_count.max(1))),
+                progress.share_bps,
+            )
+            .map_err(HonoraryQuoteFeeError::from)?;
+        let creator_transfer = creator_quote_at_close(
+            progress.claimed_quote,
+            progress.target_investor_quote,
+            progress.reserve_quote,
+            reserve_quote_after,
+        )?
+        .checked_add(released_carry)
+        .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?;
+        progress.carry_quote = saturating_sub_u64(progress.carry_quote, released_carry);
+        progress.reserve_quote = reserve_quote_after;
+
+        let (creator_destination, escrowed_total) = creator_payout_destination(
+            policy,
+            ctx.accounts.creator_quote_ata.as_ref(),
+            ctx.accounts.creator_escrow.as_deref_mut(),
+            ctx.accounts
+                .creator_escrow_vault
+                .as_ref()
+                .map(|vault| vault.as_ref().as_ref()),
+            creator_transfer,
+            now_ts,
+        )?;
+        if creator_transfer > 0 {
+            let bump_seed = [ctx.accounts.honorary_position.bump];
+            let policy_key = policy.key();
+            let seeds: [&[u8]; 3] = [HONORARY_POSITION_SEED, policy_key.as_ref(), &bump_seed];
+            let signer: &[&[&[u8]]] = &[&seeds];
+
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.quote_treasury.to_account_info(),
+                        to: creator_destination.clone(),
+                        authority: ctx.accounts.honorary_position.to_account_info(),
+                    },
+                    signer,
+                ),
+                creator_transfer,
+            )?;
+            if let Some(total_deposited) = escrowed_total {
+                emit_cpi!(CreatorQuoteEscrowed {
+                    version: EVENT_VERSION,
+                    policy: policy_key,
+                    day_start_ts: progress.day_start_ts,
+                    amount: creator_transfer,
+                    total_deposited,
+                });
+            }
+        }
+
+        let events = EventEmitter::new(&ctx.accounts.event_authority, ctx.bumps.event_authority);
+        record_day_close(
+            &events,
+            policy,
+            policy.key(),
+            ctx.accounts.day_history.as_ref(),
+            progress,
+            creator_transfer,
+            progress.share_bps,
+        )?;
+        emit_cpi!(DayForceClosed {
+            version: EVENT_VERSION,
+            policy: policy.key(),
+            closed_by: ctx.accounts.caller.key(),
+            day_start_ts: progress.day_start_ts,
+            creator_quote_paid: creator_transfer,
+            investor_quote_paid: progress.investor_distributed,
+            unpaid_investor_quote,
+            carry_quote: progress.carry_quote,
+            released_carry,
+        });
+
+        policy.last_day_close_ts = progress.day_start_ts;
+        progress.day_open = false;
+        progress.claimed_quote = 0;
+        progress.investor_distributed = 0;
+        progress.page_cursor = 0;
+        progress.day_count = 0;
+        progress.target_investor_quote = 0;
+        progress.share_bps = 0;
+        progress.investor_snapshot = [0u8; 32];
+
+        Ok(())
+    }
+
+    pub fn rescue_tokens(ctx: Context<RescueTokens>, amount: u64) -> Result<()> {
+        let policy = &ctx.accounts.policy;
+        let source = &ctx.accounts.source;
+        require!(amount > 0, HonoraryQuoteFeeError::InvalidRescueAmount);
+
+        let rescuable = if source.key() == policy.quote_treasury {
+            // The open day's claim is only split between investors and creator at close.
+            require!(
+                !ctx.accounts.progress.day_open,
+                HonoraryQuoteFeeError::RescueDuringDay
+            );
+            let group_pending_quote = match ctx.accounts.policy_group.as_deref() {
+                _ if policy.group == Pubkey::default() => 0,
+                Some(group) if group.key() == policy.group => {
+                    if group.quote_treasury == source.key() {
+                        group.pending_quote
+                    } else {
+                        0
+                    }
+                }
+                _ => return err!(HonoraryQuoteFeeError::PolicyGroupMissing),
+            };
+            let committed = committed_treasury_quote(&ctx.accounts.progress, group_pending_quote)?;
+            saturating_sub_u64(source.amount, committed)
+        } else {
+            source.amount
+        };
+        require!(
+            amount <= rescuable,
+            HonoraryQuoteFeeError::RescueExceedsAvailable
+        );
+
+        let bump_seed = [ctx.accounts.honorary_position.bump];
+        let policy_key = policy.key();
+        let seeds: [&[u8]; 3] = [HONORARY_POSITION_SEED, policy_key.as_ref(), &bump_seed];
+        let signer: &[&[&[u8]]] = &[&seeds];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: source.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: ctx.accounts.honorary_position.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+        )?;
+
+        emit_cpi!(TokensRescued {
+            version: EVENT_VERSION,
+            policy: policy_key,
+            source: source.key(),
+            destination: ctx.accounts.destination.key(),
+            mint: source.mint,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    pub fn queue_policy_change(
+        ctx: Context<QueuePolicyChange>,
+        change: PolicyChange,
+    ) -> Result<()> {
+        let now_ts = Clock::get()?.unix_timestamp;
+        let policy = &ctx.accounts.policy;
+        validate_policy_change(
+            &change,
+            policy.quote_mint,
+            ctx.accounts.new_creator_quote_ata.as_deref(),
+            ctx.accounts.creator_escrow.as_deref(),
+        )?;
+        if change.creator_vesting.is_some() {
+            require_keys_eq!(
+                policy.creator_escrow,
+                Pubkey::default(),
+                HonoraryQuoteFeeError::InvalidCreatorEscrow
+            );
+        }
+
+        let pending = &mut ctx.accounts.pending_change;
+        pending.policy = policy.key();
+        pending.change = change.clone();
+        pending.queued_at = now_ts;
+        pending.eta = now_ts
+            .checked_add(policy.timelock_seconds)
+            .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?;
+        pending.bump = ctx.bumps.pending_change;
+
+        emit_cpi!(PolicyChangeQueued {
+            version: EVENT_VERSION,
+            policy: policy.key(),
+            change,
+            eta: pending.eta,
+        });
+
+        Ok(())
+    }
+
+    pub fn execute_policy_change(ctx: Context<ExecutePolicyChange>) -> Result<()> {
+        let now_ts = Clock::get()?.unix_timestamp;
+        let pending = &ctx.accounts.pending_change;
+        require!(
+            now_ts >= pending.eta,
+            HonoraryQuoteFeeError::PolicyChangeNotReady
+        );
+        require!(
+            !ctx.accounts.progress.day_open,
+            HonoraryQuoteFeeError::PolicyChangeDuringDay
+        );
+
+        let policy = &mut ctx.accounts.policy;
+        pending.change.apply(policy);
+        if pending.change.creator_vesting.is_some() {
+            let escrow = ctx
+                .accounts
+                .creator_escrow
+                .as_ref()
+                .ok_or(HonoraryQuoteFeeError::CreatorEscrowMissing)?;
+            policy.creator_escrow = escrow.key();
+        }
+
+        emit_cpi!(PolicyChangeExecuted {
+            version: EVENT_VERSION,
+            policy: policy.key(),
+            change: pending.change.clone(),
+            executed_by: ctx.accounts.executor.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn cancel_policy_change(ctx: Context<CancelPolicyChange>) -> Result<()> {
+        let pending = &ctx.accounts.pending_change;
+
+        emit_cpi!(PolicyChangeCancelled {
+            version: EVENT_VERSION,
+            policy: ctx.accounts.policy.key(),
+            change: pending.change.clone(),
+            eta: pending.eta,
+        });
+
+        Ok(())
+    }
+
+    /// Creates the escrow and its vault. Creator payouts only move into it once a queued
+    /// `PolicyChange::creator_vesting` is executed, under the policy's timelock.
+    pub fn initialize_creator_escrow(ctx: Context<InitializeCreatorEscrow>) -> Result<()> {
+        let policy = &ctx.accounts.policy;
+        let escrow = &mut ctx.accounts.creator_escrow;
+        escrow.policy = policy.key();
+        escrow.vault = ctx.accounts.escrow_vault.key();
+        escrow.bump = ctx.bumps.creator_escrow;
+
+        emit_cpi!(CreatorEscrowInitialized {
+            version: EVENT_VERSION,
+            policy: policy.key(),
+            escrow: escrow.key(),
+            vault: escrow.vault,
+        });
+
+        Ok(())
+    }
+
+    /// Releases everything vested so far to the policy's creator quote ATA. Anyone may
+    /// call it since the destination is fixed by the policy.
+    pub fn withdraw_creator_vested(ctx: Context<WithdrawCreatorVested>) -> Result<()> {
+        let now_ts = Clock::get()?.unix_timestamp;
+        let policy = &ctx.accounts.policy;
+        let escrow = &mut ctx.accounts.creator_escrow;
+
+        escrow.release_matured(&policy.creator_vesting, now_ts)?;
+        let amount = escrow.withdrawable(&policy.creator_vesting, now_ts)?;
+        require!(amount > 0, HonoraryQuoteFeeError::NothingVested);
+
+        let bump_seed = [escrow.bump];
+        let policy_key = policy.key();
+        let seeds: [&[u8]; 3] = [CREATOR_ESCROW_SEED, policy_key.as_ref(), &bump_seed];
+        let signer: &[&[&[u8]]] = &[&seeds];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.escrow_vault.to_account_info(),
+                    to: ctx.accounts.creator_quote_ata.to_account_info(),
+                    authority: escrow.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+        )?;
+        escrow.total_withdrawn = escrow
+            .total_withdrawn
+            .checked_add(amount)
+            .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?;
+
+        emit_cpi!(CreatorVestedWithdrawn {
+            version: EVENT_VERSION,
+            policy: policy_key,
+            destination: ctx.accounts.creator_quote_ata.key(),
+            amount,
+            total_withdrawn: escrow.total_withdrawn,
+            total_deposited: escrow.total_deposited,
+        });
+
+        Ok(())
+    }
+
+    /// Grows a policy and its progress account created before fields were appended to
+    /// `Policy` and `DistributionProgress`, with `payer` covering the extra rent. Appended
+    /// fields start zeroed, which keeps the original terms (linear curve, no eligibility
+    /// rules, reserve, timelock or page interval); the mint fields and position fee capture
+    /// are filled in here.
+    pub fn migrate_policy_accounts(ctx: Context<MigratePolicyAccounts>) -> Result<()> {
+        let policy_info = ctx.accounts.policy.to_account_info();
+        require!(
+            policy_info.data_len() < Policy::LEN,
+            HonoraryQuoteFeeError::AccountAlreadyMigrated
+        );
+        extend_account(
+            &policy_info,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            Policy::LEN,
+        )?;
+        let mut policy = {
+            let data = policy_info.try_borrow_data()?;
+            Policy::try_deserialize(&mut &data[..])?
+        };
+        require_keys_eq!(
+            policy.authority,
+            ctx.accounts.authority.key(),
+            HonoraryQuoteFeeError::Unauthorized
+        );
+        require_keys_eq!(
+            policy.pool,
+            ctx.accounts.damm_pool.key(),
+            HonoraryQuoteFeeError::InvalidPoolAccount
+        );
+        require_keys_eq!(
+            policy.quote_mint,
+            ctx.accounts.quote_mint.key(),
+            HonoraryQuoteFeeError::QuoteMintMismatch
+        );
+
+        let progress_info = ctx.accounts.progress.to_account_info();
+        if progress_info.data_len() < DistributionProgress::LEN {
+            extend_account(
+                &progress_info,
+                &ctx.accounts.payer,
+                &ctx.accounts.system_program,
+                DistributionProgress::LEN,
+            )?;
+        }
+        let progress = load_progress(&ctx.accounts.progress)?;
+        require_keys_eq!(
+            progress.policy,
+            policy_info.key(),
+            HonoraryQuoteFeeError::InvalidProgressAccount
+        );
+        require!(
+            !progress.day_open,
+            HonoraryQuoteFeeError::MigrationDuringDay
+        );
+
+        policy.vesting_mint = ctx.accounts.vesting_mint.key();
+        policy.quote_decimals = ctx.accounts.quote_mint.decimals;
+        policy.vesting_decimals = ctx.accounts.vesting_mint.decimals;
+        policy.fee_capture_mode = FeeCaptureMode::POSITION_FEES;
+        {
+            let mut data = policy_info.try_borrow_mut_data()?;
+            policy.try_serialize(&mut &mut data[..])?;
+        }
+
+        emit_cpi!(PolicyAccountsMigrated {
+            version: EVENT_VERSION,
+            policy: policy_info.key(),
+            progress: progress_info.key(),
+            vesting_mint: policy.vesting_mint,
+            policy_len: Policy::LEN as u32,
+            progress_len: DistributionProgress::LEN as u32,
+        });
+
+        Ok(())
+    }
+}
 
 pub struct InvestorPayoutPlan {
     pub transfers: Vec<(u64, usize)>,
@@ -478,92 +1693,491 @@ pub struct InvestorPayoutPlan {
     pub target_investor_quote: u64,
     pub carry_for_creator: u64,
     pub carry_quote_after: u64,
+    /// Cap reserve balance once the day closes with this plan's target.
+    pub reserve_quote_after: u64,
+}
+
+/// Cap reserve settings of `policy` with the balance `progress` opened the day with.
+pub fn cap_reserve(policy: &Policy, progress: &DistributionProgress) -> CapReserve {
+    CapReserve {
+        enabled: policy.cap_reserve_enabled,
+        retention_bps: policy.reserve_retention_bps,
+        balance: progress.reserve_quote,
+    }
+}
+
+/// Investors' uncapped share of `claimed_quote`.
+pub fn investor_entitlement_quote(claimed_quote: u64, share_bps: u16) -> Result<u64> {
+    Ok(
+        honorary_payout::investor_entitlement_quote(claimed_quote, share_bps)
+            .map_err(HonoraryQuoteFeeError::from)?,
+    )
+}
+
+/// Creator transfer when a day closes: whatever was claimed or released from the reserve
+/// that is neither the investor target nor kept in the reserve.
+pub fn creator_quote_at_close(
+    claimed_quote: u64,
+    target_investor_quote: u64,
+    reserve_before: u64,
+    reserve_after: u64,
+) -> Result<u64> {
+    Ok(honorary_payout::creator_quote_at_close(
+        claimed_quote,
+        target_investor_quote,
+        reserve_before,
+        reserve_after,
+    )
+    .map_err(HonoraryQuoteFeeError::from)?)
+}
+
+/// Sizes one page with `honorary_payout::build_payout_plan` and pairs each payout with
+/// its investor's token account index.
+#[inline(never)]
+#[allow(clippy::too_many_arguments)]
+pub fn build_investor_payout_plan(
+    investors: Vec<InvestorEntry>,
+    claimed_quote: u64,
+    investor_distributed: u64,
+    carry_quote: u64,
+    y0: u64,
+    investor_fee_share_bps: u16,
+    eligibility_curve: &EligibilityCurve,
+    daily_cap_quote: u64,
+    min_payout_lamports: u64,
+    cap_reserve: CapReserve,
+) -> Result<InvestorPayoutPlan> {
+    let investor_count_u32 = u32::try_from(investors.len())
+        .map_err(|_| error!(HonoraryQuoteFeeError::ArithmeticOverflow))?;
+
+    let locked_amounts: Vec<u64> = investors.iter().map(|entry| entry.locked_amount).collect();
+    let plan = build_payout_plan(
+        &locked_amounts,
+        &DayState {
+            claimed_quote,
+            investor_distributed,
+            carry_quote,
+        },
+        &PayoutTerms {
+            y0,
+            investor_fee_share_bps,
+            eligibility_curve: (*eligibility_curve).into(),
+            daily_cap_quote,
+            min_payout_lamports,
+        },
+        cap_reserve,
+    )
+    .map_err(HonoraryQuoteFeeError::from)?;
+
+    let transfers = plan
+        .payouts
+        .iter()
+        .zip(investors.iter())
+        .map(|(payout, entry)| (*payout, entry.token_account_index))
+        .collect();
+
+    Ok(InvestorPayoutPlan {
+        transfers,
+        investor_count: investor_count_u32,
+        share_bps: plan.share_bps,
+        total_paid: plan.total_paid,
+        target_investor_quote: plan.target_investor_quote,
+        carry_for_creator: plan.carry_for_creator,
+        carry_quote_after: plan.carry_quote_after,
+        reserve_quote_after: plan.reserve_quote_after,
+    })
+}
+
+/// Treasury quote that `rescue_tokens` must leave in place: investor carry, the open
+/// day's unpaid investor target, the cap reserve, and deposits or group forwards not yet
+/// counted by a crank.
+pub fn committed_treasury_quote(
+    progress: &DistributionProgress,
+    group_pending_quote: u64,
+) -> Result<u64> {
+    let unpaid_investor_quote = if progress.day_open {
+        saturating_sub_u64(
+            progress.target_investor_quote,
+            progress.investor_distributed,
+        )
+    } else {
+        0
+    };
+    [
+        unpaid_investor_quote,
+        progress.reserve_quote,
+        progress.pending_deposit_quote,
+        group_pending_quote,
+    ]
+    .iter()
+    .try_fold(progress.carry_quote, |total, amount| {
+        total.checked_add(*amount)
+    })
+    .ok_or_else(|| error!(HonoraryQuoteFeeError::ArithmeticOverflow))
+}
+
+/// Checks a queued change the same way `initialize_policy` checks its parameters.
+pub fn validate_policy_change(
+    change: &PolicyChange,
+    quote_mint: Pubkey,
+    new_creator_quote_ata: Option<&Account<'_, TokenAccount>>,
+    creator_escrow: Option<&Account<'_, CreatorEscrow>>,
+) -> Result<()> {
+    require!(!change.is_empty(), HonoraryQuoteFeeError::EmptyPolicyChange);
+    if let Some(share_bps) = change.investor_fee_share_bps {
+        require!(
+            share_bps <= MAX_BASIS_POINTS,
+            HonoraryQuoteFeeError::InvalidInvestorShare
+        );
+    }
+    if let Some(y0) = change.y0 {
+        require!(y0 > 0, HonoraryQuoteFeeError::InvalidY0);
+    }
+    if let Some(creator_quote_ata) = change.creator_quote_ata {
+        let account =
+            new_creator_quote_ata.ok_or(HonoraryQuoteFeeError::InvalidCreatorQuoteAccount)?;
+        require_keys_eq!(
+            account.key(),
+            creator_quote_ata,
+            HonoraryQuoteFeeError::InvalidCreatorQuoteAccount
+        );
+        require_keys_eq!(
+            account.mint,
+            quote_mint,
+            HonoraryQuoteFeeError::InvalidCreatorQuoteAccount
+        );
+    }
+    if let Some(schedule) = change.creator_vesting {
+        schedule.validate()?;
+        require!(
+            creator_escrow.is_some(),
+            HonoraryQuoteFeeError::CreatorEscrowMissing
+        );
+    }
+    Ok(())
+}
+
+/// Requires `cranker` to be allowed when the policy restricts cranking to an allowlist.
+pub fn check_cranker_allowed(
+    policy: &Policy,
+    allowlist: Option<&Account<'_, CrankerAllowlist>>,
+    cranker: &Pubkey,
+) -> Result<()> {
+    if policy.cranker_allowlist == Pubkey::default() {
+        return Ok(());
+    }
+
+    let allowlist = allowlist.ok_or(HonoraryQuoteFeeError::CrankerAllowlistMissing)?;
+    require_keys_eq!(
+        allowlist.key(),
+        policy.cranker_allowlist,
+        HonoraryQuoteFeeError::InvalidCrankerAllowlist
+    );
+    require!(
+        allowlist.permits(cranker),
+        HonoraryQuoteFeeError::CrankerNotAllowed
+    );
+    Ok(())
+}
+
+/// Enforces the policy's minimum spacing between pages of an open day. Opening a day is
+/// already gated by `DAY_SECONDS` since the previous close.
+pub fn check_page_spacing(
+    opening_day: bool,
+    last_page_ts: i64,
+    min_page_interval_seconds: i64,
+    now_ts: i64,
+) -> Result<()> {
+    if opening_day || min_page_interval_seconds == 0 {
+        return Ok(());
+    }
+
+    let next_page_ts = last_page_ts
+        .checked_add(min_page_interval_seconds)
+        .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?;
+    require!(
+        now_ts >= next_page_ts,
+        HonoraryQuoteFeeError::PageRateLimited
+    );
+    Ok(())
+}
+
+/// Whether enough compute remains to pay `investor_count` investors and still finish the
+/// page.
+pub fn page_has_compute_for(investor_count: usize) -> bool {
+    let needed = (investor_count as u64)
+        .saturating_mul(INVESTOR_PAYOUT_COMPUTE_UNITS)
+        .saturating_add(CRANK_COMPUTE_RESERVE);
+    sol_remaining_compute_units() >= needed
+}
+
+/// Whether a page's streams are the next page of the day's snapshot: `committed` must link
+/// this page's digest to `remaining`, and only the last page may end the chain.
+pub fn snapshot_page_matches(
+    committed: &[u8; 32],
+    investors: &[InvestorEntry],
+    remaining: &[u8; 32],
+    is_last_page: bool,
+) -> bool {
+    let leaves: Vec<[u8; 32]> = investors.iter().map(|entry| entry.snapshot_leaf).collect();
+    (*remaining == [0u8; 32]) == is_last_page
+        && investor_snapshot_link(&investor_page_digest(&leaves), remaining) == *committed
+}
+
+/// Checks the blocklist and forfeiture ATA supplied to the crank against the policy.
+pub fn resolve_blocklist<'a>(
+    policy: &Policy,
+    blocklist: Option<&'a Account<'_, Blocklist>>,
+    forfeiture_ata: Option<&Account<'_, TokenAccount>>,
+) -> Result<Option<&'a Blocklist>> {
+    if policy.blocklist == Pubkey::default() {
+        return Ok(None);
+    }
+
+    let (blocklist, forfeiture_ata) = blocklist
+        .zip(forfeiture_ata)
+        .ok_or(HonoraryQuoteFeeError::BlocklistMissing)?;
+    require_keys_eq!(
+        blocklist.key(),
+        policy.blocklist,
+        HonoraryQuoteFeeError::InvalidBlocklistAccount
+    );
+    require_keys_eq!(
+        forfeiture_ata.key(),
+        blocklist.forfeiture_ata,
+        HonoraryQuoteFeeError::InvalidBlocklistAccount
+    );
+    Ok(Some(blocklist))
 }
 
-#[inline(never)]
-pub fn build_investor_payout_plan(
-    investors: Vec<InvestorEntry>,
-    claimed_quote: u64,
-    investor_distributed: u64,
-    carry_quote: u64,
-    y0: u64,
-    investor_fee_share_bps: u16,
-    daily_cap_quote: u64,
-    min_payout_lamports: u64,
-) -> Result<InvestorPayoutPlan> {
-    let investor_count_u32 = u32::try_from(investors.len())
-        .map_err(|_| error!(HonoraryQuoteFeeError::ArithmeticOverflow))?;
-
-    let total_locked: u128 = investors
+/// `(token_account_index, stream, recipient)` of investors whose payout is forfeited.
+///
+/// Blocked investors keep their weight so the remaining investors' shares are unchanged.
+pub fn forfeited_investors(
+    investors: &[InvestorEntry],
+    blocklist: &Blocklist,
+) -> Vec<(usize, Pubkey, Pubkey)> {
+    investors
         .iter()
-        .map(|entry| entry.locked_amount as u128)
-        .sum();
-    let share_bps = eligible_share_bps(total_locked, y0, investor_fee_share_bps);
+        .filter(|entry| blocklist.contains(&entry.recipient))
+        .map(|entry| (entry.token_account_index, entry.stream, entry.recipient))
+        .collect()
+}
+
+/// Extra seed distinguishing policies on the same pool. Index 0 contributes no bytes,
+/// so the first policy of a pool keeps its original `["policy", pool]` address.
+pub fn policy_index_seed(policy_index: u16) -> Vec<u8> {
+    if policy_index == 0 {
+        Vec::new()
+    } else {
+        policy_index.to_le_bytes().to_vec()
+    }
+}
+
+/// Address of the policy with `policy_index` on `pool`.
+pub fn policy_address(pool: &Pubkey, policy_index: u16) -> Pubkey {
+    Pubkey::find_program_address(
+        &[POLICY_SEED, pool.as_ref(), &policy_index_seed(policy_index)],
+        &crate::ID,
+    )
+    .0
+}
+
+/// Address of the distribution progress of the policy with `policy_index` on `pool`.
+pub fn progress_address(pool: &Pubkey, policy_index: u16) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            PROGRESS_SEED,
+            pool.as_ref(),
+            &policy_index_seed(policy_index),
+        ],
+        &crate::ID,
+    )
+    .0
+}
 
-    let mut target_investor_quote = u128_to_u64(mul_div_floor_u128(
-        claimed_quote as u128,
-        share_bps as u128,
-        MAX_BASIS_POINTS as u128,
-    )?)?;
+/// Address of the honorary PDA that owns the position and treasuries of `policy`.
+pub fn honorary_position_address(policy: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[HONORARY_POSITION_SEED, policy.as_ref()], &crate::ID).0
+}
 
-    if daily_cap_quote > 0 {
-        target_investor_quote = target_investor_quote.min(daily_cap_quote);
+/// Takes the quote forwarded by group members for the primary policy's current day.
+///
+/// Opening a grouped day requires every member to have claimed since the previous
+/// day opened, so that all pools contribute to the same distribution day.
+pub fn take_group_pending_quote(
+    group: &mut PolicyGroup,
+    policy_key: Pubkey,
+    opening_day: bool,
+) -> Result<u64> {
+    require_keys_eq!(
+        group.primary_policy,
+        policy_key,
+        HonoraryQuoteFeeError::GroupedPolicyCrank
+    );
+    if opening_day {
+        require!(
+            group.all_members_claimed(),
+            HonoraryQuoteFeeError::GroupMembersNotClaimed
+        );
+        group.claimed_mask = 0;
     }
 
-    let mut available_to_pay = target_investor_quote
-        .saturating_sub(investor_distributed)
-        .saturating_add(carry_quote);
-    let mut carry_for_creator = 0u64;
-    if share_bps == 0 {
-        carry_for_creator = carry_quote;
-        available_to_pay = 0;
+    let forwarded = group.pending_quote;
+    group.pending_quote = 0;
+    Ok(forwarded)
+}
+
+/// Number of logical distribution days covered by a day opened at `now_ts`.
+///
+/// The first day of a policy always counts as one; afterwards every full
+/// `DAY_SECONDS` since the previous day start counts, bounded by
+/// `MAX_CATCH_UP_DAYS`.
+pub fn elapsed_day_count(last_day_close_ts: i64, now_ts: i64) -> u32 {
+    if last_day_close_ts == NEVER_CLOSED_TS || now_ts <= last_day_close_ts {
+        return 1;
     }
 
-    let mut total_paid_this_page: u64 = 0;
-    let mut transfers: Vec<(u64, usize)> = Vec::with_capacity(investors.len());
+    let elapsed_days = now_ts.saturating_sub(last_day_close_ts) / DAY_SECONDS;
+    u32::try_from(elapsed_days)
+        .unwrap_or(MAX_CATCH_UP_DAYS)
+        .clamp(1, MAX_CATCH_UP_DAYS)
+}
 
-    for entry in investors.iter() {
-        if available_to_pay == 0 || entry.locked_amount == 0 {
-            transfers.push((0, entry.token_account_index));
-            continue;
-        }
+/// Portion of `carry_quote` handed to the creator when a day is force-closed.
+///
+/// Page accounting already retains the undistributed investor target in carry, so
+/// routing it to the creator releases it from carry rather than paying it twice.
+/// As on a regular close, a zero share releases the whole carry.
+pub fn force_close_released_carry(
+    share_bps: u16,
+    destination: u8,
+    unpaid_investor_quote: u64,
+    carry_quote: u64,
+) -> u64 {
+    match (share_bps, destination) {
+        (0, _) => carry_quote,
+        (_, ForceCloseDestination::CARRY) => 0,
+        _ => unpaid_investor_quote.min(carry_quote),
+    }
+}
 
-        let payout_u128 = mul_div_floor_u128(
-            available_to_pay as u128,
-            entry.locked_amount as u128,
-            total_locked.max(1),
-        )?;
-        let mut payout = u128_to_u64(payout_u128)?;
+/// Splits the closing day into the logical days it covers, with the totals split
+/// evenly and any remainder on the latest day.
+pub fn logical_day_summaries(
+    progress: &DistributionProgress,
+    creator_quote_paid: u64,
+    share_bps: u16,
+) -> Vec<DaySummary> {
+    let day_count = progress.day_count.max(1);
+    (0..day_count)
+        .map(|day_index| {
+            let days_before_current = i64::from(day_count - 1 - day_index);
+            DaySummary {
+                day_start_ts: progress
+                    .day_start_ts
+                    .saturating_sub(days_before_current.saturating_mul(DAY_SECONDS)),
+                claimed_quote: split_evenly(progress.claimed_quote, day_count, day_index),
+                investor_quote_paid: split_evenly(
+                    progress.investor_distributed,
+                    day_count,
+                    day_index,
+                ),
+                creator_quote_paid: split_evenly(creator_quote_paid, day_count, day_index),
+                carry_quote: progress.carry_quote,
+                investor_count: progress.page_cursor,
+                share_bps,
+            }
+        })
+        .collect()
+}
 
-        if payout < min_payout_lamports {
-            payout = 0;
-        }
+/// Emits one `CreatorPayoutDayClosed` per logical day covered by `progress` and
+/// appends the same summaries to the policy's day history when one is configured.
+#[inline(never)]
+fn record_day_close(
+    events: &EventEmitter<'_, '_>,
+    policy: &Policy,
+    policy_key: Pubkey,
+    day_history: Option<&UncheckedAccount<'_>>,
+    progress: &DistributionProgress,
+    creator_quote_paid: u64,
+    share_bps: u16,
+) -> Result<()> {
+    let summaries = logical_day_summaries(progress, creator_quote_paid, share_bps);
+    for summary in summaries.iter() {
+        events.emit(&CreatorPayoutDayClosed {
+            version: EVENT_VERSION,
+            policy: policy_key,
+            day_start_ts: summary.day_start_ts,
+            creator_quote_paid: summary.creator_quote_paid,
+            investor_quote_paid: summary.investor_quote_paid,
+            claimed_quote: summary.claimed_quote,
+            share_bps: summary.share_bps,
+            reserve_quote: progress.reserve_quote,
+        })?;
+    }
 
-        transfers.push((payout, entry.token_account_index));
-        total_paid_this_page = total_paid_this_page
-            .checked_add(payout)
-            .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?;
+    if policy.day_history == Pubkey::default() {
+        return Ok(());
     }
+    let history_account = day_history.ok_or(HonoraryQuoteFeeError::DayHistoryMissing)?;
+    require_keys_eq!(
+        history_account.key(),
+        policy.day_history,
+        HonoraryQuoteFeeError::InvalidDayHistoryAccount
+    );
 
-    let carry_quote_after = if share_bps == 0 {
-        0
-    } else {
-        available_to_pay.saturating_sub(total_paid_this_page)
+    let mut history = {
+        let data_ref = history_account
+            .try_borrow_data()
+            .map_err(|_| error!(HonoraryQuoteFeeError::InvalidDayHistoryAccount))?;
+        let mut data_slice: &[u8] = &data_ref;
+        Box::new(
+            DayHistory::try_deserialize(&mut data_slice)
+                .map_err(|_| error!(HonoraryQuoteFeeError::InvalidDayHistoryAccount))?,
+        )
     };
+    for summary in summaries {
+        history.push(summary)?;
+    }
 
-    Ok(InvestorPayoutPlan {
-        transfers,
-        investor_count: investor_count_u32,
-        share_bps,
-        total_paid: total_paid_this_page,
-        target_investor_quote,
-        carry_for_creator,
-        carry_quote_after,
-    })
+    let mut data_ref = history_account
+        .try_borrow_mut_data()
+        .map_err(|_| error!(HonoraryQuoteFeeError::InvalidDayHistoryAccount))?;
+    history
+        .try_serialize(&mut (&mut data_ref[..]))
+        .map_err(|_| error!(HonoraryQuoteFeeError::InvalidDayHistoryAccount))?;
+    Ok(())
+}
+
+/// Resolves the account receiving the day-close creator transfer. Vesting policies send it
+/// to the creator escrow vault and book it on the escrow as a tranche vesting from `now_ts`,
+/// returning the new escrow total.
+fn creator_payout_destination<'a, 'info>(
+    policy: &Policy,
+    creator_quote_ata: &'a AccountInfo<'info>,
+    creator_escrow: Option<&mut Account<'info, CreatorEscrow>>,
+    creator_escrow_vault: Option<&'a AccountInfo<'info>>,
+    amount: u64,
+    now_ts: i64,
+) -> Result<(&'a AccountInfo<'info>, Option<u64>)> {
+    if policy.creator_escrow == Pubkey::default() {
+        return Ok((creator_quote_ata, None));
+    }
+    let (escrow, vault) = creator_escrow
+        .zip(creator_escrow_vault)
+        .ok_or(HonoraryQuoteFeeError::CreatorEscrowMissing)?;
+    require!(
+        escrow.key() == policy.creator_escrow && *vault.key == escrow.vault,
+        HonoraryQuoteFeeError::InvalidCreatorEscrow
+    );
+    escrow.record_deposit(&policy.creator_vesting, now_ts, amount)?;
+    Ok((vault, Some(escrow.total_deposited)))
 }
 
 #[inline(never)]
-fn token_account_amount(account: &UncheckedAccount<'_>) -> Result<u64> {
+pub(crate) fn token_account_amount(account: &AccountInfo<'_>) -> Result<u64> {
     require_keys_eq!(
         *account.owner,
         anchor_spl::token::ID,
@@ -583,6 +2197,33 @@ fn token_account_amount(account: &UncheckedAccount<'_>) -> Result<u64> {
     Ok(amount)
 }
 
+/// Reallocates a program-owned account to `len` bytes, topping its lamports up to the
+/// rent-exempt minimum from `payer`.
+fn extend_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    len: usize,
+) -> Result<()> {
+    let top_up = Rent::get()?
+        .minimum_balance(len)
+        .saturating_sub(account.lamports());
+    if top_up > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: account.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    account.resize(len)?;
+    Ok(())
+}
+
 #[inline(never)]
 fn load_progress(account: &UncheckedAccount<'_>) -> Result<DistributionProgress> {
     let progress = {
@@ -651,10 +2292,26 @@ pub extern "Rust" fn __getrandom_v03_custom(
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
 pub struct InitializePolicyParams {
+    /// Distinguishes independent policies on the same pool; `0` for the first one.
+    pub policy_index: u16,
     pub investor_fee_share_bps: u16,
     pub y0: u64,
     pub daily_cap_quote: u64,
     pub min_payout_lamports: u64,
+    pub force_close_grace_seconds: i64,
+    pub min_page_interval_seconds: i64,
+    pub timelock_seconds: i64,
+    pub force_close_destination: u8,
+    pub fee_capture_mode: u8,
+    pub eligibility_curve: EligibilityCurve,
+    pub eligibility_rules: EligibilityRules,
+    pub cap_reserve_enabled: bool,
+    pub reserve_retention_bps: u16,
+    pub investor_snapshot_required: bool,
+    /// `y0` and `eligibility_rules.min_locked_amount` are whole vesting-mint tokens and
+    /// `daily_cap_quote` and `min_payout_lamports` whole quote tokens; the policy stores them
+    /// scaled to base units. Otherwise all four are base units.
+    pub whole_token_amounts: bool,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
@@ -662,9 +2319,30 @@ pub struct CrankQuoteFeeParams {
     pub expected_page_cursor: u32,
     pub max_page_cursor: u32,
     pub is_last_page: bool,
+    /// Trailing remaining accounts forwarded to the distribution hook.
+    pub hook_account_count: u8,
+    /// Day-open commitment to every page of the day; read on the opening page when the
+    /// policy requires investor snapshots.
+    pub investor_snapshot: [u8; 32],
+    /// Commitment to the pages after this one; all zeros on the last page.
+    pub remaining_snapshot: [u8; 32],
+    /// Most investors to take from the supplied pairs; `0` takes as many as fit.
+    pub max_investors: u16,
+}
+
+/// Set as the crank's return data so a caller can resume a partially consumed page.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CrankPageResult {
+    /// Leading investor pairs paid by this page; the rest go to the next page.
+    pub investors_processed: u32,
+    /// `expected_page_cursor` for the next page; `0` once the day closed.
+    pub next_page_cursor: u32,
+    pub day_closed: bool,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
+#[instruction(params: InitializePolicyParams)]
 pub struct InitializePolicy<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
@@ -674,106 +2352,399 @@ pub struct InitializePolicy<'info> {
         init,
         payer = payer,
         space = Policy::LEN,
-        seeds = [POLICY_SEED, damm_pool.key().as_ref()],
+        seeds = [
+            POLICY_SEED,
+            damm_pool.key().as_ref(),
+            policy_index_seed(params.policy_index).as_slice(),
+        ],
+        bump,
+    )]
+    pub policy: Account<'info, Policy>,
+    #[account(
+        init,
+        payer = payer,
+        space = DistributionProgress::LEN,
+        seeds = [
+            PROGRESS_SEED,
+            damm_pool.key().as_ref(),
+            policy_index_seed(params.policy_index).as_slice(),
+        ],
+        bump,
+    )]
+    pub progress: Account<'info, DistributionProgress>,
+    /// CHECK: DAMM pool account
+    #[account(mut)]
+    pub damm_pool: UncheckedAccount<'info>,
+    /// CHECK: DAMM pool authority PDA
+    pub pool_authority: UncheckedAccount<'info>,
+    /// CHECK: DAMM program id
+    pub damm_program: UncheckedAccount<'info>,
+    pub quote_mint: Account<'info, Mint>,
+    pub base_mint: Account<'info, Mint>,
+    /// Mint investors' Streamflow streams lock; usually the base or quote mint.
+    pub vesting_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub quote_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub base_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub creator_quote_ata: Account<'info, TokenAccount>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ConfigureHonoraryPosition<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub policy: Account<'info, Policy>,
+    #[account(
+        init,
+        payer = authority,
+        space = HonoraryPosition::LEN,
+        seeds = [HONORARY_POSITION_SEED, policy.key().as_ref()],
+        bump,
+    )]
+    pub honorary_position: Account<'info, HonoraryPosition>,
+    /// CHECK: Existing DAMM position account
+    #[account(mut)]
+    pub position: UncheckedAccount<'info>,
+    pub position_nft_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub position_nft_account: Account<'info, TokenAccount>,
+    #[account(address = policy.quote_mint)]
+    pub quote_mint: Account<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = quote_mint,
+        associated_token::authority = honorary_position,
+    )]
+    pub quote_treasury: Account<'info, TokenAccount>,
+    #[account(address = policy.base_mint)]
+    pub base_mint: Account<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = base_mint,
+        associated_token::authority = honorary_position,
+    )]
+    pub base_fee_check: Account<'info, TokenAccount>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateHonoraryPosition<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub policy: Box<Account<'info, Policy>>,
+    #[account(
+        init,
+        payer = payer,
+        space = HonoraryPosition::LEN,
+        seeds = [HONORARY_POSITION_SEED, policy.key().as_ref()],
+        bump,
+    )]
+    pub honorary_position: Box<Account<'info, HonoraryPosition>>,
+    /// CHECK: Fresh position NFT mint keypair, initialized by DAMM
+    #[account(mut, signer)]
+    pub position_nft_mint: UncheckedAccount<'info>,
+    /// CHECK: Position NFT token account PDA, initialized by DAMM
+    #[account(mut)]
+    pub position_nft_account: UncheckedAccount<'info>,
+    /// CHECK: Position account PDA, initialized by DAMM and validated after the CPI
+    #[account(mut)]
+    pub position: UncheckedAccount<'info>,
+    /// CHECK: DAMM pool account
+    #[account(mut, address = policy.pool)]
+    pub damm_pool: UncheckedAccount<'info>,
+    /// CHECK: DAMM pool authority
+    #[account(address = policy.pool_authority)]
+    pub pool_authority: UncheckedAccount<'info>,
+    #[account(address = policy.quote_mint)]
+    pub quote_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = quote_mint,
+        associated_token::authority = honorary_position,
+    )]
+    pub quote_treasury: Box<Account<'info, TokenAccount>>,
+    #[account(address = policy.base_mint)]
+    pub base_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = base_mint,
+        associated_token::authority = honorary_position,
+    )]
+    pub base_fee_check: Box<Account<'info, TokenAccount>>,
+    /// CHECK: DAMM event authority
+    pub damm_event_authority: UncheckedAccount<'info>,
+    /// CHECK: DAMM program id
+    #[account(address = policy.cp_amm_program)]
+    pub cp_amm_program: UncheckedAccount<'info>,
+    /// CHECK: Token program DAMM uses for the position NFT
+    pub position_token_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitializeDayHistory<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub policy: Account<'info, Policy>,
+    #[account(
+        init,
+        payer = payer,
+        space = DayHistory::LEN,
+        seeds = [DAY_HISTORY_SEED, policy.key().as_ref()],
+        bump,
+    )]
+    pub day_history: Box<Account<'info, DayHistory>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitializeBlocklist<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub policy: Account<'info, Policy>,
+    #[account(
+        init,
+        payer = payer,
+        space = Blocklist::LEN,
+        seeds = [BLOCKLIST_SEED, policy.key().as_ref()],
+        bump,
+    )]
+    pub blocklist: Box<Account<'info, Blocklist>>,
+    #[account(token::mint = policy.quote_mint)]
+    pub forfeiture_ata: Account<'info, TokenAccount>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateBlocklist<'info> {
+    pub authority: Signer<'info>,
+    #[account(has_one = authority)]
+    pub policy: Account<'info, Policy>,
+    #[account(
+        mut,
+        seeds = [BLOCKLIST_SEED, policy.key().as_ref()],
+        bump = blocklist.bump,
+        has_one = policy,
+    )]
+    pub blocklist: Box<Account<'info, Blocklist>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitializeCrankerAllowlist<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub policy: Account<'info, Policy>,
+    #[account(
+        init,
+        payer = payer,
+        space = CrankerAllowlist::LEN,
+        seeds = [CRANKER_ALLOWLIST_SEED, policy.key().as_ref()],
         bump,
     )]
+    pub cranker_allowlist: Box<Account<'info, CrankerAllowlist>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateCrankerAllowlist<'info> {
+    pub authority: Signer<'info>,
+    #[account(has_one = authority)]
+    pub policy: Account<'info, Policy>,
+    #[account(
+        mut,
+        seeds = [CRANKER_ALLOWLIST_SEED, policy.key().as_ref()],
+        bump = cranker_allowlist.bump,
+        has_one = policy,
+    )]
+    pub cranker_allowlist: Box<Account<'info, CrankerAllowlist>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ConfigureDistributionHook<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub policy: Account<'info, Policy>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CrankQuoteFeeDistribution<'info> {
+    /// CHECK: Only used to ensure a signature is present
+    #[account(signer)]
+    pub cranker: UncheckedAccount<'info>,
+    #[account(mut)]
     pub policy: Account<'info, Policy>,
     #[account(
-        init,
-        payer = payer,
-        space = DistributionProgress::LEN,
-        seeds = [PROGRESS_SEED, damm_pool.key().as_ref()],
+        mut,
+        seeds = [HONORARY_POSITION_SEED, policy.key().as_ref()],
+        bump = honorary_position.bump,
+    )]
+    pub honorary_position: Account<'info, HonoraryPosition>,
+    /// CHECK: Progress account is constrained by seeds and updated manually
+    #[account(
+        mut,
+        seeds = [
+            PROGRESS_SEED,
+            policy.pool.as_ref(),
+            policy_index_seed(policy.policy_index).as_slice(),
+        ],
         bump,
     )]
-    pub progress: Account<'info, DistributionProgress>,
+    pub progress: UncheckedAccount<'info>,
+    /// CHECK: Account is constrained to the policy's configured quote treasury
+    #[account(mut, address = policy.quote_treasury)]
+    pub quote_treasury: UncheckedAccount<'info>,
+    /// CHECK: Account is constrained to the policy's configured base fee check treasury
+    #[account(mut, address = policy.base_fee_check)]
+    pub base_fee_check: UncheckedAccount<'info>,
+    /// CHECK: Account is constrained to the policy's configured creator quote ATA
+    #[account(mut, address = policy.creator_quote_ata)]
+    pub creator_quote_ata: UncheckedAccount<'info>,
     /// CHECK: DAMM pool account
-    #[account(mut)]
-    pub damm_pool: UncheckedAccount<'info>,
-    /// CHECK: DAMM pool authority PDA
+    #[account(mut, address = policy.pool)]
+    pub pool: UncheckedAccount<'info>,
+    /// CHECK: DAMM pool authority
+    #[account(address = policy.pool_authority)]
     pub pool_authority: UncheckedAccount<'info>,
+    /// CHECK: DAMM position account
+    #[account(mut, address = policy.position)]
+    pub position: UncheckedAccount<'info>,
+    /// CHECK: Account is constrained to the policy's configured position NFT token account
+    #[account(mut, address = policy.position_nft_account)]
+    pub position_nft_account: UncheckedAccount<'info>,
+    /// CHECK: Account is constrained to the policy's configured base vault
+    #[account(mut, address = policy.base_vault)]
+    pub base_vault: UncheckedAccount<'info>,
+    /// CHECK: Account is constrained to the policy's configured quote vault
+    #[account(mut, address = policy.quote_vault)]
+    pub quote_vault: UncheckedAccount<'info>,
+    /// CHECK: Account address is enforced via the policy
+    pub base_mint: UncheckedAccount<'info>,
+    /// CHECK: Account address is enforced via the policy
+    pub quote_mint: UncheckedAccount<'info>,
+    /// CHECK: DAMM event authority
+    pub damm_event_authority: UncheckedAccount<'info>,
     /// CHECK: DAMM program id
-    pub damm_program: UncheckedAccount<'info>,
-    pub quote_mint: Account<'info, Mint>,
-    pub base_mint: Account<'info, Mint>,
+    #[account(address = policy.cp_amm_program)]
+    pub cp_amm_program: UncheckedAccount<'info>,
+    /// CHECK: Token A program
+    pub token_program_a: UncheckedAccount<'info>,
+    /// CHECK: Token B program
+    pub token_program_b: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    /// CHECK: Required when the policy has a day history; constrained and updated manually
     #[account(mut)]
-    pub quote_vault: Account<'info, TokenAccount>,
+    pub day_history: Option<UncheckedAccount<'info>>,
+    /// Required when the policy belongs to a group
     #[account(mut)]
-    pub base_vault: Account<'info, TokenAccount>,
+    pub policy_group: Option<Box<Account<'info, PolicyGroup>>>,
+    /// Required when the policy has a blocklist
+    pub blocklist: Option<Box<Account<'info, Blocklist>>>,
+    /// Receives forfeited payouts; required alongside `blocklist`
     #[account(mut)]
-    pub creator_quote_ata: Account<'info, TokenAccount>,
-    pub system_program: Program<'info, System>,
+    pub forfeiture_ata: Option<Box<Account<'info, TokenAccount>>>,
+    /// Required when the policy restricts cranking
+    pub cranker_allowlist: Option<Box<Account<'info, CrankerAllowlist>>>,
+    /// CHECK: Must match `policy.distribution_hook`; invoked on the last page
+    pub distribution_hook_program: Option<UncheckedAccount<'info>>,
+    /// Required on the last page when the policy vests creator proceeds
+    #[account(mut)]
+    pub creator_escrow: Option<Box<Account<'info, CreatorEscrow>>>,
+    /// CHECK: Must match `creator_escrow.vault`; checked when the creator is paid
+    #[account(mut)]
+    pub creator_escrow_vault: Option<UncheckedAccount<'info>>,
+    /// CHECK: `["hook", policy]` PDA that signs the hook call; checked when invoked
+    pub distribution_hook_authority: Option<UncheckedAccount<'info>>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-pub struct ConfigureHonoraryPosition<'info> {
+pub struct InitializePolicyGroup<'info> {
     #[account(mut)]
+    pub payer: Signer<'info>,
     pub authority: Signer<'info>,
     #[account(mut, has_one = authority)]
     pub policy: Account<'info, Policy>,
     #[account(
         init,
-        payer = authority,
-        space = HonoraryPosition::LEN,
-        seeds = [HONORARY_POSITION_SEED, policy.key().as_ref()],
+        payer = payer,
+        space = PolicyGroup::LEN,
+        seeds = [POLICY_GROUP_SEED, policy.key().as_ref()],
         bump,
     )]
-    pub honorary_position: Account<'info, HonoraryPosition>,
-    /// CHECK: Existing DAMM position account
-    #[account(mut)]
-    pub position: UncheckedAccount<'info>,
-    pub position_nft_mint: Account<'info, Mint>,
-    #[account(mut)]
-    pub position_nft_account: Account<'info, TokenAccount>,
-    #[account(address = policy.quote_mint)]
-    pub quote_mint: Account<'info, Mint>,
-    #[account(
-        init_if_needed,
-        payer = authority,
-        associated_token::mint = quote_mint,
-        associated_token::authority = honorary_position,
-    )]
-    pub quote_treasury: Account<'info, TokenAccount>,
-    #[account(address = policy.base_mint)]
-    pub base_mint: Account<'info, Mint>,
+    pub policy_group: Box<Account<'info, PolicyGroup>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AddPolicyToGroup<'info> {
+    pub authority: Signer<'info>,
     #[account(
-        init_if_needed,
-        payer = authority,
-        associated_token::mint = base_mint,
-        associated_token::authority = honorary_position,
+        mut,
+        seeds = [POLICY_GROUP_SEED, primary_policy.key().as_ref()],
+        bump = policy_group.bump,
     )]
-    pub base_fee_check: Account<'info, TokenAccount>,
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub policy_group: Box<Account<'info, PolicyGroup>>,
+    #[account(has_one = authority, address = policy_group.primary_policy)]
+    pub primary_policy: Account<'info, Policy>,
+    #[account(mut, has_one = authority)]
+    pub member_policy: Account<'info, Policy>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-pub struct CrankQuoteFeeDistribution<'info> {
+pub struct ClaimGroupMemberFees<'info> {
     /// CHECK: Only used to ensure a signature is present
     #[account(signer)]
     pub cranker: UncheckedAccount<'info>,
-    #[account(mut)]
-    pub policy: Account<'info, Policy>,
+    #[account(mut, address = policy.group)]
+    pub policy_group: Box<Account<'info, PolicyGroup>>,
+    pub policy: Box<Account<'info, Policy>>,
     #[account(
-        mut,
         seeds = [HONORARY_POSITION_SEED, policy.key().as_ref()],
         bump = honorary_position.bump,
     )]
     pub honorary_position: Account<'info, HonoraryPosition>,
-    /// CHECK: Progress account is constrained by seeds and updated manually
-    #[account(mut, seeds = [PROGRESS_SEED, policy.pool.as_ref()], bump)]
-    pub progress: UncheckedAccount<'info>,
-    /// CHECK: Account is constrained to the policy's configured quote treasury
+    /// CHECK: Account is constrained to the member policy's quote treasury
     #[account(mut, address = policy.quote_treasury)]
     pub quote_treasury: UncheckedAccount<'info>,
-    /// CHECK: Account is constrained to the policy's configured base fee check treasury
+    /// CHECK: Account is constrained to the member policy's base fee check treasury
     #[account(mut, address = policy.base_fee_check)]
     pub base_fee_check: UncheckedAccount<'info>,
-    /// CHECK: Account is constrained to the policy's configured creator quote ATA
-    #[account(mut, address = policy.creator_quote_ata)]
-    pub creator_quote_ata: UncheckedAccount<'info>,
+    /// CHECK: Account is constrained to the group's quote treasury
+    #[account(mut, address = policy_group.quote_treasury)]
+    pub group_quote_treasury: UncheckedAccount<'info>,
     /// CHECK: DAMM pool account
-    #[account(address = policy.pool)]
+    #[account(mut, address = policy.pool)]
     pub pool: UncheckedAccount<'info>,
     /// CHECK: DAMM pool authority
     #[account(address = policy.pool_authority)]
@@ -791,11 +2762,13 @@ pub struct CrankQuoteFeeDistribution<'info> {
     #[account(mut, address = policy.quote_vault)]
     pub quote_vault: UncheckedAccount<'info>,
     /// CHECK: Account address is enforced via the policy
+    #[account(address = policy.base_mint)]
     pub base_mint: UncheckedAccount<'info>,
     /// CHECK: Account address is enforced via the policy
+    #[account(address = policy.quote_mint)]
     pub quote_mint: UncheckedAccount<'info>,
     /// CHECK: DAMM event authority
-    pub event_authority: UncheckedAccount<'info>,
+    pub damm_event_authority: UncheckedAccount<'info>,
     /// CHECK: DAMM program id
     #[account(address = policy.cp_amm_program)]
     pub cp_amm_program: UncheckedAccount<'info>,
@@ -805,3 +2778,260 @@ pub struct CrankQuoteFeeDistribution<'info> {
     pub token_program_b: UncheckedAccount<'info>,
     pub token_program: Program<'info, Token>,
 }
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DepositQuoteForDistribution<'info> {
+    pub depositor: Signer<'info>,
+    pub policy: Account<'info, Policy>,
+    #[account(
+        mut,
+        seeds = [
+            PROGRESS_SEED,
+            policy.pool.as_ref(),
+            policy_index_seed(policy.policy_index).as_slice(),
+        ],
+        bump,
+        has_one = policy,
+    )]
+    pub progress: Account<'info, DistributionProgress>,
+    /// CHECK: Source token account; mint and authority are enforced by the token program
+    #[account(mut)]
+    pub depositor_quote_account: UncheckedAccount<'info>,
+    /// CHECK: Account is constrained to the policy's configured quote treasury
+    #[account(mut, address = policy.quote_treasury)]
+    pub quote_treasury: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    /// Required when the policy belongs to a group
+    pub policy_group: Option<Box<Account<'info, PolicyGroup>>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ForceCloseDay<'info> {
+    /// Policy authority, or anyone once the grace period has elapsed
+    pub caller: Signer<'info>,
+    #[account(mut)]
+    pub policy: Account<'info, Policy>,
+    #[account(
+        seeds = [HONORARY_POSITION_SEED, policy.key().as_ref()],
+        bump = honorary_position.bump,
+    )]
+    pub honorary_position: Account<'info, HonoraryPosition>,
+    #[account(
+        mut,
+        seeds = [
+            PROGRESS_SEED,
+            policy.pool.as_ref(),
+            policy_index_seed(policy.policy_index).as_slice(),
+        ],
+        bump,
+        has_one = policy,
+    )]
+    pub progress: Account<'info, DistributionProgress>,
+    #[account(mut, address = policy.quote_treasury)]
+    pub quote_treasury: Account<'info, TokenAccount>,
+    #[account(mut, address = policy.creator_quote_ata)]
+    pub creator_quote_ata: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    /// CHECK: Required when the policy has a day history; constrained and updated manually
+    #[account(mut)]
+    pub day_history: Option<UncheckedAccount<'info>>,
+    /// Required when the policy vests creator proceeds
+    #[account(mut)]
+    pub creator_escrow: Option<Box<Account<'info, CreatorEscrow>>>,
+    #[account(mut)]
+    pub creator_escrow_vault: Option<Box<Account<'info, TokenAccount>>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RescueTokens<'info> {
+    pub authority: Signer<'info>,
+    #[account(has_one = authority)]
+    pub policy: Account<'info, Policy>,
+    #[account(
+        seeds = [HONORARY_POSITION_SEED, policy.key().as_ref()],
+        bump = honorary_position.bump,
+    )]
+    pub honorary_position: Account<'info, HonoraryPosition>,
+    #[account(
+        seeds = [
+            PROGRESS_SEED,
+            policy.pool.as_ref(),
+            policy_index_seed(policy.policy_index).as_slice(),
+        ],
+        bump,
+        has_one = policy,
+    )]
+    pub progress: Account<'info, DistributionProgress>,
+    /// Any token account owned by the honorary PDA
+    #[account(mut, token::authority = honorary_position)]
+    pub source: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = source.mint)]
+    pub destination: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    /// Required when rescuing from the treasury of a grouped policy
+    pub policy_group: Option<Box<Account<'info, PolicyGroup>>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct QueuePolicyChange<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(has_one = authority)]
+    pub policy: Account<'info, Policy>,
+    #[account(
+        init,
+        payer = authority,
+        space = PendingPolicyChange::LEN,
+        seeds = [POLICY_CHANGE_SEED, policy.key().as_ref()],
+        bump,
+    )]
+    pub pending_change: Box<Account<'info, PendingPolicyChange>>,
+    /// Required when the change replaces the creator quote account
+    pub new_creator_quote_ata: Option<Box<Account<'info, TokenAccount>>>,
+    /// Required when the change routes creator payouts through the escrow
+    #[account(
+        seeds = [CREATOR_ESCROW_SEED, policy.key().as_ref()],
+        bump = creator_escrow.bump,
+        has_one = policy,
+    )]
+    pub creator_escrow: Option<Box<Account<'info, CreatorEscrow>>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecutePolicyChange<'info> {
+    /// Anyone may execute a change once its timelock has elapsed
+    pub executor: Signer<'info>,
+    /// CHECK: Receives the pending change rent; constrained to the policy authority
+    #[account(mut, address = policy.authority)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub policy: Account<'info, Policy>,
+    #[account(
+        seeds = [
+            PROGRESS_SEED,
+            policy.pool.as_ref(),
+            policy_index_seed(policy.policy_index).as_slice(),
+        ],
+        bump,
+        has_one = policy,
+    )]
+    pub progress: Account<'info, DistributionProgress>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [POLICY_CHANGE_SEED, policy.key().as_ref()],
+        bump = pending_change.bump,
+        has_one = policy,
+    )]
+    pub pending_change: Box<Account<'info, PendingPolicyChange>>,
+    /// Required when the change routes creator payouts through the escrow
+    #[account(
+        seeds = [CREATOR_ESCROW_SEED, policy.key().as_ref()],
+        bump = creator_escrow.bump,
+        has_one = policy,
+    )]
+    pub creator_escrow: Option<Box<Account<'info, CreatorEscrow>>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelPolicyChange<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(has_one = authority)]
+    pub policy: Account<'info, Policy>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [POLICY_CHANGE_SEED, policy.key().as_ref()],
+        bump = pending_change.bump,
+        has_one = policy,
+    )]
+    pub pending_change: Box<Account<'info, PendingPolicyChange>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitializeCreatorEscrow<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(has_one = authority)]
+    pub policy: Account<'info, Policy>,
+    #[account(
+        init,
+        payer = payer,
+        space = CreatorEscrow::LEN,
+        seeds = [CREATOR_ESCROW_SEED, policy.key().as_ref()],
+        bump,
+    )]
+    pub creator_escrow: Box<Account<'info, CreatorEscrow>>,
+    #[account(address = policy.quote_mint)]
+    pub quote_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = quote_mint,
+        associated_token::authority = creator_escrow,
+    )]
+    pub escrow_vault: Box<Account<'info, TokenAccount>>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawCreatorVested<'info> {
+    pub policy: Account<'info, Policy>,
+    #[account(
+        mut,
+        seeds = [CREATOR_ESCROW_SEED, policy.key().as_ref()],
+        bump = creator_escrow.bump,
+        has_one = policy,
+    )]
+    pub creator_escrow: Box<Account<'info, CreatorEscrow>>,
+    #[account(mut, address = creator_escrow.vault)]
+    pub escrow_vault: Account<'info, TokenAccount>,
+    #[account(mut, address = policy.creator_quote_ata)]
+    pub creator_quote_ata: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// Accounts created before fields were appended to `Policy` and `DistributionProgress` no
+/// longer deserialize, so both are taken unchecked at the original index-0 addresses.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigratePolicyAccounts<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    /// CHECK: policy PDA of `damm_pool`; decoded and checked by the handler once extended
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [POLICY_SEED, damm_pool.key().as_ref()],
+        bump,
+    )]
+    pub policy: UncheckedAccount<'info>,
+    /// CHECK: progress PDA of `damm_pool`; decoded and checked by the handler once extended
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [PROGRESS_SEED, damm_pool.key().as_ref()],
+        bump,
+    )]
+    pub progress: UncheckedAccount<'info>,
+    /// CHECK: only used as a seed and compared with `policy.pool`
+    pub damm_pool: UncheckedAccount<'info>,
+    pub quote_mint: Account<'info, Mint>,
+    /// Mint investors' Streamflow streams lock; usually the base or quote mint.
+    pub vesting_mint: Account<'info, Mint>,
+    pub system_program: Program<'info, System>,
+}
@@ -1,24 +1,51 @@
+//! Anchor-facing wrappers over the `honorary_payout` math, mapping its errors onto
+//! `HonoraryQuoteFeeError::{ArithmeticOverflow, DivisionByZero}`.
+
 use anchor_lang::prelude::*;
+use honorary_payout::math as payout_math;
 
 use crate::errors::HonoraryQuoteFeeError;
 
+pub use honorary_payout::math::{saturating_sub_u64, split_evenly, Rounding, U256};
+
+/// Computes `a * b / denominator` with a 256-bit intermediate product.
+///
+/// Only fails when the denominator is zero or the rounded result exceeds `u128`.
+pub fn mul_div_u128(a: u128, b: u128, denominator: u128, rounding: Rounding) -> Result<u128> {
+    Ok(payout_math::mul_div_u128(a, b, denominator, rounding)
+        .map_err(HonoraryQuoteFeeError::from)?)
+}
+
 pub fn mul_div_floor_u128(a: u128, b: u128, denominator: u128) -> Result<u128> {
-    require!(denominator != 0, HonoraryQuoteFeeError::ArithmeticOverflow);
-    let product = a
-        .checked_mul(b)
-        .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?;
-    Ok(product
-        .checked_div(denominator)
-        .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?)
+    mul_div_u128(a, b, denominator, Rounding::Floor)
+}
+
+pub fn mul_div_ceil_u128(a: u128, b: u128, denominator: u128) -> Result<u128> {
+    mul_div_u128(a, b, denominator, Rounding::Ceil)
+}
+
+pub fn mul_div_nearest_u128(a: u128, b: u128, denominator: u128) -> Result<u128> {
+    mul_div_u128(a, b, denominator, Rounding::Nearest)
+}
+
+/// Splits `total` across `weights` so that the shares sum to `total` exactly.
+///
+/// Every entry first receives `floor(total * weight / sum)`; the leftover units go one
+/// each to the entries with the largest remainders, ties broken by lowest index.
+/// Returns all zeros when every weight is zero.
+pub fn allocate_largest_remainder(total: u64, weights: &[u64]) -> Result<Vec<u64>> {
+    Ok(payout_math::allocate_largest_remainder(total, weights)
+        .map_err(HonoraryQuoteFeeError::from)?)
 }
 
 pub fn u128_to_u64(value: u128) -> Result<u64> {
-    if value > u64::MAX as u128 {
-        return err!(HonoraryQuoteFeeError::ArithmeticOverflow);
-    }
-    Ok(value as u64)
+    Ok(payout_math::u128_to_u64(value).map_err(HonoraryQuoteFeeError::from)?)
 }
 
-pub fn saturating_sub_u64(lhs: u64, rhs: u64) -> u64 {
-    lhs.saturating_sub(rhs)
+/// Converts `amount` whole tokens of a mint with `decimals` into base units.
+pub fn whole_tokens_to_base_units(amount: u64, decimals: u8) -> Result<u64> {
+    10u64
+        .checked_pow(u32::from(decimals))
+        .and_then(|scale| amount.checked_mul(scale))
+        .ok_or_else(|| error!(HonoraryQuoteFeeError::ArithmeticOverflow))
 }
@@ -1,14 +1,230 @@
 use anchor_lang::prelude::*;
 
+use crate::{
+    errors::HonoraryQuoteFeeError,
+    math::{mul_div_floor_u128, saturating_sub_u64, u128_to_u64},
+    DAY_SECONDS,
+};
+
+pub use honorary_payout::{EligibilityCurveKind, IneligibilityReason, MAX_CURVE_BREAKPOINTS};
+
 pub const POLICY_SEED: &[u8] = b"policy";
 pub const HONORARY_POSITION_SEED: &[u8] = b"honorary";
 pub const PROGRESS_SEED: &[u8] = b"progress";
+pub const DAY_HISTORY_SEED: &[u8] = b"history";
+pub const POLICY_GROUP_SEED: &[u8] = b"group";
+pub const BLOCKLIST_SEED: &[u8] = b"blocklist";
+pub const CRANKER_ALLOWLIST_SEED: &[u8] = b"crankers";
+pub const POLICY_CHANGE_SEED: &[u8] = b"change";
+pub const CREATOR_ESCROW_SEED: &[u8] = b"creator_escrow";
+/// Signs distribution hook calls; owns nothing, unlike the honorary PDA.
+pub const HOOK_AUTHORITY_SEED: &[u8] = b"hook";
+
+pub const DAY_HISTORY_CAPACITY: usize = 32;
+pub const MAX_GROUP_MEMBERS: usize = 8;
+pub const MAX_BLOCKLIST_ENTRIES: usize = 32;
+pub const MAX_CRANKERS: usize = 8;
+pub const MAX_VESTING_DURATION_SECONDS: i64 = 366 * DAY_SECONDS;
+/// Creator escrow deposits start vesting on one of this many boundaries per vesting
+/// duration, so deposits close together share a tranche.
+pub const VESTING_BUCKETS_PER_DURATION: i64 = 30;
+/// Live tranches start on distinct boundaries no more than one duration plus one bucket
+/// apart, so there are at most `VESTING_BUCKETS_PER_DURATION + 1` of them. Kept small so
+/// `CreatorEscrow` deserializes well inside the 4 KB SBF stack frame.
+pub const MAX_VESTING_TRANCHES: usize = 32;
 
 pub struct PolicyStatus;
 impl PolicyStatus {
     pub const HONORARY_READY: u8 = 1u8;
 }
 
+pub struct FeeCaptureMode;
+impl FeeCaptureMode {
+    pub const POSITION_FEES: u8 = 1u8;
+    pub const PARTNER_FEES: u8 = 1u8 << 1;
+    pub const ALL: u8 = Self::POSITION_FEES | Self::PARTNER_FEES;
+}
+
+pub struct ForceCloseDestination;
+impl ForceCloseDestination {
+    pub const CARRY: u8 = 0u8;
+    pub const CREATOR: u8 = 1u8;
+}
+
+#[derive(
+    AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, Debug, PartialEq, Eq,
+)]
+#[repr(C)]
+pub struct CurveBreakpoint {
+    /// Locked fraction of `y0`, in basis points.
+    pub locked_bps: u16,
+    /// Investor share once `locked_bps` is reached, in basis points.
+    pub share_bps: u16,
+}
+
+/// Per-stream thresholds a Streamflow contract must meet to earn fees; zero disables a rule.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, Debug, PartialEq, Eq,
+)]
+#[repr(C)]
+pub struct EligibilityRules {
+    /// Seconds left until the stream's schedule `end_time`.
+    pub min_remaining_lock_seconds: u64,
+    /// Seconds elapsed since the stream's `start_time`.
+    pub min_stream_age_seconds: u64,
+    pub min_locked_amount: u64,
+}
+
+/// Maps the locked fraction of `y0` to the eligible investor share.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, Debug, PartialEq, Eq,
+)]
+#[repr(C)]
+pub struct EligibilityCurve {
+    pub kind: u8,
+    pub breakpoint_count: u8,
+    pub breakpoints: [CurveBreakpoint; MAX_CURVE_BREAKPOINTS],
+}
+
+impl EligibilityCurve {
+    /// Breakpoint tables must be strictly increasing in `locked_bps` and non-decreasing
+    /// in `share_bps` so the curve stays monotone; linear and convex curves take none.
+    pub fn validate(&self) -> Result<()> {
+        let count = self.breakpoint_count as usize;
+        match self.kind {
+            EligibilityCurveKind::LINEAR | EligibilityCurveKind::CONVEX => {
+                require!(count == 0, HonoraryQuoteFeeError::InvalidEligibilityCurve);
+            }
+            EligibilityCurveKind::STEP | EligibilityCurveKind::PIECEWISE_LINEAR => {
+                require!(
+                    count > 0 && count <= MAX_CURVE_BREAKPOINTS,
+                    HonoraryQuoteFeeError::InvalidEligibilityCurve
+                );
+            }
+            _ => return err!(HonoraryQuoteFeeError::InvalidEligibilityCurve),
+        }
+
+        let mut previous = CurveBreakpoint::default();
+        for (index, point) in self.active_breakpoints().iter().enumerate() {
+            require!(
+                point.locked_bps <= 10_000 && point.share_bps <= 10_000,
+                HonoraryQuoteFeeError::InvalidEligibilityCurve
+            );
+            require!(
+                (index == 0 || point.locked_bps > previous.locked_bps)
+                    && point.share_bps >= previous.share_bps,
+                HonoraryQuoteFeeError::InvalidEligibilityCurve
+            );
+            previous = *point;
+        }
+        Ok(())
+    }
+
+    pub fn active_breakpoints(&self) -> &[CurveBreakpoint] {
+        let count = (self.breakpoint_count as usize).min(MAX_CURVE_BREAKPOINTS);
+        &self.breakpoints[..count]
+    }
+}
+
+impl From<CurveBreakpoint> for honorary_payout::CurveBreakpoint {
+    fn from(point: CurveBreakpoint) -> Self {
+        Self {
+            locked_bps: point.locked_bps,
+            share_bps: point.share_bps,
+        }
+    }
+}
+
+impl From<EligibilityCurve> for honorary_payout::EligibilityCurve {
+    fn from(curve: EligibilityCurve) -> Self {
+        Self {
+            kind: curve.kind,
+            breakpoint_count: curve.breakpoint_count,
+            breakpoints: curve.breakpoints.map(Into::into),
+        }
+    }
+}
+
+impl From<EligibilityRules> for honorary_payout::EligibilityRules {
+    fn from(rules: EligibilityRules) -> Self {
+        Self {
+            min_remaining_lock_seconds: rules.min_remaining_lock_seconds,
+            min_stream_age_seconds: rules.min_stream_age_seconds,
+            min_locked_amount: rules.min_locked_amount,
+        }
+    }
+}
+
+/// Linear release of each escrowed creator deposit from its tranche start (see
+/// `tranche_start`): nothing before `cliff_seconds` have passed, everything
+/// once `duration_seconds` have.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, Debug, PartialEq, Eq,
+)]
+#[repr(C)]
+pub struct CreatorVestingSchedule {
+    pub start_ts: i64,
+    pub cliff_seconds: i64,
+    pub duration_seconds: i64,
+}
+
+impl CreatorVestingSchedule {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.start_ts >= 0
+                && self.duration_seconds > 0
+                && self.duration_seconds <= MAX_VESTING_DURATION_SECONDS
+                && self.cliff_seconds >= 0
+                && self.cliff_seconds <= self.duration_seconds,
+            HonoraryQuoteFeeError::InvalidVestingSchedule
+        );
+        Ok(())
+    }
+
+    /// Spacing of the boundaries tranches start on, counted from `start_ts`.
+    pub fn bucket_seconds(&self) -> i64 {
+        ((self.duration_seconds + VESTING_BUCKETS_PER_DURATION - 1) / VESTING_BUCKETS_PER_DURATION)
+            .max(1)
+    }
+
+    /// Timestamp a deposit made at `deposit_ts` starts vesting from: `start_ts`, or the
+    /// first bucket boundary at or after the deposit.
+    pub fn tranche_start(&self, deposit_ts: i64) -> i64 {
+        if deposit_ts <= self.start_ts {
+            return self.start_ts;
+        }
+        let bucket = self.bucket_seconds();
+        let buckets = (deposit_ts - self.start_ts).saturating_add(bucket - 1) / bucket;
+        self.start_ts.saturating_add(buckets.saturating_mul(bucket))
+    }
+
+    /// Portion of a tranche of `amount` starting at `tranche_start_ts` released at `now_ts`.
+    pub fn vested_quote(&self, amount: u64, tranche_start_ts: i64, now_ts: i64) -> Result<u64> {
+        let elapsed = now_ts.saturating_sub(tranche_start_ts);
+        if elapsed <= 0 || elapsed < self.cliff_seconds {
+            return Ok(0);
+        }
+        if elapsed >= self.duration_seconds {
+            return Ok(amount);
+        }
+        u128_to_u64(mul_div_floor_u128(
+            u128::from(amount),
+            elapsed as u128,
+            self.duration_seconds as u128,
+        )?)
+    }
+}
+
+/// Creator quote deposited at one day close, vesting from `start_ts`.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, Debug, PartialEq, Eq,
+)]
+#[repr(C)]
+pub struct VestingTranche {
+    pub start_ts: i64,
+    pub amount: u64,
+}
+
 #[account]
 #[derive(InitSpace)]
 #[repr(C)]
@@ -34,6 +250,36 @@ pub struct Policy {
     pub investor_fee_share_bps: u16,
     pub bump: u8,
     pub status: u8,
+    // Fields below were appended after the first deployment; `migrate_policy_accounts`
+    // extends accounts created before them.
+    /// Mint of the tokens investors lock in Streamflow; `y0` is in its base units.
+    pub vesting_mint: Pubkey,
+    pub day_history: Pubkey,
+    pub group: Pubkey,
+    pub blocklist: Pubkey,
+    pub cranker_allowlist: Pubkey,
+    pub distribution_hook: Pubkey,
+    pub creator_escrow: Pubkey,
+    pub force_close_grace_seconds: i64,
+    /// Minimum seconds between crank pages of an open day; `0` disables the limit.
+    pub min_page_interval_seconds: i64,
+    /// Delay between queueing and executing a policy change.
+    pub timelock_seconds: i64,
+    pub policy_index: u16,
+    pub reserve_retention_bps: u16,
+    pub eligibility_curve: EligibilityCurve,
+    pub eligibility_rules: EligibilityRules,
+    /// Only read once `creator_escrow` is set.
+    pub creator_vesting: CreatorVestingSchedule,
+    pub force_close_destination: u8,
+    pub fee_capture_mode: u8,
+    pub cap_reserve_enabled: bool,
+    pub hook_failure_mode: u8,
+    /// Pages must match an investor snapshot committed when the day opens.
+    pub investor_snapshot_required: bool,
+    /// `daily_cap_quote` and `min_payout_lamports` are in quote base units.
+    pub quote_decimals: u8,
+    pub vesting_decimals: u8,
 }
 
 impl Policy {
@@ -63,8 +309,356 @@ pub struct DistributionProgress {
     pub day_start_ts: i64,
     pub page_cursor: u32,
     pub day_open: bool,
+    // Fields below were appended after the first deployment; `migrate_policy_accounts`
+    // extends accounts created before them.
+    pub target_investor_quote: u64,
+    pub pending_deposit_quote: u64,
+    /// Investor quote held in the treasury by the cap reserve.
+    pub reserve_quote: u64,
+    /// Commitment to the open day's remaining pages when the policy requires investor
+    /// snapshots; all zeros otherwise.
+    pub investor_snapshot: [u8; 32],
+    pub last_page_ts: i64,
+    /// Logical days covered by the open day (greater than one after missed cranks).
+    pub day_count: u32,
+    pub share_bps: u16,
 }
 
 impl DistributionProgress {
     pub const LEN: usize = 8 + core::mem::size_of::<Self>();
 }
+
+#[derive(
+    AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, Debug, PartialEq, Eq,
+)]
+#[repr(C)]
+pub struct DaySummary {
+    pub day_start_ts: i64,
+    pub claimed_quote: u64,
+    pub investor_quote_paid: u64,
+    pub creator_quote_paid: u64,
+    pub carry_quote: u64,
+    pub investor_count: u32,
+    pub share_bps: u16,
+}
+
+/// Ring buffer of the most recently closed days plus lifetime totals.
+#[account]
+#[derive(InitSpace)]
+#[repr(C)]
+pub struct DayHistory {
+    pub policy: Pubkey,
+    pub lifetime_claimed_quote: u64,
+    pub lifetime_investor_quote_paid: u64,
+    pub lifetime_creator_quote_paid: u64,
+    pub days_recorded: u64,
+    pub entries: [DaySummary; DAY_HISTORY_CAPACITY],
+    pub bump: u8,
+}
+
+impl DayHistory {
+    pub const LEN: usize = 8 + core::mem::size_of::<Self>();
+
+    pub fn push(&mut self, summary: DaySummary) -> Result<()> {
+        let slot = (self.days_recorded % DAY_HISTORY_CAPACITY as u64) as usize;
+        self.entries[slot] = summary;
+        self.days_recorded = self
+            .days_recorded
+            .checked_add(1)
+            .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?;
+        self.lifetime_claimed_quote = self
+            .lifetime_claimed_quote
+            .checked_add(summary.claimed_quote)
+            .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?;
+        self.lifetime_investor_quote_paid = self
+            .lifetime_investor_quote_paid
+            .checked_add(summary.investor_quote_paid)
+            .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?;
+        self.lifetime_creator_quote_paid = self
+            .lifetime_creator_quote_paid
+            .checked_add(summary.creator_quote_paid)
+            .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Most recent entries, newest first.
+    pub fn recent(&self) -> impl Iterator<Item = &DaySummary> {
+        let stored = self.days_recorded.min(DAY_HISTORY_CAPACITY as u64) as usize;
+        let newest = self.days_recorded as usize;
+        (1..=stored).map(move |age| &self.entries[(newest - age) % DAY_HISTORY_CAPACITY])
+    }
+}
+
+/// Pools sharing a quote mint whose fees are distributed by one primary policy.
+///
+/// Member policies forward their claimed quote into the primary treasury, where it
+/// is counted into the primary's `claimed_quote` under its cap, `y0` and investors.
+#[account]
+#[derive(InitSpace)]
+#[repr(C)]
+pub struct PolicyGroup {
+    pub primary_policy: Pubkey,
+    pub quote_mint: Pubkey,
+    pub quote_treasury: Pubkey,
+    pub members: [Pubkey; MAX_GROUP_MEMBERS],
+    pub pending_quote: u64,
+    pub member_count: u8,
+    pub claimed_mask: u8,
+    pub bump: u8,
+}
+
+impl PolicyGroup {
+    pub const LEN: usize = 8 + core::mem::size_of::<Self>();
+
+    pub fn member_index(&self, policy: &Pubkey) -> Option<usize> {
+        self.members[..self.member_count as usize]
+            .iter()
+            .position(|member| member == policy)
+    }
+
+    pub fn all_members_claimed(&self) -> bool {
+        let full_mask = ((1u16 << self.member_count) - 1) as u8;
+        self.claimed_mask & full_mask == full_mask
+    }
+}
+
+/// Recipients whose investor payouts are redirected to `forfeiture_ata`.
+#[account]
+#[derive(InitSpace)]
+#[repr(C)]
+pub struct Blocklist {
+    pub policy: Pubkey,
+    pub forfeiture_ata: Pubkey,
+    pub recipients: [Pubkey; MAX_BLOCKLIST_ENTRIES],
+    pub recipient_count: u8,
+    pub bump: u8,
+}
+
+impl Blocklist {
+    pub const LEN: usize = 8 + core::mem::size_of::<Self>();
+
+    pub fn contains(&self, recipient: &Pubkey) -> bool {
+        self.recipients[..self.recipient_count as usize].contains(recipient)
+    }
+
+    /// Adds `recipient`; already-listed recipients are left as is.
+    pub fn insert(&mut self, recipient: Pubkey) -> Result<()> {
+        if self.contains(&recipient) {
+            return Ok(());
+        }
+        let slot = self.recipient_count as usize;
+        require!(
+            slot < MAX_BLOCKLIST_ENTRIES,
+            HonoraryQuoteFeeError::BlocklistFull
+        );
+        self.recipients[slot] = recipient;
+        self.recipient_count = self
+            .recipient_count
+            .checked_add(1)
+            .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Removes `recipient` by swapping the last entry into its slot.
+    pub fn remove(&mut self, recipient: &Pubkey) -> Result<()> {
+        let count = self.recipient_count as usize;
+        let index = self.recipients[..count]
+            .iter()
+            .position(|listed| listed == recipient)
+            .ok_or(HonoraryQuoteFeeError::RecipientNotBlocked)?;
+        self.recipients[index] = self.recipients[count - 1];
+        self.recipients[count - 1] = Pubkey::default();
+        self.recipient_count -= 1;
+        Ok(())
+    }
+}
+
+/// Keepers allowed to crank the policy while `permissionless` is off.
+#[account]
+#[derive(InitSpace)]
+#[repr(C)]
+pub struct CrankerAllowlist {
+    pub policy: Pubkey,
+    pub crankers: [Pubkey; MAX_CRANKERS],
+    pub cranker_count: u8,
+    pub permissionless: bool,
+    pub bump: u8,
+}
+
+impl CrankerAllowlist {
+    pub const LEN: usize = 8 + core::mem::size_of::<Self>();
+
+    pub fn permits(&self, cranker: &Pubkey) -> bool {
+        self.permissionless || self.crankers[..self.cranker_count as usize].contains(cranker)
+    }
+
+    pub fn set_allowed(&mut self, cranker: Pubkey, allowed: bool) -> Result<()> {
+        let count = self.cranker_count as usize;
+        let existing = self.crankers[..count]
+            .iter()
+            .position(|listed| *listed == cranker);
+        match (existing, allowed) {
+            (Some(_), true) | (None, false) => {}
+            (None, true) => {
+                require!(
+                    count < MAX_CRANKERS,
+                    HonoraryQuoteFeeError::CrankerAllowlistFull
+                );
+                self.crankers[count] = cranker;
+                self.cranker_count += 1;
+            }
+            (Some(index), false) => {
+                self.crankers[index] = self.crankers[count - 1];
+                self.crankers[count - 1] = Pubkey::default();
+                self.cranker_count -= 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Creator day-close transfers held for vesting under `Policy::creator_vesting`, one
+/// tranche per deposit.
+#[account]
+#[derive(InitSpace)]
+#[repr(C)]
+pub struct CreatorEscrow {
+    pub policy: Pubkey,
+    /// Quote ATA owned by this escrow.
+    pub vault: Pubkey,
+    pub total_deposited: u64,
+    pub total_withdrawn: u64,
+    /// Deposits whose tranche has fully vested and was dropped from `tranches`.
+    pub matured_quote: u64,
+    /// Tranches still vesting, oldest first; only the first `tranche_count` are set.
+    pub tranches: [VestingTranche; MAX_VESTING_TRANCHES],
+    pub tranche_count: u16,
+    pub bump: u8,
+}
+
+impl CreatorEscrow {
+    pub const LEN: usize = 8 + core::mem::size_of::<Self>();
+
+    /// Books `amount` deposited at `deposit_ts` as a tranche vesting from that time.
+    pub fn record_deposit(
+        &mut self,
+        schedule: &CreatorVestingSchedule,
+        deposit_ts: i64,
+        amount: u64,
+    ) -> Result<()> {
+        self.total_deposited = self
+            .total_deposited
+            .checked_add(amount)
+            .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?;
+        if amount == 0 {
+            return Ok(());
+        }
+        self.release_matured(schedule, deposit_ts)?;
+
+        let start_ts = schedule.tranche_start(deposit_ts);
+        let count = self.tranche_count as usize;
+        match self.tranches[..count].last_mut() {
+            Some(last) if last.start_ts == start_ts => {
+                last.amount = last
+                    .amount
+                    .checked_add(amount)
+                    .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?;
+            }
+            _ => {
+                require!(
+                    count < MAX_VESTING_TRANCHES,
+                    HonoraryQuoteFeeError::CreatorEscrowFull
+                );
+                self.tranches[count] = VestingTranche { start_ts, amount };
+                self.tranche_count += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves fully vested tranches into `matured_quote`.
+    pub fn release_matured(
+        &mut self,
+        schedule: &CreatorVestingSchedule,
+        now_ts: i64,
+    ) -> Result<()> {
+        let count = self.tranche_count as usize;
+        let matured = self.tranches[..count]
+            .iter()
+            .take_while(|tranche| {
+                now_ts.saturating_sub(tranche.start_ts) >= schedule.duration_seconds
+            })
+            .count();
+        for tranche in &self.tranches[..matured] {
+            self.matured_quote = self
+                .matured_quote
+                .checked_add(tranche.amount)
+                .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?;
+        }
+        self.tranches.copy_within(matured..count, 0);
+        self.tranches[count - matured..count].fill(VestingTranche::default());
+        self.tranche_count -= matured as u16;
+        Ok(())
+    }
+
+    /// Vested quote not yet withdrawn.
+    pub fn withdrawable(&self, schedule: &CreatorVestingSchedule, now_ts: i64) -> Result<u64> {
+        let mut vested = self.matured_quote;
+        for tranche in &self.tranches[..self.tranche_count as usize] {
+            vested = vested
+                .checked_add(schedule.vested_quote(tranche.amount, tranche.start_ts, now_ts)?)
+                .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?;
+        }
+        Ok(saturating_sub_u64(vested, self.total_withdrawn))
+    }
+}
+
+/// Policy parameters a queued change may replace; `None` leaves a field unchanged.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PolicyChange {
+    pub investor_fee_share_bps: Option<u16>,
+    pub y0: Option<u64>,
+    pub creator_quote_ata: Option<Pubkey>,
+    /// Routes creator payouts through the policy's `CreatorEscrow` under this schedule.
+    /// Only allowed once, after `initialize_creator_escrow`.
+    pub creator_vesting: Option<CreatorVestingSchedule>,
+}
+
+impl PolicyChange {
+    pub fn is_empty(&self) -> bool {
+        self.investor_fee_share_bps.is_none()
+            && self.y0.is_none()
+            && self.creator_quote_ata.is_none()
+            && self.creator_vesting.is_none()
+    }
+
+    pub fn apply(&self, policy: &mut Policy) {
+        if let Some(share_bps) = self.investor_fee_share_bps {
+            policy.investor_fee_share_bps = share_bps;
+        }
+        if let Some(y0) = self.y0 {
+            policy.y0 = y0;
+        }
+        if let Some(creator_quote_ata) = self.creator_quote_ata {
+            policy.creator_quote_ata = creator_quote_ata;
+        }
+        if let Some(schedule) = self.creator_vesting {
+            policy.creator_vesting = schedule;
+        }
+    }
+}
+
+/// A single queued policy change awaiting its timelock.
+#[account]
+#[derive(InitSpace)]
+pub struct PendingPolicyChange {
+    pub policy: Pubkey,
+    pub change: PolicyChange,
+    pub queued_at: i64,
+    pub eta: i64,
+    pub bump: u8,
+}
+
+impl PendingPolicyChange {
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+}
@@ -1,33 +1,53 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::account_info::AccountInfo;
-use anchor_spl::token::TokenAccount;
+use anchor_lang::solana_program::{account_info::AccountInfo, hash::hashv};
+use anchor_spl::{associated_token::get_associated_token_address, token::TokenAccount};
 use streamflow_sdk::state::Contract;
 
-use crate::errors::HonoraryQuoteFeeError;
+use crate::{
+    errors::HonoraryQuoteFeeError,
+    state::{EligibilityCurve, EligibilityRules, IneligibilityReason},
+};
 
 pub struct InvestorEntry {
+    /// Payout weight; zero when the stream fails the policy's eligibility rules.
     pub locked_amount: u64,
     pub token_account_index: usize,
+    pub stream: Pubkey,
+    pub recipient: Pubkey,
+    pub ineligible_reason: u8,
+    /// Locked balance before eligibility rules were applied.
+    pub raw_locked_amount: u64,
+    /// See `investor_snapshot_leaf`.
+    pub snapshot_leaf: [u8; 32],
 }
 
 #[inline(never)]
+#[allow(clippy::too_many_arguments)]
 pub fn collect_investors<'info>(
     now: u64,
     accounts: &'info [AccountInfo<'info>],
+    expected_vesting_mint: Pubkey,
     expected_quote_mint: Pubkey,
     _policy_pool: Pubkey,
+    rules: &EligibilityRules,
+    max_investors: usize,
+    has_compute_for: impl Fn(usize) -> bool,
 ) -> Result<Vec<InvestorEntry>> {
     require!(
-        accounts.len() % 2 == 0,
+        accounts.len().checked_rem(2) == Some(0),
         HonoraryQuoteFeeError::InvalidInvestorAccount
     );
     let half = accounts
         .len()
         .checked_div(2)
         .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?;
-    let mut investors = Vec::with_capacity(half);
+    let mut investors = Vec::with_capacity(half.min(max_investors));
 
     for (chunk_idx, chunk) in accounts.chunks(2).enumerate() {
+        // Pairs past the limit are left for the next page; the first one always fits.
+        if chunk_idx == max_investors || (chunk_idx > 0 && !has_compute_for(chunk_idx + 1)) {
+            break;
+        }
         let stream_account = &chunk[0];
         let investor_token_account_info = &chunk[1];
 
@@ -40,11 +60,23 @@ pub fn collect_investors<'info>(
         let contract = load_stream_contract(stream_account)?;
         require_keys_eq!(
             Pubkey::new_from_array(contract.mint.to_bytes()),
-            expected_quote_mint,
+            expected_vesting_mint,
             HonoraryQuoteFeeError::StreamflowMintMismatch
         );
 
-        let locked = locked_amount(&contract, now)?;
+        let raw_locked = locked_amount(&contract, now)?;
+        let ineligible_reason = ineligibility_reason(
+            rules,
+            raw_locked,
+            contract.ix.start_time,
+            contract.end_time,
+            now,
+        );
+        let locked = if ineligible_reason == IneligibilityReason::NONE {
+            raw_locked
+        } else {
+            0
+        };
 
         let token_account: Account<TokenAccount> = Account::try_from(investor_token_account_info)?;
         require_keys_eq!(
@@ -60,8 +92,13 @@ pub fn collect_investors<'info>(
             HonoraryQuoteFeeError::InvestorAtaOwnerMismatch
         );
 
-        let expected_recipient_tokens =
-            Pubkey::new_from_array(contract.recipient_tokens.to_bytes());
+        // Streams of another mint pay into the recipient's quote ATA rather than the
+        // stream's own token account.
+        let expected_recipient_tokens = if expected_vesting_mint == expected_quote_mint {
+            Pubkey::new_from_array(contract.recipient_tokens.to_bytes())
+        } else {
+            get_associated_token_address(&expected_recipient, &expected_quote_mint)
+        };
         require_keys_eq!(
             token_account.key(),
             expected_recipient_tokens,
@@ -78,12 +115,73 @@ pub fn collect_investors<'info>(
         investors.push(InvestorEntry {
             locked_amount: locked,
             token_account_index: idx_usize,
+            stream: stream_account.key(),
+            recipient: expected_recipient,
+            ineligible_reason,
+            raw_locked_amount: raw_locked,
+            snapshot_leaf: investor_snapshot_leaf(
+                &stream_account.key(),
+                &expected_recipient,
+                contract.ix.net_amount_deposited,
+                contract.ix.amount_per_period,
+            ),
         });
     }
 
     Ok(investors)
 }
 
+/// Snapshot entry for one stream: who it pays and the deposit and rate that fix its
+/// schedule. Top-ups, rate updates and transfers change it; withdrawals do not.
+pub fn investor_snapshot_leaf(
+    stream: &Pubkey,
+    recipient: &Pubkey,
+    net_amount_deposited: u64,
+    amount_per_period: u64,
+) -> [u8; 32] {
+    hashv(&[
+        stream.as_ref(),
+        recipient.as_ref(),
+        &net_amount_deposited.to_le_bytes(),
+        &amount_per_period.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Hash of a page's snapshot leaves in page order.
+pub fn investor_page_digest(leaves: &[[u8; 32]]) -> [u8; 32] {
+    let slices: Vec<&[u8]> = leaves.iter().map(|leaf| leaf.as_slice()).collect();
+    hashv(&slices).to_bytes()
+}
+
+/// Commitment to a page followed by the pages committed to by `remaining`.
+pub fn investor_snapshot_link(page_digest: &[u8; 32], remaining: &[u8; 32]) -> [u8; 32] {
+    hashv(&[page_digest, remaining]).to_bytes()
+}
+
+/// Day-open commitment to every page of the day, in crank order. The last page links to
+/// all zeros.
+pub fn investor_snapshot_commitment(page_digests: &[[u8; 32]]) -> [u8; 32] {
+    page_digests
+        .iter()
+        .rev()
+        .fold([0u8; 32], |remaining, digest| {
+            investor_snapshot_link(digest, &remaining)
+        })
+}
+
+/// First eligibility rule a stream with `locked` tokens fails at `now`, or
+/// `IneligibilityReason::NONE`. Streams with nothing locked are not reported.
+pub fn ineligibility_reason(
+    rules: &EligibilityRules,
+    locked: u64,
+    start_time: u64,
+    end_time: u64,
+    now: u64,
+) -> u8 {
+    honorary_payout::ineligibility_reason(&(*rules).into(), locked, start_time, end_time, now)
+}
+
 #[inline(never)]
 pub fn load_stream_contract(account_info: &AccountInfo<'_>) -> Result<Contract> {
     let data = account_info.try_borrow_data()?;
@@ -93,25 +191,21 @@ pub fn load_stream_contract(account_info: &AccountInfo<'_>) -> Result<Contract>
 
 #[inline(never)]
 pub fn locked_amount(contract: &Contract, now: u64) -> Result<u64> {
-    let unlocked_now = contract
-        .amount_withdrawn
-        .checked_add(contract.available_to_claim(now, 100.0))
-        .ok_or(HonoraryQuoteFeeError::ArithmeticOverflow)?;
-    let unlocked_clamped = unlocked_now.min(contract.ix.net_amount_deposited);
-    Ok(contract
-        .ix
-        .net_amount_deposited
-        .saturating_sub(unlocked_clamped))
+    Ok(honorary_payout::locked_amount(
+        contract.ix.net_amount_deposited,
+        contract.amount_withdrawn,
+        contract.available_to_claim(now, 100.0),
+    )
+    .map_err(HonoraryQuoteFeeError::from)?)
 }
 
-pub fn eligible_share_bps(locked_total: u128, y0: u64, max_share_bps: u16) -> u16 {
-    if y0 == 0 || locked_total == 0 {
-        return 0;
-    }
-
-    let ratio = locked_total
-        .saturating_mul(10_000u128)
-        .checked_div(y0 as u128)
-        .unwrap_or(0);
-    ratio.min(max_share_bps as u128) as u16
+/// Investor share in basis points for `locked_total` out of `y0`, shaped by `curve` and
+/// clamped to `max_share_bps`.
+pub fn eligible_share_bps(
+    locked_total: u128,
+    y0: u64,
+    max_share_bps: u16,
+    curve: &EligibilityCurve,
+) -> u16 {
+    honorary_payout::eligible_share_bps(locked_total, y0, max_share_bps, &(*curve).into())
 }
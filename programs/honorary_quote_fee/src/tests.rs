@@ -1,11 +1,25 @@
 #![cfg(test)]
 use super::*;
 use crate::{
-    math::{mul_div_floor_u128, saturating_sub_u64, u128_to_u64},
-    streamflow_utils::eligible_share_bps,
+    distribution_hook::{hook_authority_address, DayClosedHookArgs, ON_DAY_CLOSED_DISCRIMINATOR},
+    events::event_cpi_data,
+    cp_amm::{DammActivationType, PoolActivity, PoolInactiveReason},
+    math::{
+        allocate_largest_remainder, mul_div_floor_u128, mul_div_u128, saturating_sub_u64,
+        split_evenly, u128_to_u64, whole_tokens_to_base_units, Rounding,
+    },
+    state::{
+        CreatorVestingSchedule, CurveBreakpoint, EligibilityCurveKind, PolicyChange,
+        EligibilityRules, IneligibilityReason,
+        DAY_HISTORY_CAPACITY, MAX_BLOCKLIST_ENTRIES, MAX_CRANKERS, MAX_CURVE_BREAKPOINTS,
+        MAX_GROUP_MEMBERS, MAX_VESTING_DURATION_SECONDS, MAX_VESTING_TRANCHES, VestingTranche,
+    },
+    streamflow_utils::{eligible_share_bps, ineligibility_reason},
 };
+use proptest::prelude::*;
 
 /// Test helper to build a mock investor payout plan
+#[allow(clippy::too_many_arguments)]
 fn build_test_payout_plan(
     locked_amounts: Vec<u64>,
     claimed_quote: u64,
@@ -22,6 +36,11 @@ fn build_test_payout_plan(
         .map(|(idx, locked)| InvestorEntry {
             locked_amount: locked,
             token_account_index: idx,
+            stream: Pubkey::default(),
+            recipient: Pubkey::default(),
+            ineligible_reason: IneligibilityReason::NONE,
+            raw_locked_amount: locked,
+            snapshot_leaf: [0; 32],
         })
         .collect();
 
@@ -32,8 +51,10 @@ fn build_test_payout_plan(
         carry_quote,
         y0,
         investor_fee_share_bps,
+        &EligibilityCurve::default(),
         daily_cap_quote,
         min_payout_lamports,
+        CapReserve::default(),
     )
 }
 
@@ -70,12 +91,12 @@ fn test_math_mul_div_floor_edge_cases() {
 
     // Test 1: Division by zero should fail
     let result = mul_div_floor_u128(100, 50, 0);
-    assert!(result.is_err(), "Division by zero should error");
+    assert_eq!(result.unwrap_err(), error!(HonoraryQuoteFeeError::DivisionByZero));
     println!("✓ Test 1 passed: Division by zero correctly errors");
 
     // Test 2: Overflow check
     let result = mul_div_floor_u128(u128::MAX, 2, 1);
-    assert!(result.is_err(), "Overflow should error");
+    assert_eq!(result.unwrap_err(), error!(HonoraryQuoteFeeError::ArithmeticOverflow));
     println!("✓ Test 2 passed: Overflow correctly errors");
 
     // Test 3: Max safe multiplication
@@ -87,6 +108,24 @@ fn test_math_mul_div_floor_edge_cases() {
     println!("✅ All mul_div_floor_u128 edge case tests passed\n");
 }
 
+#[test]
+fn test_whole_tokens_to_base_units() {
+    println!("Testing whole token scaling...");
+
+    // Test 1: Amounts scale by 10^decimals
+    assert_eq!(whole_tokens_to_base_units(25, 6).unwrap(), 25_000_000);
+    assert_eq!(whole_tokens_to_base_units(25, 0).unwrap(), 25);
+    assert_eq!(whole_tokens_to_base_units(0, 9).unwrap(), 0);
+    println!("✓ Test 1 passed: scaled by decimals");
+
+    // Test 2: Results past u64 are rejected
+    assert_eq!(whole_tokens_to_base_units(u64::MAX / 1_000_000_000 + 1, 9).unwrap_err(), error!(HonoraryQuoteFeeError::ArithmeticOverflow));
+    assert_eq!(whole_tokens_to_base_units(1, 20).unwrap_err(), error!(HonoraryQuoteFeeError::ArithmeticOverflow));
+    println!("✓ Test 2 passed: overflow rejected");
+
+    println!("✅ All whole token scaling tests passed\n");
+}
+
 #[test]
 fn test_u128_to_u64_conversions() {
     println!("Testing u128_to_u64 conversions...");
@@ -146,27 +185,27 @@ fn test_eligible_share_bps_basic() {
     println!("Testing eligible_share_bps calculations...");
 
     // Test 1: 100% locked
-    let share = eligible_share_bps(1_000_000, 1_000_000, 5000);
+    let share = eligible_share_bps(1_000_000, 1_000_000, 5000, &EligibilityCurve::default());
     assert_eq!(share, 5000, "100% locked should use max share");
     println!("✓ Test 1 passed: 100% locked = {} bps", share);
 
     // Test 2: 50% locked
-    let share = eligible_share_bps(500_000, 1_000_000, 5000);
+    let share = eligible_share_bps(500_000, 1_000_000, 5000, &EligibilityCurve::default());
     assert_eq!(share, 5000, "50% locked should cap at max");
     println!("✓ Test 2 passed: 50% locked = {} bps", share);
 
     // Test 3: 25% locked
-    let share = eligible_share_bps(250_000, 1_000_000, 5000);
+    let share = eligible_share_bps(250_000, 1_000_000, 5000, &EligibilityCurve::default());
     assert_eq!(share, 2500, "25% locked = 2500 bps");
     println!("✓ Test 3 passed: 25% locked = {} bps", share);
 
     // Test 4: 10% locked
-    let share = eligible_share_bps(100_000, 1_000_000, 5000);
+    let share = eligible_share_bps(100_000, 1_000_000, 5000, &EligibilityCurve::default());
     assert_eq!(share, 1000, "10% locked = 1000 bps");
     println!("✓ Test 4 passed: 10% locked = {} bps", share);
 
     // Test 5: Zero locked
-    let share = eligible_share_bps(0, 1_000_000, 5000);
+    let share = eligible_share_bps(0, 1_000_000, 5000, &EligibilityCurve::default());
     assert_eq!(share, 0, "Zero locked = 0 bps");
     println!("✓ Test 5 passed: 0% locked = {} bps", share);
 
@@ -178,28 +217,170 @@ fn test_eligible_share_bps_edge_cases() {
     println!("Testing eligible_share_bps edge cases...");
 
     // Test 1: Y0 is zero
-    let share = eligible_share_bps(1_000_000, 0, 5000);
+    let share = eligible_share_bps(1_000_000, 0, 5000, &EligibilityCurve::default());
     assert_eq!(share, 0, "Y0=0 should return 0");
     println!("✓ Test 1 passed: Y0=0 returns {} bps", share);
 
     // Test 2: Locked exceeds Y0
-    let share = eligible_share_bps(2_000_000, 1_000_000, 5000);
+    let share = eligible_share_bps(2_000_000, 1_000_000, 5000, &EligibilityCurve::default());
     assert_eq!(share, 5000, "200% locked should cap at max");
     println!("✓ Test 2 passed: 200% locked = {} bps (capped)", share);
 
     // Test 3: Very small percentage
-    let share = eligible_share_bps(1, 1_000_000, 10000);
+    let share = eligible_share_bps(1, 1_000_000, 10000, &EligibilityCurve::default());
     assert_eq!(share, 0, "Tiny percentage rounds to 0");
     println!("✓ Test 3 passed: 0.0001% locked = {} bps", share);
 
     // Test 4: Max values
-    let share = eligible_share_bps(u64::MAX as u128, u64::MAX, 10000);
+    let share = eligible_share_bps(u64::MAX as u128, u64::MAX, 10000, &EligibilityCurve::default());
     assert_eq!(share, 10000);
     println!("✓ Test 4 passed: Max values = {} bps", share);
 
     println!("✅ All eligible_share_bps edge case tests passed\n");
 }
 
+/// Test helper to build a curve from `(locked_bps, share_bps)` pairs
+fn curve_with_breakpoints(kind: u8, points: &[(u16, u16)]) -> EligibilityCurve {
+    let mut curve = EligibilityCurve {
+        kind,
+        breakpoint_count: points.len() as u8,
+        ..Default::default()
+    };
+    for (slot, (locked_bps, share_bps)) in curve.breakpoints.iter_mut().zip(points) {
+        *slot = CurveBreakpoint { locked_bps: *locked_bps, share_bps: *share_bps };
+    }
+    curve
+}
+
+#[test]
+fn test_eligibility_curve_kinds() {
+    println!("Testing eligibility curve kinds...");
+
+    let y0 = 1_000_000u64;
+
+    // Test 1: Step schedule (none below 50%, half share to 80%, full share above)
+    let step = curve_with_breakpoints(EligibilityCurveKind::STEP, &[(5000, 2500), (8000, 5000)]);
+    assert!(step.validate().is_ok());
+    assert_eq!(eligible_share_bps(400_000, y0, 5000, &step), 0);
+    assert_eq!(eligible_share_bps(500_000, y0, 5000, &step), 2500);
+    assert_eq!(eligible_share_bps(799_999, y0, 5000, &step), 2500);
+    assert_eq!(eligible_share_bps(900_000, y0, 5000, &step), 5000);
+    println!("✓ Test 1 passed: step schedule");
+
+    // Test 2: Piecewise-linear interpolation from (0, 0)
+    let piecewise =
+        curve_with_breakpoints(EligibilityCurveKind::PIECEWISE_LINEAR, &[(5000, 1000), (10000, 5000)]);
+    assert!(piecewise.validate().is_ok());
+    assert_eq!(eligible_share_bps(250_000, y0, 5000, &piecewise), 500);
+    assert_eq!(eligible_share_bps(750_000, y0, 5000, &piecewise), 3000);
+    assert_eq!(eligible_share_bps(2_000_000, y0, 5000, &piecewise), 5000);
+    println!("✓ Test 2 passed: piecewise-linear interpolation");
+
+    // Test 3: Convex curve squares the locked fraction
+    let convex = curve_with_breakpoints(EligibilityCurveKind::CONVEX, &[]);
+    assert!(convex.validate().is_ok());
+    assert_eq!(eligible_share_bps(500_000, y0, 8000, &convex), 2000);
+    assert_eq!(eligible_share_bps(1_000_000, y0, 8000, &convex), 8000);
+    println!("✓ Test 3 passed: convex curve");
+
+    // Test 4: Curve output is still clamped to the policy max share
+    assert_eq!(eligible_share_bps(900_000, y0, 3000, &step), 3000);
+    println!("✓ Test 4 passed: max share clamp");
+
+    // Test 5: Every kind is monotone in the locked amount
+    let linear = EligibilityCurve::default();
+    for curve in [&linear, &step, &piecewise, &convex] {
+        let mut previous = 0u16;
+        for locked in (0..=1_200_000u128).step_by(10_000) {
+            let share = eligible_share_bps(locked, y0, 5000, curve);
+            assert!(share >= previous, "kind {} not monotone at {}", curve.kind, locked);
+            previous = share;
+        }
+    }
+    println!("✓ Test 5 passed: monotonicity across kinds");
+
+    println!("✅ All eligibility curve kind tests passed\n");
+}
+
+#[test]
+fn test_eligibility_curve_validation() {
+    println!("Testing eligibility curve validation...");
+
+    // Test 1: Unknown kind and breakpoints on linear curves
+    assert!(curve_with_breakpoints(9, &[]).validate().is_err());
+    assert!(curve_with_breakpoints(EligibilityCurveKind::LINEAR, &[(5000, 5000)]).validate().is_err());
+    println!("✓ Test 1 passed: unknown kind / stray breakpoints rejected");
+
+    // Test 2: Table kinds need between 1 and MAX_CURVE_BREAKPOINTS entries
+    assert!(curve_with_breakpoints(EligibilityCurveKind::STEP, &[]).validate().is_err());
+    let mut too_many = curve_with_breakpoints(EligibilityCurveKind::STEP, &[(1000, 1000)]);
+    too_many.breakpoint_count = (MAX_CURVE_BREAKPOINTS + 1) as u8;
+    assert!(too_many.validate().is_err());
+    println!("✓ Test 2 passed: breakpoint count bounds");
+
+    // Test 3: Breakpoints must be increasing and keep the curve monotone
+    assert!(curve_with_breakpoints(EligibilityCurveKind::STEP, &[(8000, 1000), (5000, 2000)])
+        .validate()
+        .is_err());
+    assert!(curve_with_breakpoints(EligibilityCurveKind::STEP, &[(5000, 3000), (8000, 2000)])
+        .validate()
+        .is_err());
+    assert!(curve_with_breakpoints(EligibilityCurveKind::PIECEWISE_LINEAR, &[(12000, 5000)])
+        .validate()
+        .is_err());
+    println!("✓ Test 3 passed: non-monotone tables rejected");
+
+    println!("✅ All eligibility curve validation tests passed\n");
+}
+
+#[test]
+fn test_eligibility_rules() {
+    println!("Testing stream eligibility rules...");
+
+    let now = 100 * DAY_SECONDS as u64;
+    let rules = EligibilityRules {
+        min_remaining_lock_seconds: 30 * DAY_SECONDS as u64,
+        min_stream_age_seconds: 7 * DAY_SECONDS as u64,
+        min_locked_amount: 1_000,
+    };
+    let start = now - 10 * DAY_SECONDS as u64;
+    let end = now + 60 * DAY_SECONDS as u64;
+
+    // Test 1: Qualifying stream
+    assert_eq!(ineligibility_reason(&rules, 5_000, start, end, now), IneligibilityReason::NONE);
+    println!("✓ Test 1 passed: qualifying stream accepted");
+
+    // Test 2: Each rule reports its own reason
+    assert_eq!(
+        ineligibility_reason(&rules, 999, start, end, now),
+        IneligibilityReason::BELOW_MIN_LOCKED
+    );
+    assert_eq!(
+        ineligibility_reason(&rules, 5_000, now - DAY_SECONDS as u64, end, now),
+        IneligibilityReason::STREAM_TOO_YOUNG
+    );
+    assert_eq!(
+        ineligibility_reason(&rules, 5_000, start, now + DAY_SECONDS as u64, now),
+        IneligibilityReason::LOCK_ENDS_TOO_SOON
+    );
+    println!("✓ Test 2 passed: below-min, too-young and ends-too-soon reasons");
+
+    // Test 3: Streams starting in the future count as zero age
+    assert_eq!(
+        ineligibility_reason(&rules, 5_000, now + 1, end, now),
+        IneligibilityReason::STREAM_TOO_YOUNG
+    );
+    println!("✓ Test 3 passed: future start treated as too young");
+
+    // Test 4: Default rules accept everything, fully unlocked streams are not reported
+    let open = EligibilityRules::default();
+    assert_eq!(ineligibility_reason(&open, 1, now, now, now), IneligibilityReason::NONE);
+    assert_eq!(ineligibility_reason(&rules, 0, now, now, now), IneligibilityReason::NONE);
+    println!("✓ Test 4 passed: disabled rules and empty streams");
+
+    println!("✅ All eligibility rule tests passed\n");
+}
+
 #[test]
 fn test_payout_plan_single_investor_full_locked() {
     println!("Testing payout plan: single investor, 100% locked...");
@@ -791,6 +972,867 @@ fn test_integration_scenario_pagination() {
     println!("✅ Pagination integration test passed\n");
 }
 
+#[test]
+fn test_elapsed_day_count_catch_up() {
+    println!("Testing elapsed_day_count catch-up detection...");
+
+    // Test 1: First day of a policy counts once
+    assert_eq!(elapsed_day_count(NEVER_CLOSED_TS, 1_700_000_000), 1);
+    println!("✓ Test 1 passed: first day counts as 1");
+
+    // Test 2: Regular cadence
+    let last = 1_700_000_000i64;
+    assert_eq!(elapsed_day_count(last, last + DAY_SECONDS), 1);
+    assert_eq!(elapsed_day_count(last, last + DAY_SECONDS + 3_600), 1);
+    println!("✓ Test 2 passed: on-time crank counts as 1");
+
+    // Test 3: Three days without a crank
+    assert_eq!(elapsed_day_count(last, last + 3 * DAY_SECONDS + 60), 3);
+    println!("✓ Test 3 passed: three missed days detected");
+
+    // Test 4: Bounded by MAX_CATCH_UP_DAYS
+    assert_eq!(
+        elapsed_day_count(last, last + 365 * DAY_SECONDS),
+        MAX_CATCH_UP_DAYS
+    );
+    println!("✓ Test 4 passed: catch-up bounded at {}", MAX_CATCH_UP_DAYS);
+
+    println!("✅ All elapsed_day_count tests passed\n");
+}
+
+#[test]
+fn test_split_evenly_preserves_total() {
+    println!("Testing split_evenly...");
+
+    let parts: Vec<u64> = (0..3).map(|i| split_evenly(1_000, 3, i)).collect();
+    assert_eq!(parts, vec![333, 333, 334]);
+    assert_eq!(parts.iter().sum::<u64>(), 1_000);
+    println!("✓ Test 1 passed: 1000 / 3 = {:?}", parts);
+
+    assert_eq!(split_evenly(42, 1, 0), 42);
+    assert_eq!(split_evenly(42, 0, 0), 0);
+    println!("✓ Test 2 passed: single and zero part splits");
+
+    println!("✅ All split_evenly tests passed\n");
+}
+
+#[test]
+fn test_payout_plan_catch_up_scaled_cap() {
+    println!("Testing payout plan: cap scaled for catch-up days...");
+
+    // Three days of fees claimed in a single catch-up day
+    let day_count = elapsed_day_count(0, 3 * DAY_SECONDS);
+    let daily_cap = 10_000u64;
+    let plan = build_test_payout_plan(
+        vec![1_000_000u64],
+        90_000,
+        0,
+        0,
+        1_000_000,
+        5000,
+        daily_cap.saturating_mul(u64::from(day_count)),
+        0,
+    )
+    .unwrap();
+
+    println!("  Day count: {}", day_count);
+    println!("  Target investor quote: {}", plan.target_investor_quote);
+    assert_eq!(day_count, 3);
+    assert_eq!(plan.target_investor_quote, 30_000, "Cap should cover all three days");
+
+    println!("✅ Catch-up cap scaling test passed\n");
+}
+
+#[test]
+fn test_force_close_released_carry() {
+    println!("Testing force_close_released_carry...");
+
+    // Test 1: Carry destination keeps the unpaid portion for investors
+    let released = force_close_released_carry(5000, ForceCloseDestination::CARRY, 4_000, 4_500);
+    assert_eq!(released, 0);
+    println!("✓ Test 1 passed: carry destination releases {}", released);
+
+    // Test 2: Creator destination releases the unpaid portion only
+    let released = force_close_released_carry(5000, ForceCloseDestination::CREATOR, 4_000, 4_500);
+    assert_eq!(released, 4_000);
+    println!("✓ Test 2 passed: creator destination releases {}", released);
+
+    // Test 3: Never releases more than the recorded carry
+    let released = force_close_released_carry(5000, ForceCloseDestination::CREATOR, 4_000, 1_000);
+    assert_eq!(released, 1_000);
+    println!("✓ Test 3 passed: release bounded by carry = {}", released);
+
+    // Test 4: Zero share behaves like a regular close and releases all carry
+    let released = force_close_released_carry(0, ForceCloseDestination::CARRY, 0, 2_500);
+    assert_eq!(released, 2_500);
+    println!("✓ Test 4 passed: zero share releases {}", released);
+
+    println!("✅ All force_close_released_carry tests passed\n");
+}
+
+fn empty_day_history() -> DayHistory {
+    DayHistory {
+        policy: Pubkey::default(),
+        lifetime_claimed_quote: 0,
+        lifetime_investor_quote_paid: 0,
+        lifetime_creator_quote_paid: 0,
+        days_recorded: 0,
+        entries: [DaySummary::default(); DAY_HISTORY_CAPACITY],
+        bump: 0,
+    }
+}
+
+#[test]
+fn test_day_history_ring_buffer() {
+    println!("Testing DayHistory ring buffer...");
+
+    let mut history = empty_day_history();
+    let total_days = DAY_HISTORY_CAPACITY as i64 + 5;
+    for day in 0..total_days {
+        history
+            .push(DaySummary {
+                day_start_ts: day * DAY_SECONDS,
+                claimed_quote: 1_000,
+                investor_quote_paid: 400,
+                creator_quote_paid: 600,
+                carry_quote: 0,
+                investor_count: 3,
+                share_bps: 4000,
+            })
+            .unwrap();
+    }
+
+    // Test 1: Lifetime totals include evicted entries
+    assert_eq!(history.days_recorded, total_days as u64);
+    assert_eq!(history.lifetime_claimed_quote, 1_000 * total_days as u64);
+    assert_eq!(history.lifetime_investor_quote_paid, 400 * total_days as u64);
+    assert_eq!(history.lifetime_creator_quote_paid, 600 * total_days as u64);
+    println!("✓ Test 1 passed: lifetime claimed = {}", history.lifetime_claimed_quote);
+
+    // Test 2: Only the most recent entries are retained, newest first
+    let recent: Vec<i64> = history.recent().map(|entry| entry.day_start_ts).collect();
+    assert_eq!(recent.len(), DAY_HISTORY_CAPACITY);
+    assert_eq!(recent[0], (total_days - 1) * DAY_SECONDS);
+    assert_eq!(
+        recent[DAY_HISTORY_CAPACITY - 1],
+        (total_days - DAY_HISTORY_CAPACITY as i64) * DAY_SECONDS
+    );
+    println!("✓ Test 2 passed: newest entry = {}", recent[0]);
+
+    println!("✅ All DayHistory tests passed\n");
+}
+
+#[test]
+fn test_logical_day_summaries_split_catch_up_day() {
+    println!("Testing logical_day_summaries...");
+
+    let progress = DistributionProgress {
+        policy: Pubkey::default(),
+        claimed_quote: 10_000,
+        investor_distributed: 4_000,
+        carry_quote: 7,
+        target_investor_quote: 4_000,
+        pending_deposit_quote: 0,
+        reserve_quote: 0,
+        investor_snapshot: [0; 32],
+        day_start_ts: 10 * DAY_SECONDS,
+        last_page_ts: 10 * DAY_SECONDS,
+        page_cursor: 12,
+        day_count: 3,
+        share_bps: 4000,
+        day_open: true,
+    };
+
+    let summaries = logical_day_summaries(&progress, 6_000, 4000);
+    assert_eq!(summaries.len(), 3);
+    assert_eq!(summaries[0].day_start_ts, 8 * DAY_SECONDS);
+    assert_eq!(summaries[2].day_start_ts, 10 * DAY_SECONDS);
+    assert_eq!(
+        summaries.iter().map(|s| s.claimed_quote).sum::<u64>(),
+        10_000
+    );
+    assert_eq!(
+        summaries.iter().map(|s| s.creator_quote_paid).sum::<u64>(),
+        6_000
+    );
+    assert!(summaries.iter().all(|s| s.investor_count == 12 && s.carry_quote == 7));
+    println!("✓ Test 1 passed: catch-up day split into {} summaries", summaries.len());
+
+    println!("✅ All logical_day_summaries tests passed\n");
+}
+
+#[test]
+fn test_policy_group_pending_quote() {
+    println!("Testing policy group pending quote aggregation...");
+
+    let primary = Pubkey::new_unique();
+    let members = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let mut group = PolicyGroup {
+        primary_policy: primary,
+        quote_mint: Pubkey::new_unique(),
+        quote_treasury: Pubkey::new_unique(),
+        members: [Pubkey::default(); MAX_GROUP_MEMBERS],
+        pending_quote: 0,
+        member_count: 2,
+        claimed_mask: 0,
+        bump: 255,
+    };
+    group.members[..2].copy_from_slice(&members);
+
+    // Test 1: Member lookup
+    assert_eq!(group.member_index(&members[1]), Some(1));
+    assert_eq!(group.member_index(&primary), None);
+    println!("✓ Test 1 passed: member lookup");
+
+    // Test 2: Day cannot open until every member has claimed
+    group.claimed_mask = 0b01;
+    group.pending_quote = 7_000;
+    assert!(take_group_pending_quote(&mut group, primary, true).is_err());
+    println!("✓ Test 2 passed: missing member claim blocks day open");
+
+    // Test 3: Opening takes the pending quote and resets claims
+    group.claimed_mask = 0b11;
+    let forwarded = take_group_pending_quote(&mut group, primary, true).unwrap();
+    assert_eq!(forwarded, 7_000);
+    assert_eq!(group.pending_quote, 0);
+    assert_eq!(group.claimed_mask, 0);
+    println!("✓ Test 3 passed: forwarded {} on day open", forwarded);
+
+    // Test 4: Later pages pick up late forwards without claim gating
+    group.pending_quote = 500;
+    assert_eq!(take_group_pending_quote(&mut group, primary, false).unwrap(), 500);
+    println!("✓ Test 4 passed: late forwards folded into open day");
+
+    // Test 5: Only the primary policy may crank the group
+    assert!(take_group_pending_quote(&mut group, members[0], false).is_err());
+    println!("✓ Test 5 passed: member crank rejected");
+
+    println!("✅ All policy group tests passed\n");
+}
+
+#[test]
+fn test_blocklist_forfeiture() {
+    println!("Testing recipient blocklist forfeiture...");
+
+    let mut blocklist = Blocklist {
+        policy: Pubkey::new_unique(),
+        forfeiture_ata: Pubkey::new_unique(),
+        recipients: [Pubkey::default(); MAX_BLOCKLIST_ENTRIES],
+        recipient_count: 0,
+        bump: 255,
+    };
+    let blocked = Pubkey::new_unique();
+    let allowed = Pubkey::new_unique();
+
+    // Test 1: Insert is idempotent, remove requires a listed recipient
+    blocklist.insert(blocked).unwrap();
+    blocklist.insert(blocked).unwrap();
+    assert_eq!(blocklist.recipient_count, 1);
+    assert!(blocklist.contains(&blocked));
+    assert!(blocklist.remove(&allowed).is_err());
+    println!("✓ Test 1 passed: insert/remove bookkeeping");
+
+    // Test 2: Blocked investors are selected by recipient, weights untouched
+    let investors: Vec<InvestorEntry> = [(allowed, 600_000u64), (blocked, 400_000u64)]
+        .iter()
+        .enumerate()
+        .map(|(idx, (recipient, locked))| InvestorEntry {
+            locked_amount: *locked,
+            token_account_index: idx * 2 + 1,
+            stream: Pubkey::new_unique(),
+            recipient: *recipient,
+            ineligible_reason: IneligibilityReason::NONE,
+            raw_locked_amount: *locked,
+            snapshot_leaf: [0; 32],
+        })
+        .collect();
+    let forfeited = forfeited_investors(&investors, &blocklist);
+    assert_eq!(forfeited.len(), 1);
+    assert_eq!(forfeited[0].0, 3);
+    assert_eq!(forfeited[0].2, blocked);
+    println!("✓ Test 2 passed: blocked investor at account index {}", forfeited[0].0);
+
+    // Test 3: Denominator still includes the blocked weight
+    let plan = build_test_payout_plan(vec![600_000, 400_000], 10_000, 0, 0, 1_000_000, 10000, 0, 0)
+        .unwrap();
+    assert_eq!(plan.transfers[0].0, 6_000);
+    assert_eq!(plan.transfers[1].0, 4_000);
+    println!("✓ Test 3 passed: allowed investor share not inflated");
+
+    // Test 4: Removal swaps the last entry into place and a full list rejects inserts
+    blocklist.remove(&blocked).unwrap();
+    assert_eq!(blocklist.recipient_count, 0);
+    for _ in 0..MAX_BLOCKLIST_ENTRIES {
+        blocklist.insert(Pubkey::new_unique()).unwrap();
+    }
+    assert!(blocklist.insert(blocked).is_err());
+    println!("✓ Test 4 passed: capacity of {} recipients enforced", MAX_BLOCKLIST_ENTRIES);
+
+    println!("✅ All blocklist tests passed\n");
+}
+
+#[test]
+fn test_pool_activity_gate() {
+    println!("Testing DAMM pool status and activation gate...");
+
+    let clock = Clock {
+        slot: 1_000,
+        unix_timestamp: 50_000,
+        ..Default::default()
+    };
+
+    // Test 1: Enabled pools past their activation point are claimable
+    let slot_pool = PoolActivity::new(0, DammActivationType::SLOT, 900, &clock);
+    let ts_pool = PoolActivity::new(0, DammActivationType::TIMESTAMP, 50_000, &clock);
+    assert_eq!(slot_pool.inactive_reason(), None);
+    assert_eq!(ts_pool.inactive_reason(), None);
+    assert_eq!(ts_pool.current_point, 50_000);
+    println!("✓ Test 1 passed: activated pools claim normally");
+
+    // Test 2: Activation point compared against slot or timestamp per activation type
+    let slot_pending = PoolActivity::new(0, DammActivationType::SLOT, 1_001, &clock);
+    let ts_pending = PoolActivity::new(0, DammActivationType::TIMESTAMP, 50_001, &clock);
+    assert_eq!(slot_pending.inactive_reason(), Some(PoolInactiveReason::NOT_ACTIVATED));
+    assert_eq!(ts_pending.inactive_reason(), Some(PoolInactiveReason::NOT_ACTIVATED));
+    println!("✓ Test 2 passed: pools not yet activated are skipped");
+
+    // Test 3: Disabled pools are skipped even when activated
+    let disabled = PoolActivity::new(1, DammActivationType::SLOT, 0, &clock);
+    assert_eq!(disabled.inactive_reason(), Some(PoolInactiveReason::DISABLED));
+    println!("✓ Test 3 passed: disabled pool skipped");
+
+    println!("✅ All pool activity tests passed\n");
+}
+
+#[test]
+fn test_policy_index_addresses() {
+    println!("Testing policy index PDA derivation...");
+
+    let pool = Pubkey::new_unique();
+
+    // Test 1: Index 0 keeps the original single-policy addresses
+    let (legacy_policy, _) =
+        Pubkey::find_program_address(&[b"policy", pool.as_ref()], &crate::ID);
+    let (legacy_progress, _) =
+        Pubkey::find_program_address(&[b"progress", pool.as_ref()], &crate::ID);
+    assert_eq!(policy_address(&pool, 0), legacy_policy);
+    assert_eq!(progress_address(&pool, 0), legacy_progress);
+    println!("✓ Test 1 passed: index 0 matches legacy derivation");
+
+    // Test 2: Other indices get independent policy and progress accounts
+    assert_ne!(policy_address(&pool, 1), legacy_policy);
+    assert_ne!(policy_address(&pool, 1), policy_address(&pool, 2));
+    assert_ne!(progress_address(&pool, 1), legacy_progress);
+    assert_eq!(policy_index_seed(258), vec![2, 1]);
+    println!("✓ Test 2 passed: indices 1 and 2 derive distinct PDAs");
+
+    println!("✅ All policy index tests passed\n");
+}
+
+#[test]
+fn test_cap_reserve_smoothing() {
+    println!("Testing cap overflow reserve...");
+
+    let mut reserve = CapReserve {
+        enabled: true,
+        retention_bps: 0,
+        balance: 0,
+    };
+
+    // Test 1: Excess over the cap is retained instead of going to the creator
+    let (target, reserve_after) = reserve.split(15_000, 10_000, 5000).unwrap();
+    assert_eq!((target, reserve_after), (10_000, 5_000));
+    let creator = creator_quote_at_close(30_000, target, 0, reserve_after).unwrap();
+    assert_eq!(creator, 15_000);
+    println!("✓ Test 1 passed: capped-out {} held in reserve", reserve_after);
+
+    // Test 2: A quiet day releases the reserve up to the cap
+    reserve.balance = reserve_after;
+    let (target, reserve_after) = reserve.split(6_000, 10_000, 5000).unwrap();
+    assert_eq!((target, reserve_after), (10_000, 1_000));
+    let creator = creator_quote_at_close(12_000, target, 5_000, reserve_after).unwrap();
+    assert_eq!(creator, 6_000);
+    println!("✓ Test 2 passed: reserve released to {} on a quiet day", target);
+
+    // Test 3: Retention buffers part of every day's entitlement
+    reserve.balance = 0;
+    reserve.retention_bps = 1000;
+    let (target, reserve_after) = reserve.split(8_000, 0, 5000).unwrap();
+    assert_eq!((target, reserve_after), (7_200, 800));
+    println!("✓ Test 3 passed: 10% retention keeps {}", reserve_after);
+
+    // Test 4: No eligible investors releases the reserve to the creator
+    reserve.balance = 4_000;
+    assert_eq!(reserve.split(0, 10_000, 0).unwrap(), (0, 0));
+    assert_eq!(creator_quote_at_close(1_000, 0, 4_000, 0).unwrap(), 5_000);
+    println!("✓ Test 4 passed: reserve forwarded when no investors are eligible");
+
+    // Test 5: Disabled reserve keeps the original cap behaviour
+    let plan = build_test_payout_plan(vec![1_000_000], 30_000, 0, 0, 1_000_000, 5000, 10_000, 0)
+        .unwrap();
+    assert_eq!(plan.target_investor_quote, 10_000);
+    assert_eq!(plan.reserve_quote_after, 0);
+    assert_eq!(creator_quote_at_close(30_000, 10_000, 0, 0).unwrap(), 20_000);
+    println!("✓ Test 5 passed: disabled reserve hands excess to the creator");
+
+    println!("✅ All cap reserve tests passed\n");
+}
+
+#[test]
+fn test_cranker_allowlist_and_page_spacing() {
+    println!("Testing cranker allowlist and page rate limit...");
+
+    let keeper = Pubkey::new_unique();
+    let outsider = Pubkey::new_unique();
+    let mut allowlist = CrankerAllowlist {
+        policy: Pubkey::new_unique(),
+        crankers: [Pubkey::default(); MAX_CRANKERS],
+        cranker_count: 0,
+        permissionless: false,
+        bump: 255,
+    };
+
+    // Test 1: Only listed keepers may crank
+    allowlist.set_allowed(keeper, true).unwrap();
+    allowlist.set_allowed(keeper, true).unwrap();
+    assert_eq!(allowlist.cranker_count, 1);
+    assert!(allowlist.permits(&keeper));
+    assert!(!allowlist.permits(&outsider));
+    println!("✓ Test 1 passed: keeper allowed, outsider rejected");
+
+    // Test 2: Permissionless toggle opens cranking to anyone
+    allowlist.permissionless = true;
+    assert!(allowlist.permits(&outsider));
+    allowlist.permissionless = false;
+    allowlist.set_allowed(keeper, false).unwrap();
+    assert!(!allowlist.permits(&keeper));
+    println!("✓ Test 2 passed: permissionless toggle and removal");
+
+    // Test 3: Capacity is enforced
+    for _ in 0..MAX_CRANKERS {
+        allowlist.set_allowed(Pubkey::new_unique(), true).unwrap();
+    }
+    assert!(allowlist.set_allowed(keeper, true).is_err());
+    println!("✓ Test 3 passed: capacity of {} crankers", MAX_CRANKERS);
+
+    // Test 4: Pages within an open day respect the minimum interval
+    assert!(check_page_spacing(false, 1_000, 60, 1_059).is_err());
+    assert!(check_page_spacing(false, 1_000, 60, 1_060).is_ok());
+    assert!(check_page_spacing(true, 1_000, 60, 1_001).is_ok());
+    assert!(check_page_spacing(false, 1_000, 0, 1_000).is_ok());
+    println!("✓ Test 4 passed: page spacing enforced only within a day");
+
+    println!("✅ All cranker allowlist tests passed\n");
+}
+
+#[test]
+fn test_distribution_hook_instruction_layout() {
+    println!("Testing distribution hook instruction layout...");
+
+    // Test 1: Discriminator matches Anchor's `global:on_day_closed` sighash
+    let sighash = anchor_lang::solana_program::hash::hash(b"global:on_day_closed");
+    assert_eq!(ON_DAY_CLOSED_DISCRIMINATOR, sighash.to_bytes()[..8]);
+    println!("✓ Test 1 passed: discriminator {:02x?}", ON_DAY_CLOSED_DISCRIMINATOR);
+
+    // Test 2: Fixed little-endian layout after the discriminator
+    let args = DayClosedHookArgs {
+        policy: Pubkey::new_unique(),
+        day_start_ts: 86_400,
+        day_count: 2,
+        claimed_quote: 10_000,
+        investor_quote_paid: 4_000,
+        creator_quote_paid: 6_000,
+        share_bps: 4000,
+    };
+    let data = args.instruction_data().unwrap();
+    assert_eq!(data.len(), 8 + 32 + 8 + 4 + 8 * 3 + 2);
+    assert_eq!(&data[8..40], args.policy.as_ref());
+    assert_eq!(&data[40..48], &86_400i64.to_le_bytes());
+    assert_eq!(&data[76..78], &4000u16.to_le_bytes());
+    println!("✓ Test 2 passed: {} byte payload", data.len());
+
+    // Test 3: Hook programs can decode the payload back
+    let decoded = DayClosedHookArgs::try_from_slice(&data[8..]).unwrap();
+    assert_eq!(decoded, args);
+    println!("✓ Test 3 passed: round trip");
+
+    // Test 4: Hooks are signed for by a PDA distinct from the treasury owner
+    let (hook_authority, _) = hook_authority_address(&args.policy);
+    assert_ne!(hook_authority, honorary_position_address(&args.policy));
+    assert_eq!(
+        hook_authority,
+        Pubkey::find_program_address(&[b"hook", args.policy.as_ref()], &crate::ID).0
+    );
+    println!("✓ Test 4 passed: hook authority is not the honorary PDA");
+
+    println!("✅ All distribution hook tests passed\n");
+}
+
+#[test]
+fn test_policy_change_validation() {
+    println!("Testing queued policy change validation...");
+
+    let quote_mint = Pubkey::new_unique();
+
+    // Test 1: Empty changes are rejected
+    assert!(PolicyChange::default().is_empty());
+    assert!(validate_policy_change(&PolicyChange::default(), quote_mint, None, None).is_err());
+    println!("✓ Test 1 passed: empty change rejected");
+
+    // Test 2: Values are checked like initialize_policy parameters
+    let bad_share = PolicyChange {
+        investor_fee_share_bps: Some(10_001),
+        ..Default::default()
+    };
+    let bad_y0 = PolicyChange {
+        y0: Some(0),
+        ..Default::default()
+    };
+    assert!(validate_policy_change(&bad_share, quote_mint, None, None).is_err());
+    assert!(validate_policy_change(&bad_y0, quote_mint, None, None).is_err());
+    println!("✓ Test 2 passed: out-of-range share and y0 rejected");
+
+    // Test 3: A new creator destination needs its token account
+    let new_creator = PolicyChange {
+        creator_quote_ata: Some(Pubkey::new_unique()),
+        ..Default::default()
+    };
+    assert!(validate_policy_change(&new_creator, quote_mint, None, None).is_err());
+    println!("✓ Test 3 passed: creator account required");
+
+    // Test 4: Each set field is validated; unset fields are ignored
+    let change = PolicyChange {
+        investor_fee_share_bps: Some(2500),
+        y0: None,
+        creator_quote_ata: new_creator.creator_quote_ata,
+        creator_vesting: None,
+    };
+    assert!(validate_policy_change(&change, quote_mint, None, None).is_err());
+    let y0_only = PolicyChange {
+        y0: Some(5_000_000),
+        ..Default::default()
+    };
+    assert!(validate_policy_change(&y0_only, quote_mint, None, None).is_ok());
+    println!("✓ Test 4 passed: partial changes validated");
+
+    println!("✅ All policy change validation tests passed\n");
+}
+
+#[test]
+fn test_committed_treasury_quote() {
+    println!("Testing committed treasury quote for rescues...");
+
+    let mut progress = DistributionProgress {
+        policy: Pubkey::default(),
+        claimed_quote: 10_000,
+        investor_distributed: 1_500,
+        carry_quote: 40,
+        target_investor_quote: 4_000,
+        pending_deposit_quote: 300,
+        reserve_quote: 600,
+        investor_snapshot: [0; 32],
+        day_start_ts: 10 * DAY_SECONDS,
+        last_page_ts: 10 * DAY_SECONDS,
+        page_cursor: 3,
+        day_count: 1,
+        share_bps: 4000,
+        day_open: true,
+    };
+
+    // Test 1: Open day keeps the unpaid investor target
+    assert_eq!(
+        committed_treasury_quote(&progress, 0).unwrap(),
+        40 + 2_500 + 600 + 300
+    );
+    println!("✓ Test 1 passed: open day commits unpaid target");
+
+    // Test 2: Closed day only keeps carry, reserve and pending quote
+    progress.day_open = false;
+    assert_eq!(committed_treasury_quote(&progress, 0).unwrap(), 940);
+    println!("✓ Test 2 passed: closed day releases target");
+
+    // Test 3: Group forwards waiting in the treasury are committed
+    assert_eq!(committed_treasury_quote(&progress, 1_000).unwrap(), 1_940);
+    println!("✓ Test 3 passed: group pending quote committed");
+
+    // Test 4: Overflow is reported
+    progress.carry_quote = u64::MAX;
+    assert!(committed_treasury_quote(&progress, 0).is_err());
+    println!("✓ Test 4 passed: overflow rejected");
+
+    println!("✅ All committed treasury quote tests passed\n");
+}
+
+#[test]
+fn test_event_cpi_layout() {
+    println!("Testing self-CPI event layout...");
+
+    // Test 1: Data starts with Anchor's event tag, then the event discriminator
+    let event = DayOpened {
+        version: EVENT_VERSION,
+        policy: Pubkey::new_unique(),
+        day_start_ts: 10 * DAY_SECONDS,
+        day_count: 2,
+        carry_quote: 7,
+        reserve_quote: 600,
+    };
+    let data = event_cpi_data(&event);
+    assert_eq!(&data[..8], anchor_lang::event::EVENT_IX_TAG_LE);
+    assert_eq!(&data[8..16], DayOpened::DISCRIMINATOR);
+    println!("✓ Test 1 passed: tag and discriminator prefix");
+
+    // Test 2: The version is the first field so decoders can branch on it
+    assert_eq!(data[16], EVENT_VERSION);
+    let decoded = DayOpened::try_from_slice(&data[16..]).unwrap();
+    assert_eq!(decoded.policy, event.policy);
+    assert_eq!(decoded.day_count, 2);
+    assert_eq!(decoded.reserve_quote, 600);
+    println!("✓ Test 2 passed: versioned body round-trips");
+
+    println!("✅ All event CPI layout tests passed\n");
+}
+
+#[test]
+fn test_creator_vesting_schedule() {
+    println!("Testing creator vesting schedule...");
+
+    let schedule = CreatorVestingSchedule {
+        start_ts: 100 * DAY_SECONDS,
+        cliff_seconds: DAY_SECONDS,
+        duration_seconds: 10 * DAY_SECONDS,
+    };
+    schedule.validate().unwrap();
+
+    // Test 1: Nothing vests before the tranche start or before the cliff
+    let start = schedule.tranche_start(0);
+    assert_eq!(start, 100 * DAY_SECONDS);
+    assert_eq!(schedule.vested_quote(1_000, start, 0).unwrap(), 0);
+    assert_eq!(schedule.vested_quote(1_000, start, 100 * DAY_SECONDS + DAY_SECONDS - 1).unwrap(), 0);
+    println!("✓ Test 1 passed: cliff respected");
+
+    // Test 2: Linear release from the tranche start once past the cliff, rounded down
+    assert_eq!(schedule.vested_quote(1_000, start, 101 * DAY_SECONDS).unwrap(), 100);
+    assert_eq!(schedule.vested_quote(999, start, 105 * DAY_SECONDS).unwrap(), 499);
+    println!("✓ Test 2 passed: linear release");
+
+    // Test 3: Fully vested at and after the end
+    assert_eq!(schedule.vested_quote(1_000, start, 110 * DAY_SECONDS).unwrap(), 1_000);
+    assert_eq!(schedule.vested_quote(u64::MAX, start, i64::MAX).unwrap(), u64::MAX);
+    println!("✓ Test 3 passed: fully vested at end");
+
+    // Test 4: Each deposit vests from its own timestamp, withdrawals netted
+    let mut escrow = CreatorEscrow {
+        policy: Pubkey::default(),
+        vault: Pubkey::default(),
+        total_deposited: 0,
+        total_withdrawn: 0,
+        matured_quote: 0,
+        tranches: [VestingTranche::default(); MAX_VESTING_TRANCHES],
+        tranche_count: 0,
+        bump: 0,
+    };
+    escrow.record_deposit(&schedule, 50 * DAY_SECONDS, 300).unwrap();
+    escrow.record_deposit(&schedule, 60 * DAY_SECONDS, 300).unwrap();
+    assert_eq!(escrow.tranche_count, 1);
+    escrow.record_deposit(&schedule, 105 * DAY_SECONDS, 400).unwrap();
+    escrow.record_deposit(&schedule, 106 * DAY_SECONDS, 0).unwrap();
+    assert_eq!(escrow.tranche_count, 2);
+    assert_eq!(escrow.total_deposited, 1_000);
+    assert_eq!(escrow.withdrawable(&schedule, 105 * DAY_SECONDS).unwrap(), 300);
+    assert_eq!(escrow.withdrawable(&schedule, 110 * DAY_SECONDS).unwrap(), 800);
+    escrow.total_withdrawn = 800;
+    assert_eq!(escrow.withdrawable(&schedule, 110 * DAY_SECONDS).unwrap(), 0);
+    println!("✓ Test 4 passed: per-deposit tranches");
+
+    // Test 5: A deposit after the window ends starts a full schedule of its own
+    escrow.record_deposit(&schedule, 200 * DAY_SECONDS, 500).unwrap();
+    assert_eq!(escrow.tranche_count, 1);
+    assert_eq!(escrow.matured_quote, 1_000);
+    assert_eq!(escrow.withdrawable(&schedule, 200 * DAY_SECONDS).unwrap(), 200);
+    assert_eq!(escrow.withdrawable(&schedule, 205 * DAY_SECONDS).unwrap(), 450);
+    assert_eq!(escrow.withdrawable(&schedule, 210 * DAY_SECONDS).unwrap(), 700);
+    println!("✓ Test 5 passed: late deposit vests on its own schedule");
+
+    // Test 6: Deposits share a tranche until the next bucket boundary
+    let bucket = schedule.bucket_seconds();
+    assert_eq!(bucket, 8 * 3_600);
+    assert_eq!(schedule.tranche_start(100 * DAY_SECONDS + 1), 100 * DAY_SECONDS + bucket);
+    assert_eq!(schedule.tranche_start(100 * DAY_SECONDS + bucket), 100 * DAY_SECONDS + bucket);
+    assert_eq!(schedule.tranche_start(100 * DAY_SECONDS + bucket + 1), 100 * DAY_SECONDS + 2 * bucket);
+    println!("✓ Test 6 passed: tranche starts rounded up to bucket boundaries");
+
+    // Test 7: Daily and hourly deposits over the longest schedule fit in the escrow
+    let longest = CreatorVestingSchedule { start_ts: 0, cliff_seconds: 0, duration_seconds: MAX_VESTING_DURATION_SECONDS };
+    for step in [DAY_SECONDS, 3_600] {
+        let mut escrow = CreatorEscrow { total_deposited: 0, matured_quote: 0, tranche_count: 0, ..escrow };
+        for index in 0..1_000 * DAY_SECONDS / step {
+            escrow.record_deposit(&longest, index * step, 1).unwrap();
+        }
+        assert!(escrow.tranche_count as usize <= MAX_VESTING_TRANCHES);
+        let live: u64 = escrow.tranches[..escrow.tranche_count as usize].iter().map(|tranche| tranche.amount).sum();
+        assert_eq!(escrow.matured_quote + live, escrow.total_deposited);
+    }
+    const { assert!(CreatorEscrow::LEN < 1_024) };
+    println!("✓ Test 7 passed: tranche count and account size bounded");
+
+    // Test 8: Invalid schedules are rejected
+    for invalid in [
+        CreatorVestingSchedule { duration_seconds: 0, ..schedule },
+        CreatorVestingSchedule { duration_seconds: MAX_VESTING_DURATION_SECONDS + 1, ..schedule },
+        CreatorVestingSchedule { cliff_seconds: -1, ..schedule },
+        CreatorVestingSchedule { cliff_seconds: 11 * DAY_SECONDS, ..schedule },
+        CreatorVestingSchedule { start_ts: -1, ..schedule },
+    ] {
+        assert!(invalid.validate().is_err());
+    }
+    println!("✓ Test 8 passed: invalid schedules rejected");
+
+    println!("✅ All creator vesting schedule tests passed\n");
+}
+
+#[test]
+fn test_investor_snapshot_chain() {
+    println!("Testing investor snapshot commitments...");
+
+    let entry = |stream: Pubkey, deposited: u64| InvestorEntry {
+        locked_amount: deposited,
+        token_account_index: 1,
+        stream,
+        recipient: Pubkey::default(),
+        ineligible_reason: IneligibilityReason::NONE,
+        raw_locked_amount: deposited,
+        snapshot_leaf: investor_snapshot_leaf(&stream, &Pubkey::default(), deposited, 10),
+    };
+    let (a, b, c) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let first = vec![entry(a, 100), entry(b, 200)];
+    let second = vec![entry(c, 300)];
+    let digest = |page: &[InvestorEntry]| {
+        let leaves: Vec<[u8; 32]> = page.iter().map(|e| e.snapshot_leaf).collect();
+        investor_page_digest(&leaves)
+    };
+    let commitment = investor_snapshot_commitment(&[digest(&first), digest(&second)]);
+    let remaining = investor_snapshot_commitment(&[digest(&second)]);
+
+    // Test 1: Pages match in order and only the last one ends the chain
+    assert!(snapshot_page_matches(&commitment, &first, &remaining, false));
+    assert!(snapshot_page_matches(&remaining, &second, &[0; 32], true));
+    assert!(!snapshot_page_matches(&commitment, &first, &remaining, true));
+    assert!(!snapshot_page_matches(&remaining, &second, &[0; 32], false));
+    println!("✓ Test 1 passed: pages chained in order");
+
+    // Test 2: Reordered, skipped or changed streams break the chain
+    let reordered = vec![entry(b, 200), entry(a, 100)];
+    assert!(!snapshot_page_matches(&commitment, &reordered, &remaining, false));
+    assert!(!snapshot_page_matches(&commitment, &second, &[0; 32], true));
+    let topped_up = vec![entry(a, 100), entry(b, 201)];
+    assert!(!snapshot_page_matches(&commitment, &topped_up, &remaining, false));
+    println!("✓ Test 2 passed: tampered pages rejected");
+
+    println!("✅ All investor snapshot chain tests passed\n");
+}
+
+#[test]
+fn test_mul_div_rounding_modes() {
+    println!("Testing 256-bit mul_div rounding modes...");
+
+    // Test 1: Rounding of an inexact quotient (10 * 7 / 4 = 17.5)
+    assert_eq!(mul_div_u128(10, 7, 4, Rounding::Floor).unwrap(), 17);
+    assert_eq!(mul_div_u128(10, 7, 4, Rounding::Ceil).unwrap(), 18);
+    assert_eq!(mul_div_u128(10, 7, 4, Rounding::Nearest).unwrap(), 18);
+    assert_eq!(mul_div_u128(10, 7, 3, Rounding::Nearest).unwrap(), 23);
+    println!("✓ Test 1 passed: floor/ceil/nearest on inexact quotient");
+
+    // Test 2: Exact quotients are unaffected by the rounding mode
+    for rounding in [Rounding::Floor, Rounding::Ceil, Rounding::Nearest] {
+        assert_eq!(mul_div_u128(12, 5, 4, rounding).unwrap(), 15);
+    }
+    println!("✓ Test 2 passed: exact quotient identical across modes");
+
+    // Test 3: Intermediate product wider than u128
+    let result = mul_div_u128(u128::MAX, u128::MAX, u128::MAX, Rounding::Floor).unwrap();
+    assert_eq!(result, u128::MAX);
+    println!("✓ Test 3 passed: u128::MAX * u128::MAX / u128::MAX");
+
+    // Test 4: Ceil past u128::MAX and zero denominator both error
+    assert!(mul_div_u128(u128::MAX, 3, 2, Rounding::Ceil).is_err());
+    assert!(mul_div_u128(1, 1, 0, Rounding::Floor).is_err());
+    println!("✓ Test 4 passed: overflow and zero denominator rejected");
+
+    println!("✅ All mul_div rounding tests passed\n");
+}
+
+#[test]
+fn test_largest_remainder_allocation() {
+    println!("Testing largest-remainder allocation...");
+
+    // Test 1: Three equal weights split 100 as 34/33/33
+    let shares = allocate_largest_remainder(100, &[1, 1, 1]).unwrap();
+    assert_eq!(shares, vec![34, 33, 33]);
+    println!("✓ Test 1 passed: tie broken by lowest index {:?}", shares);
+
+    // Test 2: Leftover goes to the largest fractional part
+    let shares = allocate_largest_remainder(10, &[1, 2, 4]).unwrap();
+    assert_eq!(shares.iter().sum::<u64>(), 10);
+    assert_eq!(shares, vec![1, 3, 6]);
+    println!("✓ Test 2 passed: {:?}", shares);
+
+    // Test 3: Zero weights and zero totals
+    assert_eq!(allocate_largest_remainder(10, &[0, 0]).unwrap(), vec![0, 0]);
+    assert_eq!(allocate_largest_remainder(0, &[5, 5]).unwrap(), vec![0, 0]);
+    assert_eq!(allocate_largest_remainder(9, &[0, 3]).unwrap(), vec![0, 9]);
+    println!("✓ Test 3 passed: zero weights receive nothing");
+
+    // Test 4: Payout plan no longer leaves floor dust behind
+    let plan = build_test_payout_plan(vec![1, 1, 1], 100, 0, 0, 3, 10000, 0, 0).unwrap();
+    assert_eq!(plan.total_paid, 100);
+    assert_eq!(plan.carry_quote_after, 0);
+    println!("✓ Test 4 passed: page pays the full available amount");
+
+    println!("✅ All largest-remainder tests passed\n");
+}
+
+proptest! {
+    #[test]
+    fn prop_allocation_sums_to_total(
+        total in any::<u64>(),
+        weights in prop::collection::vec(any::<u64>(), 1..16),
+    ) {
+        let shares = allocate_largest_remainder(total, &weights).unwrap();
+        let sum: u128 = shares.iter().map(|share| *share as u128).sum();
+        if weights.iter().any(|weight| *weight > 0) {
+            prop_assert_eq!(sum, total as u128);
+        } else {
+            prop_assert_eq!(sum, 0);
+        }
+    }
+
+    #[test]
+    fn prop_allocation_monotone_in_weight(
+        total in any::<u64>(),
+        weights in prop::collection::vec(any::<u64>(), 2..16),
+    ) {
+        let shares = allocate_largest_remainder(total, &weights).unwrap();
+        for i in 0..weights.len() {
+            for j in 0..weights.len() {
+                if weights[i] > weights[j] {
+                    prop_assert!(shares[i] >= shares[j]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn prop_mul_div_matches_u128(a in any::<u64>(), b in any::<u64>(), d in 1..u64::MAX) {
+        let product = a as u128 * b as u128;
+        let d = d as u128;
+        prop_assert_eq!(mul_div_u128(a as u128, b as u128, d, Rounding::Floor).unwrap(), product / d);
+        prop_assert_eq!(
+            mul_div_u128(a as u128, b as u128, d, Rounding::Ceil).unwrap(),
+            product / d + u128::from(product.checked_rem(d) != Some(0))
+        );
+    }
+}
+
 #[test]
 fn test_comprehensive_requirements_checklist() {
     println!("\n========================================");
@@ -0,0 +1,1365 @@
+//! End-to-end scenarios on the in-process harness: `initialize_policy` →
+//! `configure_honorary_position` → multi-page cranks across simulated days, against the
+//! cp-amm stub and Streamflow contract fixtures.
+
+mod harness;
+
+use anchor_lang::{
+    error::ErrorCode,
+    prelude::{AccountMeta, Pubkey, Rent},
+    solana_program::{program_error::ProgramError, program_pack::Pack},
+    AnchorDeserialize,
+};
+use carbon_meteora_damm_v2_decoder::accounts::position::Position as DammPosition;
+use harness::{
+    cp_amm_stub::{pool_layout, read_u64},
+    fixtures,
+    policy_env::{default_policy_params, PolicyEnv},
+    TxOutcome, HOOK_PROGRAM_ID, MAX_COMPUTE_UNIT_LIMIT,
+};
+use honorary_quote_fee::{
+    distribution_hook::{hook_authority_address, HookFailureMode},
+    errors::HonoraryQuoteFeeError,
+    events::{
+        BlocklistInitialized, CrankerAllowlistUpdated, CreatorEscrowInitialized,
+        CreatorPayoutDayClosed, CreatorQuoteEscrowed, CreatorVestedWithdrawn, DayForceClosed,
+        DayHistoryInitialized, DayOpened, DistributionHookFailed, HonoraryPositionInitialized,
+        InvestorPayoutPage, PageRejected, PageRejectionReason, PolicyAccountsMigrated,
+        PolicyAddedToGroup, PolicyChangeExecuted, PolicyChangeQueued, PolicyGroupInitialized,
+        PolicyInitialized, QuoteDeposited, QuoteFeesClaimed, TokensRescued, EVENT_VERSION,
+    },
+    state::{
+        CreatorVestingSchedule, DistributionProgress, EligibilityRules, FeeCaptureMode, Policy,
+        PolicyChange, PolicyStatus,
+    },
+    CrankPageResult, CrankQuoteFeeParams, FeeSourceKind, InitializePolicyParams,
+    CRANK_COMPUTE_RESERVE, DAY_SECONDS, INVESTOR_PAYOUT_COMPUTE_UNITS,
+};
+
+fn page(expected_page_cursor: u32, is_last_page: bool) -> CrankQuoteFeeParams {
+    CrankQuoteFeeParams {
+        expected_page_cursor,
+        is_last_page,
+        ..CrankQuoteFeeParams::default()
+    }
+}
+
+fn program_error(error: HonoraryQuoteFeeError) -> ProgramError {
+    ProgramError::Custom(error.into())
+}
+
+/// The `PageRejected` event of the last failed transaction.
+fn last_rejection(env: &PolicyEnv) -> PageRejected {
+    let failure = env.svm.last_failure.as_ref().expect("a failed transaction");
+    let mut rejected = failure.events_of::<PageRejected>();
+    assert_eq!(rejected.len(), 1);
+    rejected.remove(0)
+}
+
+/// Four investors with 1M..4M deposits, each releasing 10% per day from now.
+fn env_with_investors() -> PolicyEnv {
+    let mut env = PolicyEnv::configured(default_policy_params());
+    for deposit in [1_000_000, 2_000_000, 3_000_000, 4_000_000] {
+        env.add_investor(deposit, 10, DAY_SECONDS as u64);
+    }
+    env
+}
+
+#[test]
+fn test_policy_setup_through_harness() {
+    println!("Testing initialize_policy and configure_honorary_position...");
+
+    // Test 1: initialize_policy creates the policy and reports it
+    let mut env = PolicyEnv::new();
+    let outcome = env.initialize_policy(default_policy_params()).unwrap();
+    let initialized = outcome.events_of::<PolicyInitialized>();
+    assert_eq!(initialized.len(), 1);
+    assert_eq!(initialized[0].version, EVENT_VERSION);
+    assert_eq!(initialized[0].policy, env.policy);
+    assert_eq!(initialized[0].pool, env.pool);
+    assert_eq!(env.policy_account().quote_mint, env.quote_mint);
+    println!("✓ Test 1 passed: policy initialized");
+
+    // Test 2: configure_honorary_position records the position and treasuries
+    env.configure_honorary_position().unwrap();
+    let policy = env.policy_account();
+    assert_ne!(policy.status & PolicyStatus::HONORARY_READY, 0);
+    assert_eq!(policy.position, env.position);
+    assert_eq!(policy.quote_treasury, env.quote_treasury);
+    assert_eq!(policy.base_fee_check, env.base_fee_check);
+    println!("✓ Test 2 passed: honorary position configured");
+
+    // Test 3: A second configure is rejected and leaves the policy untouched
+    let err = env.configure_honorary_position().unwrap_err();
+    assert!(matches!(err, ProgramError::Custom(_)));
+    assert_eq!(env.policy_account().position, env.position);
+    println!("✓ Test 3 passed: reconfiguration rejected");
+
+    println!("✅ All harness setup tests passed\n");
+}
+
+#[test]
+fn test_multi_day_paged_distribution() {
+    println!("Testing multi-page cranks across several days...");
+    let mut env = env_with_investors();
+
+    // Day 1: 90% locked; each page sizes its share from its own investors' locked total.
+    env.svm.advance_seconds(DAY_SECONDS + 5);
+    env.accrue_fees(0, 1_000_000);
+
+    // Test 1: First page opens the day and claims the fees
+    let outcome = env.crank(&[0, 1], page(0, false)).unwrap();
+    let opened = outcome.events_of::<DayOpened>();
+    assert_eq!(opened.len(), 1);
+    assert_eq!(opened[0].day_start_ts, env.svm.now());
+    let claimed = outcome.events_of::<QuoteFeesClaimed>();
+    assert_eq!(claimed[0].quote_fees_claimed, 1_000_000);
+    assert_eq!(env.investor_balance(0), 90_000);
+    assert_eq!(env.investor_balance(1), 180_000);
+    assert_eq!(env.progress_account().page_cursor, 2);
+    assert!(env.progress_account().day_open);
+    println!("✓ Test 1 passed: first page paid 2.7M-locked investors");
+
+    // Test 2: Last page pays the rest and closes the day to the creator
+    let outcome = env.crank(&[2, 3], page(2, true)).unwrap();
+    assert!(outcome.events_of::<DayOpened>().is_empty());
+    assert_eq!(env.investor_balance(2), 154_286);
+    assert_eq!(env.investor_balance(3), 205_714);
+    assert_eq!(env.token_balance(&env.creator_quote_ata), 370_000);
+    assert_eq!(env.token_balance(&env.quote_treasury), 0);
+    let closed = outcome.events_of::<CreatorPayoutDayClosed>();
+    assert_eq!(closed.len(), 1);
+    assert_eq!(closed[0].creator_quote_paid, 370_000);
+    assert_eq!(closed[0].investor_quote_paid, 630_000);
+    let progress = env.progress_account();
+    assert!(!progress.day_open);
+    assert_eq!(progress.page_cursor, 0);
+    assert_eq!(
+        env.policy_account().last_day_close_ts,
+        opened[0].day_start_ts
+    );
+    println!("✓ Test 2 passed: day closed with creator remainder");
+
+    // Test 3: The next day cannot open within 24h
+    env.svm.advance_seconds(DAY_SECONDS / 2);
+    env.accrue_fees(0, 500_000);
+    assert_eq!(
+        env.crank(&[0, 1, 2, 3], page(0, true)).unwrap_err(),
+        program_error(HonoraryQuoteFeeError::DayNotReady)
+    );
+    println!("✓ Test 3 passed: 24h gate enforced");
+
+    // Test 4: Day 2 in one page; 80% locked gives an 80% investor share
+    env.svm.advance_seconds(DAY_SECONDS / 2);
+    let outcome = env.crank(&[0, 1, 2, 3], page(0, true)).unwrap();
+    assert_eq!(outcome.events_of::<DayOpened>().len(), 1);
+    let payout = outcome.events_of::<InvestorPayoutPage>();
+    assert_eq!(payout[0].total_paid_quote, 400_000);
+    assert_eq!(env.investor_balance(0), 90_000 + 40_000);
+    assert_eq!(env.investor_balance(1), 180_000 + 80_000);
+    assert_eq!(env.investor_balance(2), 154_286 + 120_000);
+    assert_eq!(env.investor_balance(3), 205_714 + 160_000);
+    assert_eq!(env.token_balance(&env.creator_quote_ata), 370_000 + 100_000);
+    assert_eq!(env.token_balance(&env.quote_treasury), 0);
+    println!("✓ Test 4 passed: second day distributed");
+
+    println!("✅ All multi-day distribution tests passed\n");
+}
+
+#[test]
+fn test_replayed_page_is_rejected_without_side_effects() {
+    println!("Testing stale page replay...");
+    let mut env = PolicyEnv::configured(InitializePolicyParams {
+        min_page_interval_seconds: 60,
+        ..default_policy_params()
+    });
+    for deposit in [1_000_000, 2_000_000, 3_000_000, 4_000_000] {
+        env.add_investor(deposit, 10, DAY_SECONDS as u64);
+    }
+    env.svm.advance_seconds(DAY_SECONDS + 5);
+    env.accrue_fees(0, 1_000_000);
+    env.crank(&[0, 1], page(0, false)).unwrap();
+
+    // Test 1: A page inside the minimum interval fails and reports the rate limit
+    assert_eq!(
+        env.crank(&[2, 3], page(2, true)).unwrap_err(),
+        program_error(HonoraryQuoteFeeError::PageRateLimited)
+    );
+    let rejected = last_rejection(&env);
+    assert_eq!(rejected.reason, PageRejectionReason::RATE_LIMITED);
+    assert_eq!(rejected.page_cursor, 2);
+    println!("✓ Test 1 passed: early page rejected");
+
+    // Test 2: Replaying page 0 fails and reports the stale cursor
+    env.svm.advance_seconds(60);
+    env.accrue_fees(0, 10_000);
+    assert_eq!(
+        env.crank(&[0, 1], page(0, false)).unwrap_err(),
+        program_error(HonoraryQuoteFeeError::UnexpectedPageCursor)
+    );
+    let rejected = last_rejection(&env);
+    assert_eq!(rejected.version, EVENT_VERSION);
+    assert_eq!(rejected.reason, PageRejectionReason::STALE_CURSOR);
+    assert_eq!(rejected.expected_page_cursor, 0);
+    assert_eq!(rejected.page_cursor, 2);
+    let failure = env.svm.last_failure.as_ref().unwrap();
+    assert!(failure.events_of::<QuoteFeesClaimed>().is_empty());
+    println!("✓ Test 2 passed: PageRejected emitted in the failed transaction");
+
+    // Test 3: Nothing was claimed or paid
+    assert_eq!(env.investor_balance(0), 90_000);
+    assert_eq!(env.investor_balance(1), 180_000);
+    assert_eq!(env.token_balance(&env.quote_treasury), 1_000_000 - 270_000);
+    assert_eq!(env.progress_account().page_cursor, 2);
+    println!("✓ Test 3 passed: balances and cursor unchanged");
+
+    println!("✅ All stale page tests passed\n");
+}
+
+#[test]
+fn test_base_fees_abort_the_crank() {
+    println!("Testing the base-fee guard end to end...");
+    let mut env = env_with_investors();
+    env.svm.advance_seconds(DAY_SECONDS + 5);
+    env.accrue_fees(25, 1_000_000);
+
+    // Test 1: Base fees reaching the guard account fail the crank
+    assert_eq!(
+        env.crank(&[0, 1, 2, 3], page(0, true)).unwrap_err(),
+        program_error(HonoraryQuoteFeeError::BaseFeeDetected)
+    );
+    println!("✓ Test 1 passed: BaseFeeDetected");
+
+    // Test 2: The failed transaction left every account as it was
+    assert_eq!(env.token_balance(&env.quote_treasury), 0);
+    assert_eq!(env.token_balance(&env.base_fee_check), 0);
+    assert!(!env.progress_account().day_open);
+    (0..4).for_each(|index| assert_eq!(env.investor_balance(index), 0));
+    println!("✓ Test 2 passed: state rolled back");
+
+    println!("✅ All base-fee guard tests passed\n");
+}
+
+#[test]
+fn test_fully_unlocked_streams_route_fees_to_creator() {
+    println!("Testing distribution once every stream has unlocked...");
+    let mut env = PolicyEnv::configured(default_policy_params());
+    env.add_investor(5_000_000, 1, DAY_SECONDS as u64);
+    env.add_investor(5_000_000, 1, DAY_SECONDS as u64);
+    env.svm.advance_seconds(2 * DAY_SECONDS);
+    env.accrue_fees(0, 750_000);
+
+    // Test 1: Nothing is locked, so the whole claim goes to the creator
+    let outcome = env.crank(&[0, 1], page(0, true)).unwrap();
+    assert_eq!(env.investor_balance(0), 0);
+    assert_eq!(env.investor_balance(1), 0);
+    assert_eq!(env.token_balance(&env.creator_quote_ata), 750_000);
+    let closed = outcome.events_of::<CreatorPayoutDayClosed>();
+    assert_eq!(closed[0].share_bps, 0);
+    println!("✓ Test 1 passed: creator received 100%");
+
+    println!("✅ All fully unlocked tests passed\n");
+}
+
+#[test]
+fn test_snapshot_rejects_streams_changed_mid_day() {
+    println!("Testing the day-open investor snapshot...");
+    let mut env = PolicyEnv::configured(InitializePolicyParams {
+        investor_snapshot_required: true,
+        ..default_policy_params()
+    });
+    for deposit in [1_000_000, 2_000_000, 3_000_000, 4_000_000] {
+        env.add_investor(deposit, 10, DAY_SECONDS as u64);
+    }
+    env.svm.advance_seconds(DAY_SECONDS + 5);
+    env.accrue_fees(0, 1_000_000);
+
+    // Test 1: Opening a day needs a commitment
+    assert_eq!(
+        env.crank(&[0, 1], page(0, false)).unwrap_err(),
+        program_error(HonoraryQuoteFeeError::InvestorSnapshotMissing)
+    );
+    println!("✓ Test 1 passed: missing snapshot rejected");
+
+    // Test 2: The first page is checked against the commitment and pays as usual
+    let snapshot = env.investor_snapshot(&[&[0, 1], &[2, 3]]);
+    let first_page = CrankQuoteFeeParams {
+        investor_snapshot: snapshot[0],
+        remaining_snapshot: snapshot[1],
+        ..page(0, false)
+    };
+    env.crank(&[0, 1], first_page).unwrap();
+    assert_eq!(env.investor_balance(0), 90_000);
+    assert_eq!(env.investor_balance(1), 180_000);
+    assert_eq!(env.progress_account().investor_snapshot, snapshot[1]);
+    println!("✓ Test 2 passed: first page matched the snapshot");
+
+    // Test 3: A top-up after the day opened fails the page without paying anyone
+    env.top_up_investor(3, 6_000_000);
+    assert_eq!(
+        env.crank(&[2, 3], page(2, true)).unwrap_err(),
+        program_error(HonoraryQuoteFeeError::InvestorSnapshotMismatch)
+    );
+    assert_eq!(
+        last_rejection(&env).reason,
+        PageRejectionReason::SNAPSHOT_MISMATCH
+    );
+    assert_eq!(env.investor_balance(2), 0);
+    assert_eq!(env.investor_balance(3), 0);
+    assert_eq!(env.progress_account().page_cursor, 2);
+    println!("✓ Test 3 passed: topped-up stream rejected");
+
+    // Test 4: Leaving the topped-up stream out does not match the snapshot either
+    for investors in [&[2][..], &[3, 2][..]] {
+        assert_eq!(
+            env.crank(investors, page(2, true)).unwrap_err(),
+            program_error(HonoraryQuoteFeeError::InvestorSnapshotMismatch)
+        );
+    }
+    assert!(env.progress_account().day_open);
+    println!("✓ Test 4 passed: partial and reordered pages rejected");
+
+    // Test 5: The changed stream is never restored, so the day only ends by force close;
+    // the remaining page is never paid and the next day needs a fresh commitment
+    let outcome = env.force_close_day(env.authority).unwrap();
+    let closed = outcome.events_of::<DayForceClosed>();
+    assert_eq!(closed[0].investor_quote_paid, 270_000);
+    assert_eq!(env.investor_balance(2), 0);
+    assert_eq!(env.investor_balance(3), 0);
+    assert_eq!(env.token_balance(&env.creator_quote_ata), 730_000);
+    let progress = env.progress_account();
+    assert!(!progress.day_open);
+    assert_eq!(progress.investor_snapshot, [0u8; 32]);
+    println!("✓ Test 5 passed: stalled day force-closed");
+
+    println!("✅ All investor snapshot tests passed\n");
+}
+
+#[test]
+fn test_snapshot_pages_are_never_split() {
+    println!("Testing page limits on snapshot policies...");
+    let mut env = PolicyEnv::configured(InitializePolicyParams {
+        investor_snapshot_required: true,
+        ..default_policy_params()
+    });
+    for deposit in [1_000_000, 2_000_000, 3_000_000, 4_000_000] {
+        env.add_investor(deposit, 10, DAY_SECONDS as u64);
+    }
+    env.svm.advance_seconds(DAY_SECONDS + 5);
+    env.accrue_fees(0, 1_000_000);
+    let snapshot = env.investor_snapshot(&[&[0, 1], &[2, 3]]);
+    let first_page = CrankQuoteFeeParams {
+        investor_snapshot: snapshot[0],
+        remaining_snapshot: snapshot[1],
+        ..page(0, false)
+    };
+
+    // Test 1: Limits below the supplied page are rejected instead of failing the snapshot
+    for limited in [
+        CrankQuoteFeeParams {
+            max_investors: 1,
+            ..first_page.clone()
+        },
+        CrankQuoteFeeParams {
+            max_page_cursor: 1,
+            ..first_page.clone()
+        },
+    ] {
+        assert_eq!(
+            env.crank(&[0, 1], limited).unwrap_err(),
+            program_error(HonoraryQuoteFeeError::SnapshotPageTruncated)
+        );
+    }
+    assert!(!env.progress_account().day_open);
+    println!("✓ Test 1 passed: split pages rejected");
+
+    // Test 2: Limits that cover the page are accepted
+    let outcome = env
+        .crank(
+            &[0, 1],
+            CrankQuoteFeeParams {
+                max_investors: 2,
+                max_page_cursor: 4,
+                ..first_page
+            },
+        )
+        .unwrap();
+    let (_, data) = outcome.return_data.unwrap();
+    assert_eq!(
+        CrankPageResult::try_from_slice(&data)
+            .unwrap()
+            .investors_processed,
+        2
+    );
+    assert_eq!(env.progress_account().page_cursor, 2);
+    println!("✓ Test 2 passed: whole page processed");
+
+    println!("✅ All snapshot page limit tests passed\n");
+}
+
+#[test]
+fn test_whole_token_amounts_are_scaled_by_mint_decimals() {
+    println!("Testing policy amounts given in whole tokens...");
+    let mut env = PolicyEnv::new();
+    env.vesting_mint = env.base_mint;
+    let params = InitializePolicyParams {
+        y0: 10,
+        daily_cap_quote: 2,
+        min_payout_lamports: 1,
+        eligibility_rules: EligibilityRules {
+            min_locked_amount: 3,
+            ..EligibilityRules::default()
+        },
+        whole_token_amounts: true,
+        ..default_policy_params()
+    };
+
+    // Test 1: An amount that overflows once scaled is rejected
+    assert_eq!(
+        env.initialize_policy(InitializePolicyParams {
+            y0: u64::MAX / 1_000_000_000 + 1,
+            ..params.clone()
+        })
+        .unwrap_err(),
+        program_error(HonoraryQuoteFeeError::ArithmeticOverflow)
+    );
+    println!("✓ Test 1 passed: overflow rejected");
+
+    // Test 2: Vesting amounts scale by 10^9 and quote amounts by 10^6
+    let outcome = env.initialize_policy(params).unwrap();
+    let policy = env.policy_account();
+    assert_eq!(policy.y0, 10_000_000_000);
+    assert_eq!(policy.eligibility_rules.min_locked_amount, 3_000_000_000);
+    assert_eq!(policy.daily_cap_quote, 2_000_000);
+    assert_eq!(policy.min_payout_lamports, 1_000_000);
+    let initialized = outcome.events_of::<PolicyInitialized>();
+    assert_eq!(initialized[0].y0, 10_000_000_000);
+    assert_eq!(initialized[0].daily_cap_quote, 2_000_000);
+    println!("✓ Test 2 passed: amounts stored in base units");
+
+    println!("✅ All whole token amount tests passed\n");
+}
+
+#[test]
+fn test_investors_vesting_the_base_mint_are_paid_in_quote() {
+    println!("Testing a vesting mint separate from the quote mint...");
+    let mut env = PolicyEnv::new();
+    env.vesting_mint = env.base_mint;
+
+    // Test 1: The policy records the vesting mint and both mints' decimals
+    let outcome = env.initialize_policy(default_policy_params()).unwrap();
+    env.configure_honorary_position().unwrap();
+    let initialized = outcome.events_of::<PolicyInitialized>();
+    assert_eq!(initialized[0].vesting_mint, env.base_mint);
+    assert_eq!(
+        (
+            initialized[0].quote_decimals,
+            initialized[0].vesting_decimals
+        ),
+        (6, 9)
+    );
+    let policy = env.policy_account();
+    assert_eq!(policy.vesting_mint, env.base_mint);
+    assert_eq!((policy.quote_decimals, policy.vesting_decimals), (6, 9));
+    println!("✓ Test 1 passed: vesting mint and decimals stored");
+
+    // Test 2: Base-token streams weigh quote payouts into the investors' quote ATAs
+    for deposit in [1_000_000, 2_000_000, 3_000_000, 4_000_000] {
+        env.add_investor(deposit, 10, DAY_SECONDS as u64);
+    }
+    env.svm.advance_seconds(DAY_SECONDS + 5);
+    env.accrue_fees(0, 1_000_000);
+    env.crank(&[0, 1, 2, 3], page(0, true)).unwrap();
+    assert_eq!(env.investor_balance(0), 90_000);
+    assert_eq!(env.investor_balance(3), 360_000);
+    assert_eq!(env.token_balance(&env.creator_quote_ata), 100_000);
+    println!("✓ Test 2 passed: base-vesting investors paid in quote");
+
+    // Test 3: Streams of the quote mint no longer count
+    env.vesting_mint = env.quote_mint;
+    let quote_stream = env.add_investor(1_000_000, 10, DAY_SECONDS as u64);
+    env.svm.advance_seconds(DAY_SECONDS);
+    assert_eq!(
+        env.crank(&[0, quote_stream], page(0, true)).unwrap_err(),
+        program_error(HonoraryQuoteFeeError::StreamflowMintMismatch)
+    );
+    println!("✓ Test 3 passed: quote-mint stream rejected");
+
+    println!("✅ All vesting mint tests passed\n");
+}
+
+#[test]
+fn test_pages_stop_early_and_report_the_next_cursor() {
+    println!("Testing partially consumed crank pages...");
+    let mut env = env_with_investors();
+    env.svm.advance_seconds(DAY_SECONDS + 5);
+    env.accrue_fees(0, 1_000_000);
+    let page_result = |outcome: &TxOutcome| {
+        let (program_id, data) = outcome.return_data.clone().unwrap();
+        assert_eq!(program_id, honorary_quote_fee::ID);
+        CrankPageResult::try_from_slice(&data).unwrap()
+    };
+
+    // Test 1: `max_investors` leaves the remaining pairs for the next page
+    let outcome = env
+        .crank(
+            &[0, 1, 2, 3],
+            CrankQuoteFeeParams {
+                max_investors: 2,
+                ..page(0, true)
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        page_result(&outcome),
+        CrankPageResult {
+            investors_processed: 2,
+            next_page_cursor: 2,
+            day_closed: false,
+        }
+    );
+    assert_eq!(env.investor_balance(0), 90_000);
+    assert_eq!(env.investor_balance(2), 0);
+    assert!(env.progress_account().day_open);
+    println!("✓ Test 1 passed: page capped at two investors");
+
+    // Test 2: A tight compute budget stops after the investors it can pay
+    env.svm.compute_unit_limit = CRANK_COMPUTE_RESERVE + INVESTOR_PAYOUT_COMPUTE_UNITS;
+    let outcome = env.crank(&[2, 3], page(2, true)).unwrap();
+    assert_eq!(
+        page_result(&outcome),
+        CrankPageResult {
+            investors_processed: 1,
+            next_page_cursor: 3,
+            day_closed: false,
+        }
+    );
+    println!("✓ Test 2 passed: compute budget ended the page early");
+
+    // Test 3: Resuming from the reported cursor closes the day
+    env.svm.compute_unit_limit = MAX_COMPUTE_UNIT_LIMIT;
+    let outcome = env.crank(&[3], page(3, true)).unwrap();
+    assert_eq!(
+        page_result(&outcome),
+        CrankPageResult {
+            investors_processed: 1,
+            next_page_cursor: 0,
+            day_closed: true,
+        }
+    );
+    let paid: u64 = (0..4).map(|index| env.investor_balance(index)).sum();
+    assert_eq!(paid + env.token_balance(&env.creator_quote_ata), 1_000_000);
+    assert!(!env.progress_account().day_open);
+    println!("✓ Test 3 passed: resumed page closed the day");
+
+    // Test 4: Rejected pages report the cursor to resume from
+    env.svm.advance_seconds(DAY_SECONDS);
+    env.crank(&[0, 1], page(0, false)).unwrap();
+    assert_eq!(
+        env.crank(&[0, 1], page(0, false)).unwrap_err(),
+        program_error(HonoraryQuoteFeeError::UnexpectedPageCursor)
+    );
+    assert_eq!(last_rejection(&env).page_cursor, 2);
+    println!("✓ Test 4 passed: stale page reported the live cursor");
+
+    println!("✅ All partial page tests passed\n");
+}
+
+#[test]
+fn test_creator_share_vests_through_escrow() {
+    println!("Testing creator proceeds routed through the vesting escrow...");
+    let mut env = PolicyEnv::configured(InitializePolicyParams {
+        timelock_seconds: 3_600,
+        ..default_policy_params()
+    });
+    // The schedule starts once the timelock has elapsed
+    let start_ts = env.svm.now() + 3_600;
+    let schedule = CreatorVestingSchedule {
+        start_ts,
+        cliff_seconds: 3 * DAY_SECONDS,
+        duration_seconds: 4 * DAY_SECONDS,
+    };
+    let vesting_change = PolicyChange {
+        creator_vesting: Some(schedule),
+        ..Default::default()
+    };
+
+    // Test 1: The switch needs an initialized escrow and a valid schedule
+    assert_eq!(
+        env.queue_policy_change(vesting_change.clone()).unwrap_err(),
+        program_error(HonoraryQuoteFeeError::CreatorEscrowMissing)
+    );
+    let outcome = env.initialize_creator_escrow().unwrap();
+    assert_eq!(outcome.events_of::<CreatorEscrowInitialized>().len(), 1);
+    assert_eq!(env.policy_account().creator_escrow, Pubkey::default());
+    assert_eq!(
+        env.queue_policy_change(PolicyChange {
+            creator_vesting: Some(CreatorVestingSchedule {
+                cliff_seconds: 5 * DAY_SECONDS,
+                ..schedule
+            }),
+            ..Default::default()
+        })
+        .unwrap_err(),
+        program_error(HonoraryQuoteFeeError::InvalidVestingSchedule)
+    );
+    println!("✓ Test 1 passed: escrow required and invalid schedule rejected");
+
+    // Test 2: The switch waits for the timelock, then applies the schedule
+    env.queue_policy_change(vesting_change.clone()).unwrap();
+    assert_eq!(
+        env.execute_policy_change().unwrap_err(),
+        program_error(HonoraryQuoteFeeError::PolicyChangeNotReady)
+    );
+    assert_eq!(env.policy_account().creator_escrow, Pubkey::default());
+    env.svm.advance_seconds(3_600);
+    env.execute_policy_change().unwrap();
+    let policy = env.policy_account();
+    assert_eq!(policy.creator_escrow, env.creator_escrow);
+    assert_eq!(policy.creator_vesting.cliff_seconds, 3 * DAY_SECONDS);
+    assert_eq!(
+        env.queue_policy_change(vesting_change).unwrap_err(),
+        program_error(HonoraryQuoteFeeError::InvalidCreatorEscrow)
+    );
+    println!("✓ Test 2 passed: escrow activated once through the timelock");
+
+    // Test 3: Day close deposits the creator remainder into the escrow vault
+    env.add_investor(5_000_000, 1, DAY_SECONDS as u64);
+    env.svm.advance_seconds(2 * DAY_SECONDS);
+    env.accrue_fees(0, 800_000);
+    let outcome = env.crank(&[0], page(0, true)).unwrap();
+    assert_eq!(env.token_balance(&env.creator_escrow_vault), 800_000);
+    assert_eq!(env.token_balance(&env.creator_quote_ata), 0);
+    assert_eq!(env.creator_escrow_account().total_deposited, 800_000);
+    let escrowed = outcome.events_of::<CreatorQuoteEscrowed>();
+    assert_eq!(escrowed.len(), 1);
+    assert_eq!(escrowed[0].amount, 800_000);
+    println!("✓ Test 3 passed: creator share escrowed");
+
+    // Test 4: Nothing is released before the cliff
+    assert_eq!(
+        env.withdraw_creator_vested().unwrap_err(),
+        program_error(HonoraryQuoteFeeError::NothingVested)
+    );
+    println!("✓ Test 4 passed: cliff enforced");
+
+    // Test 5: The deposit vests from its own day close, not from the schedule start
+    env.svm.advance_seconds(DAY_SECONDS);
+    assert_eq!(
+        env.withdraw_creator_vested().unwrap_err(),
+        program_error(HonoraryQuoteFeeError::NothingVested)
+    );
+    env.svm.advance_seconds(2 * DAY_SECONDS);
+    let outcome = env.withdraw_creator_vested().unwrap();
+    assert_eq!(env.token_balance(&env.creator_quote_ata), 600_000);
+    assert_eq!(env.token_balance(&env.creator_escrow_vault), 200_000);
+    let withdrawn = outcome.events_of::<CreatorVestedWithdrawn>();
+    assert_eq!(withdrawn[0].amount, 600_000);
+    assert_eq!(withdrawn[0].total_withdrawn, 600_000);
+    println!("✓ Test 5 passed: 3/4 released at the deposit's cliff");
+
+    // Test 6: The rest is released once the duration has elapsed
+    env.svm.advance_seconds(DAY_SECONDS);
+    env.withdraw_creator_vested().unwrap();
+    assert_eq!(env.token_balance(&env.creator_quote_ata), 800_000);
+    assert_eq!(env.token_balance(&env.creator_escrow_vault), 0);
+    assert_eq!(
+        env.withdraw_creator_vested().unwrap_err(),
+        program_error(HonoraryQuoteFeeError::NothingVested)
+    );
+    println!("✓ Test 6 passed: fully vested and drained");
+
+    // Test 7: A deposit made after the schedule's window ended still vests in full
+    assert!(env.svm.now() > start_ts + 4 * DAY_SECONDS);
+    env.accrue_fees(0, 400_000);
+    env.crank(&[0], page(0, true)).unwrap();
+    assert_eq!(env.token_balance(&env.creator_escrow_vault), 400_000);
+    assert_eq!(
+        env.withdraw_creator_vested().unwrap_err(),
+        program_error(HonoraryQuoteFeeError::NothingVested)
+    );
+    env.svm.advance_seconds(3 * DAY_SECONDS);
+    env.withdraw_creator_vested().unwrap();
+    assert_eq!(env.token_balance(&env.creator_quote_ata), 1_100_000);
+    println!("✓ Test 7 passed: late deposit held until its own cliff");
+
+    println!("✅ All creator vesting escrow tests passed\n");
+}
+
+#[test]
+fn test_force_close_day_after_grace() {
+    println!("Testing force_close_day on a stalled day...");
+    let mut env = PolicyEnv::configured(InitializePolicyParams {
+        force_close_grace_seconds: 3_600,
+        ..default_policy_params()
+    });
+    for deposit in [1_000_000, 2_000_000, 3_000_000, 4_000_000] {
+        env.add_investor(deposit, 10, DAY_SECONDS as u64);
+    }
+    env.svm.advance_seconds(DAY_SECONDS + 5);
+    env.accrue_fees(0, 1_000_000);
+    env.crank(&[0, 1], page(0, false)).unwrap();
+    let day_start_ts = env.progress_account().day_start_ts;
+
+    // Test 1: Strangers cannot close the day before the grace period ends
+    let stranger = Pubkey::new_unique();
+    env.svm.advance_seconds(DAY_SECONDS);
+    assert_eq!(
+        env.force_close_day(stranger).unwrap_err(),
+        program_error(HonoraryQuoteFeeError::ForceCloseNotReady)
+    );
+    assert!(env.progress_account().day_open);
+    println!("✓ Test 1 passed: ForceCloseNotReady inside the grace period");
+
+    // Test 2: After the grace period anyone closes it; the target is the share sized by
+    // the one page cranked, so the creator receives everything else
+    env.svm.advance_seconds(3_600);
+    let outcome = env.force_close_day(stranger).unwrap();
+    let closed = outcome.events_of::<DayForceClosed>();
+    assert_eq!(closed.len(), 1);
+    assert_eq!(closed[0].closed_by, stranger);
+    assert_eq!(closed[0].day_start_ts, day_start_ts);
+    assert_eq!(closed[0].creator_quote_paid, 730_000);
+    assert_eq!(closed[0].investor_quote_paid, 270_000);
+    assert_eq!(closed[0].unpaid_investor_quote, 0);
+    assert_eq!(closed[0].released_carry, 0);
+    assert_eq!(outcome.events_of::<CreatorPayoutDayClosed>().len(), 1);
+    assert_eq!(env.token_balance(&env.creator_quote_ata), 730_000);
+    assert_eq!(env.token_balance(&env.quote_treasury), 0);
+    let progress = env.progress_account();
+    assert!(!progress.day_open);
+    assert_eq!(progress.page_cursor, 0);
+    assert_eq!(env.policy_account().last_day_close_ts, day_start_ts);
+    println!("✓ Test 2 passed: day force-closed by a stranger");
+
+    // Test 3: A closed day cannot be force-closed again, and the next day opens
+    assert_eq!(
+        env.force_close_day(env.authority).unwrap_err(),
+        program_error(HonoraryQuoteFeeError::DayNotOpen)
+    );
+    let outcome = env.crank(&[0, 1, 2, 3], page(0, true)).unwrap();
+    assert_eq!(outcome.events_of::<DayOpened>().len(), 1);
+    assert!(!env.progress_account().day_open);
+    println!("✓ Test 3 passed: next day opened after the force close");
+
+    println!("✅ All force close tests passed\n");
+}
+
+#[test]
+fn test_create_honorary_position_through_damm() {
+    println!("Testing create_honorary_position against the cp-amm stub...");
+    let mut env = PolicyEnv::new();
+    env.initialize_policy(default_policy_params()).unwrap();
+
+    // Test 1: DAMM creates the position and mints its NFT to the honorary PDA
+    let outcome = env.create_honorary_position().unwrap();
+    let initialized = outcome.events_of::<HonoraryPositionInitialized>();
+    assert_eq!(initialized.len(), 1);
+    assert_eq!(initialized[0].position, env.position);
+    let policy = env.policy_account();
+    assert_ne!(policy.status & PolicyStatus::HONORARY_READY, 0);
+    assert_eq!(policy.position, env.position);
+    assert_eq!(policy.position_nft_mint, env.position_nft_mint);
+    assert_eq!(policy.position_nft_account, env.position_nft_account);
+    let nft = spl_token::state::Account::unpack(
+        &env.svm.account(&env.position_nft_account).unwrap().data,
+    )
+    .unwrap();
+    assert_eq!(nft.owner, env.honorary_position);
+    assert_eq!(nft.mint, env.position_nft_mint);
+    assert_eq!(nft.amount, 1);
+    let position = DammPosition::deserialize(&env.svm.account(&env.position).unwrap().data)
+        .expect("DAMM position layout");
+    assert_eq!(position.pool, env.pool);
+    assert_eq!(position.nft_mint, env.position_nft_mint);
+    println!("✓ Test 1 passed: position created and NFT held by the honorary PDA");
+
+    // Test 2: The crank claims fees through the created position
+    env.add_investor(1_000_000, 10, DAY_SECONDS as u64);
+    env.svm.advance_seconds(DAY_SECONDS + 5);
+    env.accrue_fees(0, 500_000);
+    let outcome = env.crank(&[0], page(0, true)).unwrap();
+    assert_eq!(
+        outcome.events_of::<QuoteFeesClaimed>()[0].quote_fees_claimed,
+        500_000
+    );
+    assert_eq!(env.investor_balance(0), 45_000);
+    println!("✓ Test 2 passed: fees claimed through the new position");
+
+    // Test 3: A second position cannot replace the first
+    let position = env.position;
+    assert!(env.create_honorary_position().is_err());
+    assert_eq!(env.policy_account().position, position);
+    println!("✓ Test 3 passed: second create rejected");
+
+    // Test 4: A position without DAMM's account discriminator is not decoded
+    let mut env = PolicyEnv::new();
+    env.initialize_policy(default_policy_params()).unwrap();
+    let mut position = env.svm.account(&env.position).unwrap().clone();
+    position.data.drain(..8);
+    env.svm.set_account(env.position, position);
+    assert_eq!(
+        env.configure_honorary_position().unwrap_err(),
+        program_error(HonoraryQuoteFeeError::InvalidPositionAccount)
+    );
+    assert_eq!(env.policy_account().position, Pubkey::default());
+    println!("✓ Test 4 passed: position without a discriminator rejected");
+
+    println!("✅ All create_honorary_position tests passed\n");
+}
+
+#[test]
+fn test_partner_fees_are_claimed_by_the_honorary_partner() {
+    println!("Testing partner fee capture...");
+    let partner_params = InitializePolicyParams {
+        fee_capture_mode: FeeCaptureMode::PARTNER_FEES,
+        ..default_policy_params()
+    };
+    let mut env = PolicyEnv::new();
+
+    // Test 1: Partner capture needs the honorary PDA as the pool's partner
+    env.set_pool_partner(Pubkey::new_unique());
+    assert_eq!(
+        env.initialize_policy(partner_params.clone()).unwrap_err(),
+        program_error(HonoraryQuoteFeeError::PartnerMismatch)
+    );
+    println!("✓ Test 1 passed: foreign partner rejected at initialization");
+
+    // Test 2: The crank claims only the quote side of the partner fees
+    env.set_pool_partner(env.honorary_position);
+    env.initialize_policy(partner_params).unwrap();
+    env.configure_honorary_position().unwrap();
+    env.add_investor(1_000_000, 10, DAY_SECONDS as u64);
+    env.svm.advance_seconds(DAY_SECONDS + 5);
+    env.accrue_partner_fees(25, 600_000);
+    let outcome = env.crank(&[0], page(0, true)).unwrap();
+    assert_eq!(
+        outcome.events_of::<QuoteFeesClaimed>()[0].quote_fees_claimed,
+        600_000
+    );
+    assert_eq!(env.investor_balance(0), 54_000);
+    assert_eq!(env.token_balance(&env.base_fee_check), 0);
+    let pool = &env.svm.account(&env.pool).unwrap().data;
+    assert_eq!(read_u64(pool, pool_layout::PARTNER_A_FEE), 25);
+    assert_eq!(read_u64(pool, pool_layout::PARTNER_B_FEE), 0);
+    println!("✓ Test 2 passed: partner quote fees claimed, base left in the pool");
+
+    // Test 3: A partner changed after initialization stops the crank
+    env.set_pool_partner(Pubkey::new_unique());
+    env.svm.advance_seconds(DAY_SECONDS);
+    env.accrue_partner_fees(0, 600_000);
+    assert_eq!(
+        env.crank(&[0], page(0, true)).unwrap_err(),
+        program_error(HonoraryQuoteFeeError::PartnerMismatch)
+    );
+    assert!(!env.progress_account().day_open);
+    println!("✓ Test 3 passed: PartnerMismatch on a replaced partner");
+
+    println!("✅ All partner fee tests passed\n");
+}
+
+#[test]
+fn test_manual_deposits_are_distributed_with_claimed_fees() {
+    println!("Testing deposit_quote_for_distribution...");
+    let mut env = PolicyEnv::new();
+    env.initialize_policy(default_policy_params()).unwrap();
+    let depositor = Pubkey::new_unique();
+    let source = Pubkey::new_unique();
+    env.svm.set_account(
+        source,
+        fixtures::token_account(env.quote_mint, depositor, 1_000_000),
+    );
+
+    // Test 1: Deposits wait for the honorary position
+    assert_eq!(
+        env.deposit_quote(depositor, source, 400_000).unwrap_err(),
+        program_error(HonoraryQuoteFeeError::HonoraryPositionNotReady)
+    );
+    println!("✓ Test 1 passed: HonoraryPositionNotReady");
+
+    // Test 2: Zero deposits are rejected
+    env.configure_honorary_position().unwrap();
+    assert_eq!(
+        env.deposit_quote(depositor, source, 0).unwrap_err(),
+        program_error(HonoraryQuoteFeeError::InvalidDepositAmount)
+    );
+    println!("✓ Test 2 passed: InvalidDepositAmount");
+
+    // Test 3: A deposit moves quote into the treasury and is queued for the next page
+    let outcome = env.deposit_quote(depositor, source, 400_000).unwrap();
+    let deposited = outcome.events_of::<QuoteDeposited>();
+    assert_eq!(deposited.len(), 1);
+    assert_eq!(deposited[0].version, EVENT_VERSION);
+    assert_eq!(deposited[0].policy, env.policy);
+    assert_eq!(deposited[0].depositor, depositor);
+    assert_eq!(deposited[0].amount, 400_000);
+    assert_eq!(deposited[0].pending_deposit_quote, 400_000);
+    assert_eq!(env.token_balance(&source), 600_000);
+    assert_eq!(env.token_balance(&env.quote_treasury), 400_000);
+    assert_eq!(env.progress_account().pending_deposit_quote, 400_000);
+    println!("✓ Test 3 passed: deposit queued");
+
+    // Test 4: The next crank counts the deposit alongside the DAMM claim
+    env.add_investor(1_000_000, 10, DAY_SECONDS as u64);
+    env.svm.advance_seconds(DAY_SECONDS + 5);
+    env.accrue_fees(0, 600_000);
+    let outcome = env.crank(&[0], page(0, true)).unwrap();
+    let claimed = outcome.events_of::<QuoteFeesClaimed>();
+    let by_source: Vec<(u8, u64)> = claimed
+        .iter()
+        .map(|event| (event.source, event.quote_fees_claimed))
+        .collect();
+    assert!(by_source.contains(&(FeeSourceKind::DAMM_V2, 600_000)));
+    assert!(by_source.contains(&(FeeSourceKind::MANUAL_DEPOSIT, 400_000)));
+    assert_eq!(env.progress_account().pending_deposit_quote, 0);
+    assert_eq!(env.investor_balance(0), 90_000);
+    assert_eq!(env.token_balance(&env.creator_quote_ata), 910_000);
+    println!("✓ Test 4 passed: deposit distributed with the claimed fees");
+
+    println!("✅ All manual deposit tests passed\n");
+}
+
+#[test]
+fn test_cranker_allowlist_gates_the_crank() {
+    println!("Testing the cranker allowlist...");
+    let mut env = env_with_investors();
+    env.svm.advance_seconds(DAY_SECONDS + 5);
+    env.accrue_fees(0, 1_000_000);
+
+    // Test 1: Creating the allowlist reports its initial mode
+    let outcome = env.initialize_cranker_allowlist(false).unwrap();
+    let updated = outcome.events_of::<CrankerAllowlistUpdated>();
+    assert_eq!(updated.len(), 1);
+    assert_eq!(updated[0].policy, env.policy);
+    assert_eq!(updated[0].cranker, Pubkey::default());
+    assert!(!updated[0].allowed);
+    assert!(!updated[0].permissionless);
+    assert_eq!(
+        env.policy_account().cranker_allowlist,
+        env.cranker_allowlist
+    );
+    println!("✓ Test 1 passed: CrankerAllowlistUpdated emitted on creation");
+
+    // Test 2: Unlisted crankers are rejected until added
+    assert_eq!(
+        env.crank(&[0, 1, 2, 3], page(0, true)).unwrap_err(),
+        program_error(HonoraryQuoteFeeError::CrankerNotAllowed)
+    );
+    let cranker = env.cranker;
+    let outcome = env.update_cranker_allowlist(cranker, true).unwrap();
+    let updated = outcome.events_of::<CrankerAllowlistUpdated>();
+    assert_eq!((updated[0].cranker, updated[0].allowed), (cranker, true));
+    env.crank(&[0, 1, 2, 3], page(0, true)).unwrap();
+    assert!(!env.progress_account().day_open);
+    println!("✓ Test 2 passed: listed cranker closed the day");
+
+    println!("✅ All cranker allowlist tests passed\n");
+}
+
+#[test]
+fn test_setup_instructions_emit_events() {
+    println!("Testing events of the optional account setup instructions...");
+    let mut env = PolicyEnv::configured(default_policy_params());
+
+    // Test 1: initialize_day_history reports the history account
+    let outcome = env.initialize_day_history().unwrap();
+    let initialized = outcome.events_of::<DayHistoryInitialized>();
+    assert_eq!(initialized.len(), 1);
+    assert_eq!(initialized[0].version, EVENT_VERSION);
+    assert_eq!(initialized[0].policy, env.policy);
+    assert_eq!(initialized[0].day_history, env.day_history);
+    println!("✓ Test 1 passed: DayHistoryInitialized emitted");
+
+    // Test 2: initialize_blocklist reports the blocklist and its forfeiture account
+    let forfeiture_ata = Pubkey::new_unique();
+    env.svm.set_account(
+        forfeiture_ata,
+        fixtures::token_account(env.quote_mint, env.authority, 0),
+    );
+    let outcome = env.initialize_blocklist(forfeiture_ata).unwrap();
+    let initialized = outcome.events_of::<BlocklistInitialized>();
+    assert_eq!(initialized.len(), 1);
+    assert_eq!(initialized[0].version, EVENT_VERSION);
+    assert_eq!(initialized[0].policy, env.policy);
+    assert_eq!(initialized[0].blocklist, env.blocklist);
+    assert_eq!(initialized[0].forfeiture_ata, forfeiture_ata);
+    println!("✓ Test 2 passed: BlocklistInitialized emitted");
+
+    // Test 3: initialize_policy_group reports the group and its primary policy
+    let member_policy = Pubkey::new_unique();
+    let ready_policy = env.svm.account(&env.policy).unwrap().clone();
+    env.svm.set_account(member_policy, ready_policy);
+    let outcome = env.initialize_policy_group().unwrap();
+    let initialized = outcome.events_of::<PolicyGroupInitialized>();
+    assert_eq!(initialized.len(), 1);
+    assert_eq!(initialized[0].version, EVENT_VERSION);
+    assert_eq!(initialized[0].group, env.policy_group);
+    assert_eq!(initialized[0].primary_policy, env.policy);
+    assert_eq!(initialized[0].quote_mint, env.quote_mint);
+    println!("✓ Test 3 passed: PolicyGroupInitialized emitted");
+
+    // Test 4: add_policy_to_group reports the member and the new member count
+    let outcome = env.add_policy_to_group(member_policy).unwrap();
+    let added = outcome.events_of::<PolicyAddedToGroup>();
+    assert_eq!(added.len(), 1);
+    assert_eq!(added[0].version, EVENT_VERSION);
+    assert_eq!(added[0].group, env.policy_group);
+    assert_eq!(added[0].policy, member_policy);
+    assert_eq!(added[0].member_count, 1);
+    println!("✓ Test 4 passed: PolicyAddedToGroup emitted");
+
+    println!("✅ All setup event tests passed\n");
+}
+
+#[test]
+fn test_distribution_hook_cannot_spend_the_treasury() {
+    println!("Testing the distribution hook signer...");
+    let mut env = env_with_investors();
+    env.configure_distribution_hook(HOOK_PROGRAM_ID, HookFailureMode::FATAL)
+        .unwrap();
+    env.svm.advance_seconds(DAY_SECONDS + 5);
+    env.accrue_fees(0, 1_000_000);
+    env.crank(&[0, 1], page(0, false)).unwrap();
+    let treasury_before = env.token_balance(&env.quote_treasury);
+    let thief = Pubkey::new_unique();
+    env.svm
+        .set_account(thief, fixtures::token_account(env.quote_mint, thief, 0));
+    let quote_treasury = env.quote_treasury;
+    let hostile_page = move |authority: Pubkey| {
+        (
+            [
+                AccountMeta::new(quote_treasury, false),
+                AccountMeta::new(thief, false),
+                AccountMeta::new_readonly(authority, false),
+            ],
+            CrankQuoteFeeParams {
+                hook_account_count: 3,
+                ..page(2, true)
+            },
+        )
+    };
+
+    // Test 1: The honorary PDA never signs the hook call, so spending with it aborts the
+    // crank and nothing moves
+    let (hook_accounts, params) = hostile_page(env.honorary_position);
+    assert_eq!(
+        env.crank_with_hook_accounts(&[2, 3], &hook_accounts, params)
+            .unwrap_err(),
+        ProgramError::MissingRequiredSignature
+    );
+    println!("✓ Test 1 passed: forwarded honorary PDA cannot sign");
+
+    // Test 2: The hook PDA does sign but owns no treasury, so spending with it aborts too
+    let (hook_accounts, params) = hostile_page(hook_authority_address(&env.policy).0);
+    assert_eq!(
+        env.crank_with_hook_accounts(&[2, 3], &hook_accounts, params)
+            .unwrap_err(),
+        ProgramError::Custom(spl_token::error::TokenError::OwnerMismatch as u32)
+    );
+    assert_eq!(env.token_balance(&thief), 0);
+    assert_eq!(env.token_balance(&env.quote_treasury), treasury_before);
+    assert_eq!(env.investor_balance(3), 0);
+    assert!(env.progress_account().day_open);
+    println!("✓ Test 2 passed: hook PDA owns nothing");
+
+    // Test 3: A hook that only reads its accounts runs signed and the day closes
+    let outcome = env.crank(&[2, 3], page(2, true)).unwrap();
+    assert!(outcome.events_of::<DistributionHookFailed>().is_empty());
+    assert!(!env.progress_account().day_open);
+    assert_eq!(env.token_balance(&env.creator_quote_ata), 370_000);
+    println!("✓ Test 3 passed: hook invoked and the day closed");
+
+    println!("✅ All distribution hook signer tests passed\n");
+}
+
+#[test]
+fn test_ignored_hook_failures_cover_only_hook_accounts() {
+    println!("Testing HookFailureMode::IGNORE...");
+    let mut env = env_with_investors();
+    env.configure_distribution_hook(HOOK_PROGRAM_ID, HookFailureMode::FATAL)
+        .unwrap();
+    env.svm.advance_seconds(DAY_SECONDS + 5);
+    env.accrue_fees(0, 1_000_000);
+    env.crank(&[0, 1], page(0, false)).unwrap();
+
+    // Test 1: In fatal mode a page without the hook accounts cannot close the day
+    env.omit_hook_accounts = true;
+    assert_eq!(
+        env.crank(&[2, 3], page(2, true)).unwrap_err(),
+        program_error(HonoraryQuoteFeeError::InvalidDistributionHook)
+    );
+    println!("✓ Test 1 passed: missing hook accounts fatal");
+
+    // Test 2: In ignore mode the day closes without the hook and reports it
+    env.configure_distribution_hook(HOOK_PROGRAM_ID, HookFailureMode::IGNORE)
+        .unwrap();
+    let outcome = env.crank(&[2, 3], page(2, true)).unwrap();
+    let failed = outcome.events_of::<DistributionHookFailed>();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].hook_program, HOOK_PROGRAM_ID);
+    assert!(!env.progress_account().day_open);
+    println!("✓ Test 2 passed: missing hook accounts skipped");
+
+    // Test 3: A hook failing inside its CPI still aborts the crank in ignore mode
+    env.omit_hook_accounts = false;
+    env.svm.advance_seconds(DAY_SECONDS);
+    env.accrue_fees(0, 1_000_000);
+    let hook_accounts = [
+        AccountMeta::new(env.quote_treasury, false),
+        AccountMeta::new(env.creator_quote_ata, false),
+        AccountMeta::new_readonly(env.honorary_position, false),
+    ];
+    assert_eq!(
+        env.crank_with_hook_accounts(
+            &[0, 1, 2, 3],
+            &hook_accounts,
+            CrankQuoteFeeParams {
+                hook_account_count: 3,
+                ..page(0, true)
+            },
+        )
+        .unwrap_err(),
+        ProgramError::MissingRequiredSignature
+    );
+    assert!(!env.progress_account().day_open);
+    assert_eq!(env.investor_balance(0), 90_000);
+    println!("✓ Test 3 passed: hook CPI failure aborts");
+
+    println!("✅ All ignored hook failure tests passed\n");
+}
+
+#[test]
+fn test_policy_change_waits_for_timelock_and_closed_day() {
+    println!("Testing timelocked policy changes end to end...");
+    let mut env = PolicyEnv::configured(InitializePolicyParams {
+        timelock_seconds: DAY_SECONDS,
+        ..default_policy_params()
+    });
+    for deposit in [1_000_000, 2_000_000, 3_000_000, 4_000_000] {
+        env.add_investor(deposit, 10, DAY_SECONDS as u64);
+    }
+    let old_creator_ata = env.creator_quote_ata;
+    let new_creator_ata = Pubkey::new_unique();
+    env.svm.set_account(
+        new_creator_ata,
+        fixtures::token_account(env.quote_mint, env.authority, 0),
+    );
+    let change = PolicyChange {
+        investor_fee_share_bps: Some(5_000),
+        y0: None,
+        creator_quote_ata: Some(new_creator_ata),
+        creator_vesting: None,
+    };
+
+    // Test 1: Queuing records the change with an eta one timelock away
+    let outcome = env.queue_policy_change(change.clone()).unwrap();
+    let queued = outcome.events_of::<PolicyChangeQueued>();
+    assert_eq!(queued.len(), 1);
+    assert_eq!(queued[0].eta, env.svm.now() + DAY_SECONDS);
+    assert_eq!(queued[0].change.investor_fee_share_bps, Some(5_000));
+    println!("✓ Test 1 passed: change queued");
+
+    // Test 2: Executing before the eta fails
+    assert_eq!(
+        env.execute_policy_change().unwrap_err(),
+        program_error(HonoraryQuoteFeeError::PolicyChangeNotReady)
+    );
+    println!("✓ Test 2 passed: PolicyChangeNotReady");
+
+    // Test 3: Executing while a day is open fails, and the open day keeps the old terms
+    env.svm.advance_seconds(DAY_SECONDS + 5);
+    env.accrue_fees(0, 1_000_000);
+    env.crank(&[0, 1], page(0, false)).unwrap();
+    assert_eq!(
+        env.execute_policy_change().unwrap_err(),
+        program_error(HonoraryQuoteFeeError::PolicyChangeDuringDay)
+    );
+    env.crank(&[2, 3], page(2, true)).unwrap();
+    assert_eq!(env.investor_balance(3), 205_714);
+    assert_eq!(env.token_balance(&old_creator_ata), 370_000);
+    println!("✓ Test 3 passed: PolicyChangeDuringDay");
+
+    // Test 4: Once the day closed anyone executes it and the pending account is closed
+    let outcome = env.execute_policy_change().unwrap();
+    let executed = outcome.events_of::<PolicyChangeExecuted>();
+    assert_eq!(executed.len(), 1);
+    assert_eq!(executed[0].change.creator_quote_ata, Some(new_creator_ata));
+    let policy = env.policy_account();
+    assert_eq!(policy.investor_fee_share_bps, 5_000);
+    assert_eq!(policy.creator_quote_ata, new_creator_ata);
+    assert!(env
+        .svm
+        .account(&env.pending_change)
+        .is_none_or(|account| account.lamports == 0));
+    println!("✓ Test 4 passed: change executed");
+
+    // Test 5: The next day pays at most 50% to investors and the rest to the new creator ATA
+    env.creator_quote_ata = new_creator_ata;
+    env.svm.advance_seconds(DAY_SECONDS);
+    env.accrue_fees(0, 1_000_000);
+    env.crank(&[0, 1, 2, 3], page(0, true)).unwrap();
+    assert_eq!(env.investor_balance(0), 90_000 + 50_000);
+    assert_eq!(env.investor_balance(3), 205_714 + 200_000);
+    assert_eq!(env.token_balance(&new_creator_ata), 500_000);
+    assert_eq!(env.token_balance(&old_creator_ata), 370_000);
+    println!("✓ Test 5 passed: new terms applied from the next day");
+
+    println!("✅ All policy change tests passed\n");
+}
+
+#[test]
+fn test_migrate_policy_accounts_from_baseline_layout() {
+    println!("Testing migration of policy accounts in the first deployment's layout...");
+    let mut env = env_with_investors();
+    let policy_data = env.svm.account(&env.policy).unwrap().data.clone();
+    let progress_data = env.svm.account(&env.progress).unwrap().data.clone();
+    env.downgrade_to_baseline_layout();
+    assert!(env.svm.account(&env.policy).unwrap().data.len() < Policy::LEN);
+    assert!(env.svm.account(&env.progress).unwrap().data.len() < DistributionProgress::LEN);
+
+    // Test 1: Baseline accounts cannot be loaded before they are migrated
+    assert_eq!(
+        env.queue_policy_change(PolicyChange {
+            y0: Some(5_000_000),
+            ..Default::default()
+        })
+        .unwrap_err(),
+        ProgramError::Custom(ErrorCode::AccountDidNotDeserialize.into())
+    );
+    println!("✓ Test 1 passed: baseline policy does not deserialize");
+
+    // Test 2: Only the policy authority migrates
+    assert_eq!(
+        env.migrate_policy_accounts(Pubkey::new_unique())
+            .unwrap_err(),
+        program_error(HonoraryQuoteFeeError::Unauthorized)
+    );
+    println!("✓ Test 2 passed: stranger rejected");
+
+    // Test 3: Both accounts are extended and rent-exempt, keep every baseline field and
+    // fill the appended ones exactly as initialize_policy would for the same terms
+    let outcome = env.migrate_policy_accounts(env.authority).unwrap();
+    let migrated = outcome.events_of::<PolicyAccountsMigrated>();
+    assert_eq!(migrated.len(), 1);
+    assert_eq!(migrated[0].vesting_mint, env.vesting_mint);
+    assert_eq!(migrated[0].policy_len as usize, Policy::LEN);
+    let policy = env.svm.account(&env.policy).unwrap();
+    assert_eq!(policy.data, policy_data);
+    assert_eq!(
+        policy.lamports,
+        Rent::default().minimum_balance(Policy::LEN)
+    );
+    let progress = env.svm.account(&env.progress).unwrap();
+    assert_eq!(progress.data, progress_data);
+    assert_eq!(
+        progress.lamports,
+        Rent::default().minimum_balance(DistributionProgress::LEN)
+    );
+    println!("✓ Test 3 passed: accounts migrated in place");
+
+    // Test 4: Migrating twice is rejected
+    assert_eq!(
+        env.migrate_policy_accounts(env.authority).unwrap_err(),
+        program_error(HonoraryQuoteFeeError::AccountAlreadyMigrated)
+    );
+    println!("✓ Test 4 passed: AccountAlreadyMigrated");
+
+    // Test 5: The migrated policy pays out like one created with the current layout
+    let mut reference = env_with_investors();
+    reference.svm.advance_seconds(DAY_SECONDS + 5);
+    reference.accrue_fees(0, 1_000_000);
+    reference.crank(&[0, 1, 2, 3], page(0, true)).unwrap();
+    env.svm.advance_seconds(DAY_SECONDS + 5);
+    env.accrue_fees(0, 1_000_000);
+    env.crank(&[0, 1, 2, 3], page(0, true)).unwrap();
+    for index in 0..4 {
+        assert_eq!(
+            env.investor_balance(index),
+            reference.investor_balance(index)
+        );
+    }
+    assert_eq!(
+        env.token_balance(&env.creator_quote_ata),
+        reference.token_balance(&reference.creator_quote_ata)
+    );
+    println!("✓ Test 5 passed: migrated policy distributes a day");
+
+    // Test 6: A baseline progress account with an open day is left alone
+    let mut env = env_with_investors();
+    let mut progress = env.progress_account();
+    progress.day_open = true;
+    env.downgrade_to_baseline_layout();
+    env.svm
+        .set_account(env.progress, fixtures::baseline_progress(&progress));
+    assert_eq!(
+        env.migrate_policy_accounts(env.authority).unwrap_err(),
+        program_error(HonoraryQuoteFeeError::MigrationDuringDay)
+    );
+    assert!(env.svm.account(&env.policy).unwrap().data.len() < Policy::LEN);
+    println!("✓ Test 6 passed: MigrationDuringDay");
+
+    println!("✅ All policy account migration tests passed\n");
+}
+
+#[test]
+fn test_rescue_tokens_leaves_committed_quote() {
+    println!("Testing rescue_tokens against the treasuries...");
+    let mut env = env_with_investors();
+    let quote_destination = Pubkey::new_unique();
+    let base_destination = Pubkey::new_unique();
+    env.svm.set_account(
+        quote_destination,
+        fixtures::token_account(env.quote_mint, env.authority, 0),
+    );
+    env.svm.set_account(
+        base_destination,
+        fixtures::token_account(env.base_mint, env.authority, 0),
+    );
+    env.svm.advance_seconds(DAY_SECONDS + 5);
+    env.accrue_fees(0, 1_000_000);
+    env.crank(&[0, 1], page(0, false)).unwrap();
+    env.credit_tokens(env.quote_treasury, 50_000);
+    env.credit_tokens(env.base_fee_check, 1_000);
+
+    // Test 1: The quote treasury is off limits while a day is open
+    assert_eq!(
+        env.rescue_tokens(env.quote_treasury, quote_destination, 1)
+            .unwrap_err(),
+        program_error(HonoraryQuoteFeeError::RescueDuringDay)
+    );
+    println!("✓ Test 1 passed: RescueDuringDay");
+
+    // Test 2: Other honorary-owned accounts can be rescued at any time
+    let outcome = env
+        .rescue_tokens(env.base_fee_check, base_destination, 1_000)
+        .unwrap();
+    let rescued = outcome.events_of::<TokensRescued>();
+    assert_eq!(rescued[0].source, env.base_fee_check);
+    assert_eq!(rescued[0].amount, 1_000);
+    assert_eq!(env.token_balance(&base_destination), 1_000);
+    println!("✓ Test 2 passed: base guard account rescued");
+
+    // Test 3: Once the day closed, only quote beyond the committed balance moves
+    env.crank(&[2, 3], page(2, true)).unwrap();
+    assert_eq!(env.token_balance(&env.creator_quote_ata), 370_000);
+    let depositor = Pubkey::new_unique();
+    let source = Pubkey::new_unique();
+    env.svm.set_account(
+        source,
+        fixtures::token_account(env.quote_mint, depositor, 20_000),
+    );
+    env.deposit_quote(depositor, source, 20_000).unwrap();
+    assert_eq!(env.token_balance(&env.quote_treasury), 70_000);
+    assert_eq!(
+        env.rescue_tokens(env.quote_treasury, quote_destination, 50_001)
+            .unwrap_err(),
+        program_error(HonoraryQuoteFeeError::RescueExceedsAvailable)
+    );
+    env.rescue_tokens(env.quote_treasury, quote_destination, 50_000)
+        .unwrap();
+    assert_eq!(env.token_balance(&quote_destination), 50_000);
+    assert_eq!(env.token_balance(&env.quote_treasury), 20_000);
+    println!("✓ Test 3 passed: pending deposit left in the treasury");
+
+    println!("✅ All rescue tests passed\n");
+}
@@ -0,0 +1,352 @@
+//! Stand-in for DAMM v2 that implements `claim_position_fee`, `claim_partner_fee` and
+//! `create_position`.
+//!
+//! `claim_position_fee` checks the 15-account layout `cp_amm::invoke_claim_position_fee`
+//! builds, pays the position's `fee_a_pending` / `fee_b_pending` out of the pool vaults
+//! through an SPL Token CPI signed by the pool authority, and clears them.
+//!
+//! `claim_partner_fee` takes the 13 accounts `cp_amm::invoke_claim_partner_fee` builds,
+//! requires the signer to be the pool's partner and pays up to `max_amount_a` /
+//! `max_amount_b` of the pool's partner fees the same way.
+//!
+//! `create_position` takes the 11 accounts `cp_amm::invoke_create_position` builds, creates
+//! the NFT mint from the signing keypair, mints the NFT into the
+//! `["position_nft_account", mint]` PDA for the owner and creates an empty
+//! `["position", mint]` account for the pool, laid out as DAMM's `Position` account.
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        entrypoint::ProgramResult, hash::hash, program::invoke_signed, program_error::ProgramError,
+        program_pack::Pack,
+    },
+    system_program::{self, CreateAccount},
+};
+
+use super::CP_AMM_PROGRAM_ID;
+
+pub const POOL_AUTHORITY_SEED: &[u8] = b"pool_authority";
+pub const POSITION_SEED: &[u8] = b"position";
+pub const POSITION_NFT_ACCOUNT_SEED: &[u8] = b"position_nft_account";
+
+/// Byte offsets in the position account, discriminator included.
+pub mod position_layout {
+    pub const POOL: usize = 8;
+    pub const NFT_MINT: usize = 40;
+    pub const FEE_A_PENDING: usize = 136;
+    pub const FEE_B_PENDING: usize = 144;
+    pub const TOTAL_CLAIMED_A_FEE: usize = 200;
+    pub const TOTAL_CLAIMED_B_FEE: usize = 208;
+    pub const LEN: usize = 408;
+}
+
+/// Byte offsets in the pool account, discriminator included.
+pub mod pool_layout {
+    pub const PARTNER: usize = 328;
+    pub const PARTNER_A_FEE: usize = 408;
+    pub const PARTNER_B_FEE: usize = 416;
+}
+
+/// Anchor discriminator `sha256("<namespace>:<name>")[..8]`, derived here rather than copied
+/// from the program so a wrong constant there fails against the stub.
+pub fn discriminator(namespace: &str, name: &str) -> [u8; 8] {
+    hash(format!("{namespace}:{name}").as_bytes()).to_bytes()[..8]
+        .try_into()
+        .unwrap()
+}
+
+pub fn pool_authority() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POOL_AUTHORITY_SEED], &CP_AMM_PROGRAM_ID)
+}
+
+/// Position account `create_position` derives from the NFT mint.
+pub fn position_address(nft_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POSITION_SEED, nft_mint.as_ref()], &CP_AMM_PROGRAM_ID)
+}
+
+/// Token account `create_position` mints the position NFT into.
+pub fn position_nft_account_address(nft_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[POSITION_NFT_ACCOUNT_SEED, nft_mint.as_ref()],
+        &CP_AMM_PROGRAM_ID,
+    )
+}
+
+pub fn process(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let (ix_discriminator, args) = data.split_at(data.len().min(8));
+    let is = |name: &str| ix_discriminator == discriminator("global", name);
+    match args.len() {
+        0 if is("claim_position_fee") => claim_position_fee(accounts),
+        16 if is("claim_partner_fee") => {
+            claim_partner_fee(accounts, read_u64(args, 0), read_u64(args, 8))
+        }
+        0 if is("create_position") => create_position(accounts),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn claim_position_fee(accounts: &[AccountInfo]) -> ProgramResult {
+    let [pool_authority_info, pool, position, token_a_account, token_b_account, token_a_vault, token_b_vault, _token_a_mint, _token_b_mint, position_nft_account, owner, token_program_a, token_program_b, _event_authority, program] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let (pool_authority_key, bump) = pool_authority();
+    if *pool_authority_info.key != pool_authority_key || *program.key != CP_AMM_PROGRAM_ID {
+        return Err(ProgramError::InvalidArgument);
+    }
+    if *position.owner != CP_AMM_PROGRAM_ID || !owner.is_signer {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let (fee_a, fee_b) = {
+        let position_data = position.try_borrow_data()?;
+        if position_data.len() != position_layout::LEN
+            || position_data[..8] != discriminator("account", "Position")
+            || read_pubkey(&position_data, position_layout::POOL) != *pool.key
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let nft = spl_token::state::Account::unpack(&position_nft_account.try_borrow_data()?)?;
+        if nft.owner != *owner.key
+            || nft.amount != 1
+            || nft.mint != read_pubkey(&position_data, position_layout::NFT_MINT)
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        (
+            read_u64(&position_data, position_layout::FEE_A_PENDING),
+            read_u64(&position_data, position_layout::FEE_B_PENDING),
+        )
+    };
+
+    let signer_seeds: &[&[u8]] = &[POOL_AUTHORITY_SEED, &[bump]];
+    for (amount, vault, destination, token_program) in [
+        (fee_a, token_a_vault, token_a_account, token_program_a),
+        (fee_b, token_b_vault, token_b_account, token_program_b),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+        let ix = spl_token::instruction::transfer(
+            token_program.key,
+            vault.key,
+            destination.key,
+            pool_authority_info.key,
+            &[],
+            amount,
+        )?;
+        invoke_signed(
+            &ix,
+            &[
+                vault.clone(),
+                destination.clone(),
+                pool_authority_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+    }
+
+    let mut position_data = position.try_borrow_mut_data()?;
+    write_u64(&mut position_data, position_layout::FEE_A_PENDING, 0);
+    write_u64(&mut position_data, position_layout::FEE_B_PENDING, 0);
+    for (offset, amount) in [
+        (position_layout::TOTAL_CLAIMED_A_FEE, fee_a),
+        (position_layout::TOTAL_CLAIMED_B_FEE, fee_b),
+    ] {
+        let total = read_u64(&position_data, offset) + amount;
+        write_u64(&mut position_data, offset, total);
+    }
+    Ok(())
+}
+
+fn claim_partner_fee(
+    accounts: &[AccountInfo],
+    max_amount_a: u64,
+    max_amount_b: u64,
+) -> ProgramResult {
+    let [pool_authority_info, pool, token_a_account, token_b_account, token_a_vault, token_b_vault, _token_a_mint, _token_b_mint, partner, token_program_a, token_program_b, _event_authority, program] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let (pool_authority_key, bump) = pool_authority();
+    if *pool_authority_info.key != pool_authority_key || *program.key != CP_AMM_PROGRAM_ID {
+        return Err(ProgramError::InvalidArgument);
+    }
+    if *pool.owner != CP_AMM_PROGRAM_ID || !partner.is_signer {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let (fee_a, fee_b) = {
+        let pool_data = pool.try_borrow_data()?;
+        if read_pubkey(&pool_data, pool_layout::PARTNER) != *partner.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        (
+            read_u64(&pool_data, pool_layout::PARTNER_A_FEE).min(max_amount_a),
+            read_u64(&pool_data, pool_layout::PARTNER_B_FEE).min(max_amount_b),
+        )
+    };
+
+    let signer_seeds: &[&[u8]] = &[POOL_AUTHORITY_SEED, &[bump]];
+    for (amount, vault, destination, token_program) in [
+        (fee_a, token_a_vault, token_a_account, token_program_a),
+        (fee_b, token_b_vault, token_b_account, token_program_b),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+        let ix = spl_token::instruction::transfer(
+            token_program.key,
+            vault.key,
+            destination.key,
+            pool_authority_info.key,
+            &[],
+            amount,
+        )?;
+        invoke_signed(
+            &ix,
+            &[
+                vault.clone(),
+                destination.clone(),
+                pool_authority_info.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+    }
+
+    let mut pool_data = pool.try_borrow_mut_data()?;
+    for (offset, amount) in [
+        (pool_layout::PARTNER_A_FEE, fee_a),
+        (pool_layout::PARTNER_B_FEE, fee_b),
+    ] {
+        let remaining = read_u64(&pool_data, offset) - amount;
+        write_u64(&mut pool_data, offset, remaining);
+    }
+    Ok(())
+}
+
+fn create_position(accounts: &[AccountInfo]) -> ProgramResult {
+    let [owner, nft_mint, nft_account, pool, position, pool_authority_info, payer, token_program, system_program_info, _event_authority, program] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let (pool_authority_key, pool_authority_bump) = pool_authority();
+    let (position_key, position_bump) = position_address(nft_mint.key);
+    let (nft_account_key, nft_account_bump) = position_nft_account_address(nft_mint.key);
+    if *pool_authority_info.key != pool_authority_key
+        || *position.key != position_key
+        || *nft_account.key != nft_account_key
+        || *program.key != CP_AMM_PROGRAM_ID
+        || *system_program_info.key != system_program::ID
+    {
+        return Err(ProgramError::InvalidArgument);
+    }
+    if !nft_mint.is_signer || !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if *pool.owner != CP_AMM_PROGRAM_ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let position_seeds: &[&[u8]] = &[POSITION_SEED, nft_mint.key.as_ref(), &[position_bump]];
+    let nft_account_seeds: &[&[u8]] = &[
+        POSITION_NFT_ACCOUNT_SEED,
+        nft_mint.key.as_ref(),
+        &[nft_account_bump],
+    ];
+    let pool_authority_seeds: &[&[u8]] = &[POOL_AUTHORITY_SEED, &[pool_authority_bump]];
+
+    for (account, space, account_owner, seeds) in [
+        (
+            nft_mint,
+            spl_token::state::Mint::LEN,
+            *token_program.key,
+            None,
+        ),
+        (
+            nft_account,
+            spl_token::state::Account::LEN,
+            *token_program.key,
+            Some(nft_account_seeds),
+        ),
+        (
+            position,
+            position_layout::LEN,
+            CP_AMM_PROGRAM_ID,
+            Some(position_seeds),
+        ),
+    ] {
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program_info.clone(),
+                CreateAccount {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+                seeds.as_slice(),
+            ),
+            Rent::get()?.minimum_balance(space),
+            space as u64,
+            &account_owner,
+        )?;
+    }
+
+    let ix = spl_token::instruction::initialize_mint2(
+        token_program.key,
+        nft_mint.key,
+        pool_authority_info.key,
+        None,
+        0,
+    )?;
+    invoke_signed(&ix, std::slice::from_ref(nft_mint), &[])?;
+    let ix = spl_token::instruction::initialize_account3(
+        token_program.key,
+        nft_account.key,
+        nft_mint.key,
+        owner.key,
+    )?;
+    invoke_signed(&ix, &[nft_account.clone(), nft_mint.clone()], &[])?;
+    let ix = spl_token::instruction::mint_to(
+        token_program.key,
+        nft_mint.key,
+        nft_account.key,
+        pool_authority_info.key,
+        &[],
+        1,
+    )?;
+    invoke_signed(
+        &ix,
+        &[
+            nft_mint.clone(),
+            nft_account.clone(),
+            pool_authority_info.clone(),
+        ],
+        &[pool_authority_seeds],
+    )?;
+
+    let mut position_data = position.try_borrow_mut_data()?;
+    position_data[..8].copy_from_slice(&discriminator("account", "Position"));
+    position_data[position_layout::POOL..position_layout::POOL + 32]
+        .copy_from_slice(pool.key.as_ref());
+    position_data[position_layout::NFT_MINT..position_layout::NFT_MINT + 32]
+        .copy_from_slice(nft_mint.key.as_ref());
+    Ok(())
+}
+
+pub fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+pub fn write_u64(data: &mut [u8], offset: usize, value: u64) {
+    data[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Pubkey {
+    Pubkey::new_from_array(data[offset..offset + 32].try_into().unwrap())
+}
@@ -0,0 +1,304 @@
+//! Account fixtures: SPL mints and token accounts, DAMM v2 pool and position accounts in
+//! the layout the program decodes, Streamflow `Contract` accounts, and policy accounts in
+//! the layout of the first deployment.
+
+use anchor_lang::{prelude::*, solana_program::program_pack::Pack, Discriminator};
+use honorary_quote_fee::state::{DistributionProgress, Policy};
+use streamflow_sdk::state::{Contract, CreateParams};
+
+use super::{
+    cp_amm_stub::{discriminator, pool_layout, position_layout, read_u64, write_u64},
+    AccountState, CP_AMM_PROGRAM_ID,
+};
+
+/// DAMM v2 `CollectFeeMode::OnlyB`; token B is the quote mint.
+pub const COLLECT_FEE_MODE_ONLY_QUOTE: u8 = 2;
+pub const ACTIVATION_TYPE_TIMESTAMP: u8 = 1;
+
+fn rent_exempt(data: Vec<u8>, owner: Pubkey) -> AccountState {
+    AccountState {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner,
+        executable: false,
+    }
+}
+
+pub fn mint(decimals: u8) -> AccountState {
+    let mut data = vec![0u8; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        mint_authority: Some(Pubkey::new_unique()).into(),
+        supply: u64::MAX / 2,
+        decimals,
+        is_initialized: true,
+        freeze_authority: None.into(),
+    }
+    .pack_into_slice(&mut data);
+    rent_exempt(data, spl_token::ID)
+}
+
+pub fn token_account(mint: Pubkey, owner: Pubkey, amount: u64) -> AccountState {
+    let mut data = vec![0u8; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint,
+        owner,
+        amount,
+        state: spl_token::state::AccountState::Initialized,
+        ..spl_token::state::Account::default()
+    }
+    .pack_into_slice(&mut data);
+    rent_exempt(data, spl_token::ID)
+}
+
+pub fn token_amount(account: &AccountState) -> u64 {
+    spl_token::state::Account::unpack(&account.data)
+        .expect("not a token account")
+        .amount
+}
+
+pub struct PoolFixture {
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    pub token_a_vault: Pubkey,
+    pub token_b_vault: Pubkey,
+    pub partner: Pubkey,
+    pub collect_fee_mode: u8,
+    pub pool_status: u8,
+    pub activation_type: u8,
+    pub activation_point: u64,
+}
+
+/// `Pool` account, written field by field in declaration order.
+pub fn damm_pool(pool: &PoolFixture) -> AccountState {
+    let mut data = discriminator("account", "Pool").to_vec();
+    data.extend_from_slice(&[0u8; 160]); // pool_fees
+    for key in [
+        pool.token_a_mint,
+        pool.token_b_mint,
+        pool.token_a_vault,
+        pool.token_b_vault,
+        Pubkey::default(), // whitelisted_vault
+        pool.partner,
+    ] {
+        data.extend_from_slice(key.as_ref());
+    }
+    data.extend_from_slice(&[0u8; 32]); // liquidity, padding
+    data.extend_from_slice(&[0u8; 32]); // protocol and partner fees
+    data.extend_from_slice(&[0u8; 48]); // sqrt prices
+    data.extend_from_slice(&pool.activation_point.to_le_bytes());
+    data.extend_from_slice(&[
+        pool.activation_type,
+        pool.pool_status,
+        0, // token_a_flag
+        0, // token_b_flag
+        pool.collect_fee_mode,
+        0, // pool_type
+        0,
+        0,
+    ]);
+    data.extend_from_slice(&[0u8; 64]); // fee per liquidity
+    data.extend_from_slice(&[0u8; 16]); // permanent_lock_liquidity
+    data.extend_from_slice(&[0u8; 80]); // metrics
+    data.extend_from_slice(&[0u8; 80]); // padding_1
+    data.extend_from_slice(&[0u8; 384]); // reward_infos
+    rent_exempt(data, CP_AMM_PROGRAM_ID)
+}
+
+/// Replaces the pool's partner, as a DAMM admin could.
+pub fn set_pool_partner(pool: &mut AccountState, partner: Pubkey) {
+    pool.data[pool_layout::PARTNER..pool_layout::PARTNER + 32].copy_from_slice(partner.as_ref());
+}
+
+/// Credits claimable partner fees to a pool, as swaps through it would.
+pub fn accrue_partner_fees(pool: &mut AccountState, fee_a: u64, fee_b: u64) {
+    for (offset, fee) in [
+        (pool_layout::PARTNER_A_FEE, fee_a),
+        (pool_layout::PARTNER_B_FEE, fee_b),
+    ] {
+        let pending = read_u64(&pool.data, offset);
+        write_u64(&mut pool.data, offset, pending + fee);
+    }
+}
+
+/// Empty `Position` (no liquidity, no pending fees) for `pool`, owned by `nft_mint` holders.
+pub fn damm_position(pool: Pubkey, nft_mint: Pubkey) -> AccountState {
+    let mut data = vec![0u8; position_layout::LEN];
+    data[..8].copy_from_slice(&discriminator("account", "Position"));
+    data[position_layout::POOL..position_layout::POOL + 32].copy_from_slice(pool.as_ref());
+    data[position_layout::NFT_MINT..position_layout::NFT_MINT + 32]
+        .copy_from_slice(nft_mint.as_ref());
+    rent_exempt(data, CP_AMM_PROGRAM_ID)
+}
+
+/// Credits claimable fees to a position, as swaps through the pool would.
+pub fn accrue_position_fees(position: &mut AccountState, fee_a: u64, fee_b: u64) {
+    for (offset, fee) in [
+        (position_layout::FEE_A_PENDING, fee_a),
+        (position_layout::FEE_B_PENDING, fee_b),
+    ] {
+        let pending = read_u64(&position.data, offset);
+        write_u64(&mut position.data, offset, pending + fee);
+    }
+}
+
+pub struct StreamFixture {
+    pub recipient: Pubkey,
+    pub recipient_tokens: Pubkey,
+    pub mint: Pubkey,
+    pub net_amount_deposited: u64,
+    pub start_time: u64,
+    /// Length of each release step; `amount_per_period` releases at its end.
+    pub period: u64,
+    pub amount_per_period: u64,
+    pub end_time: u64,
+}
+
+/// Streamflow `Contract` with no cliff, fees, pauses or withdrawals.
+pub fn stream(stream: &StreamFixture) -> AccountState {
+    let contract = Contract {
+        magic: 0,
+        version: 0,
+        created_at: stream.start_time,
+        amount_withdrawn: 0,
+        canceled_at: 0,
+        end_time: stream.end_time,
+        last_withdrawn_at: 0,
+        sender: Pubkey::new_unique(),
+        sender_tokens: Pubkey::new_unique(),
+        recipient: stream.recipient,
+        recipient_tokens: stream.recipient_tokens,
+        mint: stream.mint,
+        escrow_tokens: Pubkey::new_unique(),
+        streamflow_treasury: Pubkey::default(),
+        streamflow_treasury_tokens: Pubkey::default(),
+        streamflow_fee_total: 0,
+        streamflow_fee_withdrawn: 0,
+        streamflow_fee_percent: 0.0,
+        partner: Pubkey::default(),
+        partner_tokens: Pubkey::default(),
+        partner_fee_total: 0,
+        partner_fee_withdrawn: 0,
+        partner_fee_percent: 0.0,
+        ix: CreateParams {
+            start_time: stream.start_time,
+            net_amount_deposited: stream.net_amount_deposited,
+            period: stream.period,
+            amount_per_period: stream.amount_per_period,
+            cliff: 0,
+            cliff_amount: 0,
+            cancelable_by_sender: false,
+            cancelable_by_recipient: false,
+            automatic_withdrawal: false,
+            transferable_by_sender: false,
+            transferable_by_recipient: false,
+            can_topup: false,
+            stream_name: [0u8; 64],
+            withdraw_frequency: 0,
+            ghost: 0,
+            pausable: false,
+            can_update_rate: false,
+        },
+        ix_padding: Vec::new(),
+        closed: false,
+        current_pause_start: 0,
+        pause_cumulative: 0,
+        last_rate_change_time: 0,
+        funds_unlocked_at_last_rate_change: 0,
+    };
+    rent_exempt(contract.try_to_vec().unwrap(), streamflow_sdk::id())
+}
+
+/// `Policy` as first deployed, before fields were appended to it.
+#[derive(AnchorSerialize)]
+#[repr(C)]
+struct BaselinePolicy {
+    authority: Pubkey,
+    pool: Pubkey,
+    pool_authority: Pubkey,
+    cp_amm_program: Pubkey,
+    quote_mint: Pubkey,
+    base_mint: Pubkey,
+    quote_vault: Pubkey,
+    base_vault: Pubkey,
+    position: Pubkey,
+    position_nft_mint: Pubkey,
+    position_nft_account: Pubkey,
+    quote_treasury: Pubkey,
+    base_fee_check: Pubkey,
+    creator_quote_ata: Pubkey,
+    y0: u64,
+    daily_cap_quote: u64,
+    min_payout_lamports: u64,
+    last_day_close_ts: i64,
+    investor_fee_share_bps: u16,
+    bump: u8,
+    status: u8,
+}
+
+/// `DistributionProgress` as first deployed, before fields were appended to it.
+#[derive(AnchorSerialize)]
+#[repr(C)]
+struct BaselineDistributionProgress {
+    policy: Pubkey,
+    claimed_quote: u64,
+    investor_distributed: u64,
+    carry_quote: u64,
+    day_start_ts: i64,
+    page_cursor: u32,
+    day_open: bool,
+}
+
+/// Account the first deployment allocated for `value`: discriminator, borsh fields and
+/// zeroed padding up to `8 + size_of::<T>()`.
+fn baseline_account<T: AnchorSerialize>(discriminator: &[u8], value: &T) -> AccountState {
+    let mut data = discriminator.to_vec();
+    value.serialize(&mut data).unwrap();
+    data.resize(8 + core::mem::size_of::<T>(), 0);
+    rent_exempt(data, honorary_quote_fee::ID)
+}
+
+/// `policy` written in the first deployment's layout; appended fields are dropped.
+pub fn baseline_policy(policy: &Policy) -> AccountState {
+    baseline_account(
+        Policy::DISCRIMINATOR,
+        &BaselinePolicy {
+            authority: policy.authority,
+            pool: policy.pool,
+            pool_authority: policy.pool_authority,
+            cp_amm_program: policy.cp_amm_program,
+            quote_mint: policy.quote_mint,
+            base_mint: policy.base_mint,
+            quote_vault: policy.quote_vault,
+            base_vault: policy.base_vault,
+            position: policy.position,
+            position_nft_mint: policy.position_nft_mint,
+            position_nft_account: policy.position_nft_account,
+            quote_treasury: policy.quote_treasury,
+            base_fee_check: policy.base_fee_check,
+            creator_quote_ata: policy.creator_quote_ata,
+            y0: policy.y0,
+            daily_cap_quote: policy.daily_cap_quote,
+            min_payout_lamports: policy.min_payout_lamports,
+            last_day_close_ts: policy.last_day_close_ts,
+            investor_fee_share_bps: policy.investor_fee_share_bps,
+            bump: policy.bump,
+            status: policy.status,
+        },
+    )
+}
+
+/// `progress` written in the first deployment's layout; appended fields are dropped.
+pub fn baseline_progress(progress: &DistributionProgress) -> AccountState {
+    baseline_account(
+        DistributionProgress::DISCRIMINATOR,
+        &BaselineDistributionProgress {
+            policy: progress.policy,
+            claimed_quote: progress.claimed_quote,
+            investor_distributed: progress.investor_distributed,
+            carry_quote: progress.carry_quote,
+            day_start_ts: progress.day_start_ts,
+            page_cursor: progress.page_cursor,
+            day_open: progress.day_open,
+        },
+    )
+}
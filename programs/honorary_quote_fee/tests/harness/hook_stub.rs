@@ -0,0 +1,36 @@
+//! Distribution hook stub. It requires `hook_authority` to have signed and, when handed
+//! trailing `quote_treasury`, `destination` and `authority` accounts, behaves like a
+//! hostile hook: it tries to move the whole treasury balance with `authority` as the token
+//! authority. Any failure propagates and, as on chain, aborts the crank.
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        entrypoint::ProgramResult, program::invoke, program_error::ProgramError, program_pack::Pack,
+    },
+};
+
+pub fn process(accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    let [_policy, hook_authority, trailing @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if !hook_authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let [treasury, destination, authority, ..] = trailing else {
+        return Ok(());
+    };
+    let amount = spl_token::state::Account::unpack(&treasury.try_borrow_data()?)?.amount;
+    let ix = spl_token::instruction::transfer(
+        &spl_token::ID,
+        treasury.key,
+        destination.key,
+        authority.key,
+        &[],
+        amount,
+    )?;
+    invoke(
+        &ix,
+        &[treasury.clone(), destination.clone(), authority.clone()],
+    )
+}
@@ -0,0 +1,294 @@
+//! In-process runtime for the integration scenarios.
+//!
+//! Instructions run natively against an in-memory account map. Each invocation gets an
+//! input buffer in the loader's aligned layout, so `AccountInfo::realloc` and `assign`
+//! behave as on chain. CPIs are routed through `program_stubs` to this program, SPL
+//! Token, the associated token program, a minimal system program, the cp-amm stub in
+//! `cp_amm_stub` and the distribution hook in `hook_stub`. As on chain, a failed CPI
+//! fails the whole transaction even when the caller ignores the error.
+
+pub mod cp_amm_stub;
+pub mod fixtures;
+mod hook_stub;
+pub mod policy_env;
+mod syscalls;
+mod system_stub;
+
+use std::{collections::HashMap, mem::size_of};
+
+use anchor_lang::{
+    event::EVENT_IX_TAG_LE,
+    prelude::*,
+    solana_program::{
+        entrypoint::{deserialize, ProgramResult, MAX_PERMITTED_DATA_INCREASE, NON_DUP_MARKER},
+        instruction::Instruction,
+        program_error::ProgramError,
+        pubkey,
+    },
+    system_program,
+};
+
+pub const CP_AMM_PROGRAM_ID: Pubkey = pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+pub const HOOK_PROGRAM_ID: Pubkey = pubkey!("HooK1111111111111111111111111111111111111111");
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// Unix timestamp the harness clock starts at.
+pub const GENESIS_TS: i64 = 1_700_000_000;
+const SLOTS_PER_SECOND: u64 = 2;
+/// Largest compute unit limit a transaction can request.
+pub const MAX_COMPUTE_UNIT_LIMIT: u64 = 1_400_000;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountState {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub executable: bool,
+}
+
+/// What a successful transaction left behind besides account changes.
+#[derive(Debug, Default)]
+pub struct TxOutcome {
+    /// Data of every self-CPI event, in emission order (`EVENT_IX_TAG` stripped).
+    pub events: Vec<Vec<u8>>,
+    pub return_data: Option<(Pubkey, Vec<u8>)>,
+    pub logs: Vec<String>,
+}
+
+impl TxOutcome {
+    /// Decodes every emitted event of type `E`.
+    pub fn events_of<E: anchor_lang::Event + AnchorDeserialize + Discriminator>(&self) -> Vec<E> {
+        self.events
+            .iter()
+            .filter(|data| data.starts_with(E::DISCRIMINATOR))
+            .map(|data| E::try_from_slice(&data[E::DISCRIMINATOR.len()..]).unwrap())
+            .collect()
+    }
+}
+
+pub struct Svm {
+    accounts: HashMap<Pubkey, AccountState>,
+    clock: Clock,
+    /// What `sol_remaining_compute_units` reports; execution itself is not metered.
+    pub compute_unit_limit: u64,
+    /// Logs and events of the last failed transaction, which RPC keeps in its meta even
+    /// though the account changes are rolled back.
+    pub last_failure: Option<TxOutcome>,
+}
+
+impl Default for Svm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Svm {
+    pub fn new() -> Self {
+        syscalls::install();
+        let mut svm = Self {
+            accounts: HashMap::new(),
+            clock: Clock {
+                slot: 1,
+                unix_timestamp: GENESIS_TS,
+                ..Clock::default()
+            },
+            compute_unit_limit: MAX_COMPUTE_UNIT_LIMIT,
+            last_failure: None,
+        };
+        for program_id in [
+            system_program::ID,
+            spl_token::ID,
+            ASSOCIATED_TOKEN_PROGRAM_ID,
+            CP_AMM_PROGRAM_ID,
+            HOOK_PROGRAM_ID,
+            honorary_quote_fee::ID,
+        ] {
+            svm.set_account(
+                program_id,
+                AccountState {
+                    lamports: 1,
+                    executable: true,
+                    owner: anchor_lang::solana_program::bpf_loader_upgradeable::ID,
+                    ..AccountState::default()
+                },
+            );
+        }
+        svm
+    }
+
+    pub fn set_account(&mut self, key: Pubkey, account: AccountState) {
+        self.accounts.insert(key, account);
+    }
+
+    pub fn account(&self, key: &Pubkey) -> Option<&AccountState> {
+        self.accounts.get(key)
+    }
+
+    /// Funds a fresh system account, e.g. a fee payer.
+    pub fn airdrop(&mut self, key: Pubkey, lamports: u64) {
+        self.accounts.entry(key).or_default().lamports += lamports;
+    }
+
+    pub fn now(&self) -> i64 {
+        self.clock.unix_timestamp
+    }
+
+    pub fn advance_seconds(&mut self, seconds: i64) {
+        self.clock.unix_timestamp += seconds;
+        self.clock.slot += seconds as u64 * SLOTS_PER_SECOND;
+    }
+
+    /// Runs `instructions` atomically: any failure restores every account.
+    pub fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[Pubkey],
+    ) -> std::result::Result<TxOutcome, ProgramError> {
+        let snapshot = self.accounts.clone();
+        syscalls::begin_transaction(self.clock.clone(), self.compute_unit_limit);
+        for instruction in instructions {
+            let result = self.execute(instruction, signers);
+            if let Err(error) = syscalls::take_cpi_failure().map_or(result, Err) {
+                self.accounts = snapshot;
+                let outcome = syscalls::end_transaction();
+                for line in &outcome.logs {
+                    println!("  {line}");
+                }
+                self.last_failure = Some(outcome);
+                return Err(error);
+            }
+        }
+        Ok(syscalls::end_transaction())
+    }
+
+    fn execute(&mut self, instruction: &Instruction, signers: &[Pubkey]) -> ProgramResult {
+        let mut accounts = Vec::with_capacity(instruction.accounts.len());
+        for meta in instruction.accounts.iter() {
+            if meta.is_signer && !signers.contains(&meta.pubkey) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            let state = self.accounts.get(&meta.pubkey).cloned().unwrap_or_default();
+            accounts.push((meta.pubkey, meta.is_signer, meta.is_writable, state));
+        }
+
+        let updated = invoke_serialized(&instruction.program_id, &accounts, &instruction.data)?;
+        for (key, is_writable, state) in updated {
+            if is_writable {
+                self.accounts.insert(key, state);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Serializes `accounts` into an aligned input buffer, runs the program and returns the
+/// post-execution state of each unique account with its writable flag.
+pub(crate) fn invoke_serialized(
+    program_id: &Pubkey,
+    accounts: &[(Pubkey, bool, bool, AccountState)],
+    data: &[u8],
+) -> std::result::Result<Vec<(Pubkey, bool, AccountState)>, ProgramError> {
+    let mut buffer = serialize_input(program_id, accounts, data);
+    let input = buffer.as_mut_ptr() as *mut u8;
+    // SAFETY: `buffer` holds the aligned layout `deserialize` expects and outlives `infos`.
+    let (program_id, infos, data) = unsafe { deserialize(input) };
+
+    syscalls::push_program(*program_id);
+    let result = dispatch(program_id, &infos, data);
+    syscalls::pop_program();
+    result?;
+
+    let mut updated: Vec<(Pubkey, bool, AccountState)> = Vec::new();
+    for info in infos.iter() {
+        if updated.iter().any(|(key, _, _)| key == info.key) {
+            continue;
+        }
+        updated.push((
+            *info.key,
+            info.is_writable,
+            AccountState {
+                lamports: info.lamports(),
+                data: info.data.borrow().to_vec(),
+                owner: *info.owner,
+                executable: info.executable,
+            },
+        ));
+    }
+    Ok(updated)
+}
+
+fn dispatch<'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    data: &[u8],
+) -> ProgramResult {
+    if *program_id == honorary_quote_fee::ID {
+        if let Some(event) = data.strip_prefix(EVENT_IX_TAG_LE) {
+            syscalls::record_event(event);
+        }
+        honorary_quote_fee::entry(program_id, accounts, data)
+    } else if *program_id == spl_token::ID {
+        spl_token::processor::Processor::process(program_id, accounts, data)
+    } else if *program_id == ASSOCIATED_TOKEN_PROGRAM_ID {
+        spl_associated_token_account::processor::process_instruction(program_id, accounts, data)
+    } else if *program_id == system_program::ID {
+        system_stub::process(accounts, data)
+    } else if *program_id == CP_AMM_PROGRAM_ID {
+        cp_amm_stub::process(accounts, data)
+    } else if *program_id == HOOK_PROGRAM_ID {
+        hook_stub::process(accounts, data)
+    } else {
+        Err(ProgramError::IncorrectProgramId)
+    }
+}
+
+/// Loader v2 aligned input layout. Duplicate keys reference their first occurrence and
+/// the first occurrence carries the union of the privileges requested for that key.
+fn serialize_input(
+    program_id: &Pubkey,
+    accounts: &[(Pubkey, bool, bool, AccountState)],
+    data: &[u8],
+) -> Vec<u64> {
+    let mut bytes: Vec<u8> = Vec::new();
+    bytes.extend_from_slice(&(accounts.len() as u64).to_le_bytes());
+    for (index, (key, _, _, state)) in accounts.iter().enumerate() {
+        if let Some(first) = accounts[..index]
+            .iter()
+            .position(|(other, ..)| other == key)
+        {
+            bytes.push(first as u8);
+            bytes.extend_from_slice(&[0u8; 7]);
+            continue;
+        }
+        let (is_signer, is_writable) = accounts
+            .iter()
+            .filter(|(other, ..)| other == key)
+            .fold((false, false), |(signer, writable), (_, s, w, _)| {
+                (signer || *s, writable || *w)
+            });
+        bytes.push(NON_DUP_MARKER);
+        bytes.push(u8::from(is_signer));
+        bytes.push(u8::from(is_writable));
+        bytes.push(u8::from(state.executable));
+        bytes.extend_from_slice(&[0u8; 4]);
+        bytes.extend_from_slice(key.as_ref());
+        bytes.extend_from_slice(state.owner.as_ref());
+        bytes.extend_from_slice(&state.lamports.to_le_bytes());
+        bytes.extend_from_slice(&(state.data.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&state.data);
+        bytes.resize(bytes.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+        bytes.resize(bytes.len().next_multiple_of(size_of::<u64>()), 0);
+        bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+    }
+    bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(data);
+    bytes.extend_from_slice(program_id.as_ref());
+
+    let mut words = vec![0u64; bytes.len().div_ceil(size_of::<u64>())];
+    // SAFETY: `words` spans at least `bytes.len()` bytes.
+    unsafe {
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), words.as_mut_ptr() as *mut u8, bytes.len());
+    }
+    words
+}
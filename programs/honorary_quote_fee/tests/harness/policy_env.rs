@@ -0,0 +1,823 @@
+//! A DAMM v2 pool with one honorary policy on it, plus Streamflow investors; the Rust
+//! counterpart of `tests/helpers/setup.ts`.
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{instruction::Instruction, program_pack::Pack},
+    system_program, AccountDeserialize, InstructionData, ToAccountMetas,
+};
+use anchor_spl::associated_token::get_associated_token_address;
+use honorary_quote_fee::{
+    accounts,
+    distribution_hook::hook_authority_address,
+    events::EVENT_AUTHORITY_SEED,
+    honorary_position_address, instruction, investor_page_digest, investor_snapshot_commitment,
+    investor_snapshot_leaf, policy_address, progress_address,
+    state::{
+        CreatorEscrow, DistributionProgress, FeeCaptureMode, Policy, PolicyChange, BLOCKLIST_SEED,
+        CRANKER_ALLOWLIST_SEED, CREATOR_ESCROW_SEED, DAY_HISTORY_SEED, POLICY_CHANGE_SEED,
+        POLICY_GROUP_SEED,
+    },
+    CrankQuoteFeeParams, InitializePolicyParams,
+};
+use streamflow_sdk::state::Contract;
+
+use super::{
+    cp_amm_stub,
+    fixtures::{self, PoolFixture, StreamFixture},
+    Svm, TxOutcome, ASSOCIATED_TOKEN_PROGRAM_ID, CP_AMM_PROGRAM_ID,
+};
+
+pub type TxResult = std::result::Result<TxOutcome, ProgramError>;
+
+/// Quote held by the pool vault to pay claimed fees from.
+const VAULT_QUOTE: u64 = 1_000_000_000_000;
+
+pub struct Investor {
+    pub stream: Pubkey,
+    pub quote_ata: Pubkey,
+}
+
+pub struct PolicyEnv {
+    pub svm: Svm,
+    pub authority: Pubkey,
+    pub cranker: Pubkey,
+    pub pool: Pubkey,
+    pub pool_authority: Pubkey,
+    pub quote_mint: Pubkey,
+    pub base_mint: Pubkey,
+    /// Mint of investor streams; the quote mint unless changed before `initialize_policy`.
+    pub vesting_mint: Pubkey,
+    pub quote_vault: Pubkey,
+    pub base_vault: Pubkey,
+    pub creator_quote_ata: Pubkey,
+    pub policy: Pubkey,
+    pub progress: Pubkey,
+    pub honorary_position: Pubkey,
+    pub position: Pubkey,
+    pub position_nft_mint: Pubkey,
+    pub position_nft_account: Pubkey,
+    pub quote_treasury: Pubkey,
+    pub base_fee_check: Pubkey,
+    pub event_authority: Pubkey,
+    pub creator_escrow: Pubkey,
+    pub creator_escrow_vault: Pubkey,
+    pub cranker_allowlist: Pubkey,
+    pub pending_change: Pubkey,
+    pub day_history: Pubkey,
+    pub blocklist: Pubkey,
+    /// Group with this env's policy as its primary.
+    pub policy_group: Pubkey,
+    /// Leaves the hook program and authority out of crank pages, as a keeper working
+    /// around a broken hook would.
+    pub omit_hook_accounts: bool,
+    pub investors: Vec<Investor>,
+}
+
+/// Position-fee capture, a linear curve, no cap and no optional features.
+pub fn default_policy_params() -> InitializePolicyParams {
+    InitializePolicyParams {
+        investor_fee_share_bps: 10_000,
+        y0: 10_000_000,
+        min_payout_lamports: 1_000,
+        fee_capture_mode: FeeCaptureMode::POSITION_FEES,
+        ..InitializePolicyParams::default()
+    }
+}
+
+impl PolicyEnv {
+    /// Creates the pool, its vaults and mints, and an empty position whose NFT the
+    /// honorary PDA already holds. The policy itself is not initialized yet.
+    pub fn new() -> Self {
+        let mut svm = Svm::new();
+        let authority = Pubkey::new_unique();
+        let cranker = Pubkey::new_unique();
+        svm.airdrop(authority, 100_000_000_000);
+
+        let pool = Pubkey::new_unique();
+        let (pool_authority, _) = cp_amm_stub::pool_authority();
+        let quote_mint = Pubkey::new_unique();
+        let base_mint = Pubkey::new_unique();
+        let quote_vault = Pubkey::new_unique();
+        let base_vault = Pubkey::new_unique();
+        let creator_quote_ata = Pubkey::new_unique();
+        svm.set_account(quote_mint, fixtures::mint(6));
+        svm.set_account(base_mint, fixtures::mint(9));
+        svm.set_account(
+            quote_vault,
+            fixtures::token_account(quote_mint, pool_authority, VAULT_QUOTE),
+        );
+        svm.set_account(
+            base_vault,
+            fixtures::token_account(base_mint, pool_authority, VAULT_QUOTE),
+        );
+        svm.set_account(
+            creator_quote_ata,
+            fixtures::token_account(quote_mint, authority, 0),
+        );
+        svm.set_account(
+            pool,
+            fixtures::damm_pool(&PoolFixture {
+                token_a_mint: base_mint,
+                token_b_mint: quote_mint,
+                token_a_vault: base_vault,
+                token_b_vault: quote_vault,
+                partner: Pubkey::default(),
+                collect_fee_mode: fixtures::COLLECT_FEE_MODE_ONLY_QUOTE,
+                pool_status: 0,
+                activation_type: fixtures::ACTIVATION_TYPE_TIMESTAMP,
+                activation_point: 0,
+            }),
+        );
+
+        let policy = policy_address(&pool, 0);
+        let progress = progress_address(&pool, 0);
+        let honorary_position = honorary_position_address(&policy);
+
+        let position = Pubkey::new_unique();
+        let position_nft_mint = Pubkey::new_unique();
+        let position_nft_account = Pubkey::new_unique();
+        svm.set_account(position, fixtures::damm_position(pool, position_nft_mint));
+        svm.set_account(position_nft_mint, fixtures::mint(0));
+        svm.set_account(
+            position_nft_account,
+            fixtures::token_account(position_nft_mint, honorary_position, 1),
+        );
+
+        // The treasuries already exist, so `init_if_needed` only validates them.
+        let quote_treasury = get_associated_token_address(&honorary_position, &quote_mint);
+        let base_fee_check = get_associated_token_address(&honorary_position, &base_mint);
+        svm.set_account(
+            quote_treasury,
+            fixtures::token_account(quote_mint, honorary_position, 0),
+        );
+        svm.set_account(
+            base_fee_check,
+            fixtures::token_account(base_mint, honorary_position, 0),
+        );
+
+        let (event_authority, _) =
+            Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], &honorary_quote_fee::ID);
+        let (creator_escrow, _) = Pubkey::find_program_address(
+            &[CREATOR_ESCROW_SEED, policy.as_ref()],
+            &honorary_quote_fee::ID,
+        );
+        let creator_escrow_vault = get_associated_token_address(&creator_escrow, &quote_mint);
+        let (cranker_allowlist, _) = Pubkey::find_program_address(
+            &[CRANKER_ALLOWLIST_SEED, policy.as_ref()],
+            &honorary_quote_fee::ID,
+        );
+        let (pending_change, _) = Pubkey::find_program_address(
+            &[POLICY_CHANGE_SEED, policy.as_ref()],
+            &honorary_quote_fee::ID,
+        );
+        let [day_history, blocklist, policy_group] =
+            [DAY_HISTORY_SEED, BLOCKLIST_SEED, POLICY_GROUP_SEED].map(|seed| {
+                Pubkey::find_program_address(&[seed, policy.as_ref()], &honorary_quote_fee::ID).0
+            });
+
+        Self {
+            svm,
+            authority,
+            cranker,
+            pool,
+            pool_authority,
+            quote_mint,
+            base_mint,
+            vesting_mint: quote_mint,
+            quote_vault,
+            base_vault,
+            creator_quote_ata,
+            policy,
+            progress,
+            honorary_position,
+            position,
+            position_nft_mint,
+            position_nft_account,
+            quote_treasury,
+            base_fee_check,
+            event_authority,
+            creator_escrow,
+            creator_escrow_vault,
+            cranker_allowlist,
+            pending_change,
+            day_history,
+            blocklist,
+            policy_group,
+            omit_hook_accounts: false,
+            investors: Vec::new(),
+        }
+    }
+
+    /// `new` followed by `initialize_policy` and `configure_honorary_position`.
+    pub fn configured(params: InitializePolicyParams) -> Self {
+        let mut env = Self::new();
+        env.initialize_policy(params)
+            .expect("initialize_policy failed");
+        env.configure_honorary_position()
+            .expect("configure_honorary_position failed");
+        env
+    }
+
+    pub fn initialize_policy(&mut self, params: InitializePolicyParams) -> TxResult {
+        let ix = Instruction {
+            program_id: honorary_quote_fee::ID,
+            accounts: accounts::InitializePolicy {
+                payer: self.authority,
+                authority: self.authority,
+                policy: self.policy,
+                progress: self.progress,
+                damm_pool: self.pool,
+                pool_authority: self.pool_authority,
+                damm_program: CP_AMM_PROGRAM_ID,
+                quote_mint: self.quote_mint,
+                base_mint: self.base_mint,
+                vesting_mint: self.vesting_mint,
+                quote_vault: self.quote_vault,
+                base_vault: self.base_vault,
+                creator_quote_ata: self.creator_quote_ata,
+                system_program: system_program::ID,
+                event_authority: self.event_authority,
+                program: honorary_quote_fee::ID,
+            }
+            .to_account_metas(None),
+            data: instruction::InitializePolicy { params }.data(),
+        };
+        self.svm.process(&[ix], &[self.authority])
+    }
+
+    pub fn configure_honorary_position(&mut self) -> TxResult {
+        let ix = Instruction {
+            program_id: honorary_quote_fee::ID,
+            accounts: accounts::ConfigureHonoraryPosition {
+                authority: self.authority,
+                policy: self.policy,
+                honorary_position: self.honorary_position,
+                position: self.position,
+                position_nft_mint: self.position_nft_mint,
+                position_nft_account: self.position_nft_account,
+                quote_mint: self.quote_mint,
+                quote_treasury: self.quote_treasury,
+                base_mint: self.base_mint,
+                base_fee_check: self.base_fee_check,
+                system_program: system_program::ID,
+                token_program: spl_token::ID,
+                associated_token_program: ASSOCIATED_TOKEN_PROGRAM_ID,
+                event_authority: self.event_authority,
+                program: honorary_quote_fee::ID,
+            }
+            .to_account_metas(None),
+            data: instruction::ConfigureHonoraryPosition {}.data(),
+        };
+        self.svm.process(&[ix], &[self.authority])
+    }
+
+    /// Creates a fresh position through DAMM instead of adopting the fixture one, and
+    /// points `position`, `position_nft_mint` and `position_nft_account` at it.
+    pub fn create_honorary_position(&mut self) -> TxResult {
+        let position_nft_mint = Pubkey::new_unique();
+        let ix = Instruction {
+            program_id: honorary_quote_fee::ID,
+            accounts: accounts::CreateHonoraryPosition {
+                payer: self.authority,
+                authority: self.authority,
+                policy: self.policy,
+                honorary_position: self.honorary_position,
+                position_nft_mint,
+                position_nft_account: cp_amm_stub::position_nft_account_address(&position_nft_mint)
+                    .0,
+                position: cp_amm_stub::position_address(&position_nft_mint).0,
+                damm_pool: self.pool,
+                pool_authority: self.pool_authority,
+                quote_mint: self.quote_mint,
+                quote_treasury: self.quote_treasury,
+                base_mint: self.base_mint,
+                base_fee_check: self.base_fee_check,
+                damm_event_authority: Pubkey::new_unique(),
+                cp_amm_program: CP_AMM_PROGRAM_ID,
+                position_token_program: spl_token::ID,
+                system_program: system_program::ID,
+                token_program: spl_token::ID,
+                associated_token_program: ASSOCIATED_TOKEN_PROGRAM_ID,
+                event_authority: self.event_authority,
+                program: honorary_quote_fee::ID,
+            }
+            .to_account_metas(None),
+            data: instruction::CreateHonoraryPosition {}.data(),
+        };
+        let outcome = self
+            .svm
+            .process(&[ix], &[self.authority, position_nft_mint])?;
+        self.position_nft_mint = position_nft_mint;
+        self.position_nft_account = cp_amm_stub::position_nft_account_address(&position_nft_mint).0;
+        self.position = cp_amm_stub::position_address(&position_nft_mint).0;
+        Ok(outcome)
+    }
+
+    pub fn initialize_creator_escrow(&mut self) -> TxResult {
+        let ix = Instruction {
+            program_id: honorary_quote_fee::ID,
+            accounts: accounts::InitializeCreatorEscrow {
+                payer: self.authority,
+                authority: self.authority,
+                policy: self.policy,
+                creator_escrow: self.creator_escrow,
+                quote_mint: self.quote_mint,
+                escrow_vault: self.creator_escrow_vault,
+                system_program: system_program::ID,
+                token_program: spl_token::ID,
+                associated_token_program: ASSOCIATED_TOKEN_PROGRAM_ID,
+                event_authority: self.event_authority,
+                program: honorary_quote_fee::ID,
+            }
+            .to_account_metas(None),
+            data: instruction::InitializeCreatorEscrow {}.data(),
+        };
+        self.svm.process(&[ix], &[self.authority])
+    }
+
+    /// The creator escrow, once `initialize_creator_escrow` has created it.
+    fn initialized_creator_escrow(&self) -> Option<Pubkey> {
+        self.svm
+            .account(&self.creator_escrow)
+            .filter(|account| account.lamports > 0)
+            .map(|_| self.creator_escrow)
+    }
+
+    pub fn initialize_day_history(&mut self) -> TxResult {
+        let ix = Instruction {
+            program_id: honorary_quote_fee::ID,
+            accounts: accounts::InitializeDayHistory {
+                payer: self.authority,
+                authority: self.authority,
+                policy: self.policy,
+                day_history: self.day_history,
+                system_program: system_program::ID,
+                event_authority: self.event_authority,
+                program: honorary_quote_fee::ID,
+            }
+            .to_account_metas(None),
+            data: instruction::InitializeDayHistory {}.data(),
+        };
+        self.svm.process(&[ix], &[self.authority])
+    }
+
+    pub fn initialize_blocklist(&mut self, forfeiture_ata: Pubkey) -> TxResult {
+        let ix = Instruction {
+            program_id: honorary_quote_fee::ID,
+            accounts: accounts::InitializeBlocklist {
+                payer: self.authority,
+                authority: self.authority,
+                policy: self.policy,
+                blocklist: self.blocklist,
+                forfeiture_ata,
+                system_program: system_program::ID,
+                event_authority: self.event_authority,
+                program: honorary_quote_fee::ID,
+            }
+            .to_account_metas(None),
+            data: instruction::InitializeBlocklist {}.data(),
+        };
+        self.svm.process(&[ix], &[self.authority])
+    }
+
+    pub fn initialize_policy_group(&mut self) -> TxResult {
+        let ix = Instruction {
+            program_id: honorary_quote_fee::ID,
+            accounts: accounts::InitializePolicyGroup {
+                payer: self.authority,
+                authority: self.authority,
+                policy: self.policy,
+                policy_group: self.policy_group,
+                system_program: system_program::ID,
+                event_authority: self.event_authority,
+                program: honorary_quote_fee::ID,
+            }
+            .to_account_metas(None),
+            data: instruction::InitializePolicyGroup {}.data(),
+        };
+        self.svm.process(&[ix], &[self.authority])
+    }
+
+    pub fn add_policy_to_group(&mut self, member_policy: Pubkey) -> TxResult {
+        let ix = Instruction {
+            program_id: honorary_quote_fee::ID,
+            accounts: accounts::AddPolicyToGroup {
+                authority: self.authority,
+                policy_group: self.policy_group,
+                primary_policy: self.policy,
+                member_policy,
+                event_authority: self.event_authority,
+                program: honorary_quote_fee::ID,
+            }
+            .to_account_metas(None),
+            data: instruction::AddPolicyToGroup {}.data(),
+        };
+        self.svm.process(&[ix], &[self.authority])
+    }
+
+    pub fn initialize_cranker_allowlist(&mut self, permissionless: bool) -> TxResult {
+        let ix = Instruction {
+            program_id: honorary_quote_fee::ID,
+            accounts: accounts::InitializeCrankerAllowlist {
+                payer: self.authority,
+                authority: self.authority,
+                policy: self.policy,
+                cranker_allowlist: self.cranker_allowlist,
+                system_program: system_program::ID,
+                event_authority: self.event_authority,
+                program: honorary_quote_fee::ID,
+            }
+            .to_account_metas(None),
+            data: instruction::InitializeCrankerAllowlist { permissionless }.data(),
+        };
+        self.svm.process(&[ix], &[self.authority])
+    }
+
+    pub fn update_cranker_allowlist(&mut self, cranker: Pubkey, allowed: bool) -> TxResult {
+        let ix = Instruction {
+            program_id: honorary_quote_fee::ID,
+            accounts: accounts::UpdateCrankerAllowlist {
+                authority: self.authority,
+                policy: self.policy,
+                cranker_allowlist: self.cranker_allowlist,
+                event_authority: self.event_authority,
+                program: honorary_quote_fee::ID,
+            }
+            .to_account_metas(None),
+            data: instruction::UpdateCrankerAllowlist { cranker, allowed }.data(),
+        };
+        self.svm.process(&[ix], &[self.authority])
+    }
+
+    pub fn configure_distribution_hook(
+        &mut self,
+        hook_program: Pubkey,
+        failure_mode: u8,
+    ) -> TxResult {
+        let ix = Instruction {
+            program_id: honorary_quote_fee::ID,
+            accounts: accounts::ConfigureDistributionHook {
+                authority: self.authority,
+                policy: self.policy,
+                event_authority: self.event_authority,
+                program: honorary_quote_fee::ID,
+            }
+            .to_account_metas(None),
+            data: instruction::ConfigureDistributionHook {
+                hook_program,
+                failure_mode,
+            }
+            .data(),
+        };
+        self.svm.process(&[ix], &[self.authority])
+    }
+
+    pub fn queue_policy_change(&mut self, change: PolicyChange) -> TxResult {
+        let ix = Instruction {
+            program_id: honorary_quote_fee::ID,
+            accounts: accounts::QueuePolicyChange {
+                authority: self.authority,
+                policy: self.policy,
+                pending_change: self.pending_change,
+                new_creator_quote_ata: change.creator_quote_ata,
+                creator_escrow: change
+                    .creator_vesting
+                    .and_then(|_| self.initialized_creator_escrow()),
+                system_program: system_program::ID,
+                event_authority: self.event_authority,
+                program: honorary_quote_fee::ID,
+            }
+            .to_account_metas(None),
+            data: instruction::QueuePolicyChange { change }.data(),
+        };
+        self.svm.process(&[ix], &[self.authority])
+    }
+
+    /// Sent by a fresh signer, since anyone may execute a change once it is due.
+    pub fn execute_policy_change(&mut self) -> TxResult {
+        let executor = Pubkey::new_unique();
+        let creator_escrow = self.initialized_creator_escrow();
+        let ix = Instruction {
+            program_id: honorary_quote_fee::ID,
+            accounts: accounts::ExecutePolicyChange {
+                executor,
+                authority: self.authority,
+                policy: self.policy,
+                progress: self.progress,
+                pending_change: self.pending_change,
+                creator_escrow,
+                event_authority: self.event_authority,
+                program: honorary_quote_fee::ID,
+            }
+            .to_account_metas(None),
+            data: instruction::ExecutePolicyChange {}.data(),
+        };
+        self.svm.process(&[ix], &[executor])
+    }
+
+    /// Rewrites the policy and progress accounts in the first deployment's layout.
+    pub fn downgrade_to_baseline_layout(&mut self) {
+        let policy = fixtures::baseline_policy(&self.policy_account());
+        let progress = fixtures::baseline_progress(&self.progress_account());
+        self.svm.set_account(self.policy, policy);
+        self.svm.set_account(self.progress, progress);
+    }
+
+    pub fn migrate_policy_accounts(&mut self, authority: Pubkey) -> TxResult {
+        let ix = Instruction {
+            program_id: honorary_quote_fee::ID,
+            accounts: accounts::MigratePolicyAccounts {
+                payer: self.authority,
+                authority,
+                policy: self.policy,
+                progress: self.progress,
+                damm_pool: self.pool,
+                quote_mint: self.quote_mint,
+                vesting_mint: self.vesting_mint,
+                system_program: system_program::ID,
+                event_authority: self.event_authority,
+                program: honorary_quote_fee::ID,
+            }
+            .to_account_metas(None),
+            data: instruction::MigratePolicyAccounts {}.data(),
+        };
+        self.svm.process(&[ix], &[self.authority, authority])
+    }
+
+    /// Sent by a fresh signer, since anyone may release vested creator quote.
+    pub fn withdraw_creator_vested(&mut self) -> TxResult {
+        let caller = Pubkey::new_unique();
+        let ix = Instruction {
+            program_id: honorary_quote_fee::ID,
+            accounts: accounts::WithdrawCreatorVested {
+                policy: self.policy,
+                creator_escrow: self.creator_escrow,
+                escrow_vault: self.creator_escrow_vault,
+                creator_quote_ata: self.creator_quote_ata,
+                token_program: spl_token::ID,
+                event_authority: self.event_authority,
+                program: honorary_quote_fee::ID,
+            }
+            .to_account_metas(None),
+            data: instruction::WithdrawCreatorVested {}.data(),
+        };
+        self.svm.process(&[ix], &[caller])
+    }
+
+    /// Adds an investor whose stream releases `deposit` in `periods` equal steps of
+    /// `period_seconds`, starting now. Returns the investor index.
+    pub fn add_investor(&mut self, deposit: u64, periods: u64, period_seconds: u64) -> usize {
+        let recipient = Pubkey::new_unique();
+        let stream = Pubkey::new_unique();
+        let quote_ata = get_associated_token_address(&recipient, &self.quote_mint);
+        let start_time = self.svm.now() as u64;
+        self.svm.set_account(
+            quote_ata,
+            fixtures::token_account(self.quote_mint, recipient, 0),
+        );
+        self.svm.set_account(
+            stream,
+            fixtures::stream(&StreamFixture {
+                recipient,
+                recipient_tokens: get_associated_token_address(&recipient, &self.vesting_mint),
+                mint: self.vesting_mint,
+                net_amount_deposited: deposit,
+                start_time,
+                period: period_seconds,
+                amount_per_period: deposit / periods,
+                end_time: start_time + periods * period_seconds,
+            }),
+        );
+        self.investors.push(Investor { stream, quote_ata });
+        self.investors.len() - 1
+    }
+
+    /// Adds `amount` to an investor's deposit, as a Streamflow top-up would.
+    pub fn top_up_investor(&mut self, index: usize, amount: u64) {
+        let stream = self.investors[index].stream;
+        let mut contract = self.stream_contract(index);
+        contract.ix.net_amount_deposited += amount;
+        let mut account = self.svm.account(&stream).unwrap().clone();
+        account.data = contract.try_to_vec().unwrap();
+        self.svm.set_account(stream, account);
+    }
+
+    /// Day-open commitment for cranking `pages` in order, followed by the commitment each
+    /// page leaves behind; the last is all zeros.
+    pub fn investor_snapshot(&self, pages: &[&[usize]]) -> Vec<[u8; 32]> {
+        let digests: Vec<[u8; 32]> = pages
+            .iter()
+            .map(|page| {
+                let leaves: Vec<[u8; 32]> = page
+                    .iter()
+                    .map(|index| {
+                        let contract = self.stream_contract(*index);
+                        investor_snapshot_leaf(
+                            &self.investors[*index].stream,
+                            &contract.recipient,
+                            contract.ix.net_amount_deposited,
+                            contract.ix.amount_per_period,
+                        )
+                    })
+                    .collect();
+                investor_page_digest(&leaves)
+            })
+            .collect();
+        (0..=digests.len())
+            .map(|start| investor_snapshot_commitment(&digests[start..]))
+            .collect()
+    }
+
+    fn stream_contract(&self, index: usize) -> Contract {
+        let data = &self
+            .svm
+            .account(&self.investors[index].stream)
+            .unwrap()
+            .data;
+        Contract::try_from_slice(data).unwrap()
+    }
+
+    /// Fees the honorary position can claim on the next crank.
+    pub fn accrue_fees(&mut self, base: u64, quote: u64) {
+        let mut position = self.svm.account(&self.position).unwrap().clone();
+        fixtures::accrue_position_fees(&mut position, base, quote);
+        self.svm.set_account(self.position, position);
+    }
+
+    pub fn set_pool_partner(&mut self, partner: Pubkey) {
+        let mut pool = self.svm.account(&self.pool).unwrap().clone();
+        fixtures::set_pool_partner(&mut pool, partner);
+        self.svm.set_account(self.pool, pool);
+    }
+
+    /// Partner fees the pool's partner can claim on the next crank.
+    pub fn accrue_partner_fees(&mut self, base: u64, quote: u64) {
+        let mut pool = self.svm.account(&self.pool).unwrap().clone();
+        fixtures::accrue_partner_fees(&mut pool, base, quote);
+        self.svm.set_account(self.pool, pool);
+    }
+
+    pub fn crank(&mut self, investors: &[usize], params: CrankQuoteFeeParams) -> TxResult {
+        self.crank_with_hook_accounts(investors, &[], params)
+    }
+
+    /// `crank` with `hook_accounts` appended after the investor pairs; pass their count in
+    /// `params.hook_account_count`.
+    pub fn crank_with_hook_accounts(
+        &mut self,
+        investors: &[usize],
+        hook_accounts: &[AccountMeta],
+        params: CrankQuoteFeeParams,
+    ) -> TxResult {
+        let policy = self.policy_account();
+        let escrowed = policy.creator_escrow != Pubkey::default();
+        let allowlisted = policy.cranker_allowlist != Pubkey::default();
+        let hooked = policy.distribution_hook != Pubkey::default() && !self.omit_hook_accounts;
+        let mut metas = accounts::CrankQuoteFeeDistribution {
+            cranker: self.cranker,
+            policy: self.policy,
+            honorary_position: self.honorary_position,
+            progress: self.progress,
+            quote_treasury: self.quote_treasury,
+            base_fee_check: self.base_fee_check,
+            creator_quote_ata: self.creator_quote_ata,
+            pool: self.pool,
+            pool_authority: self.pool_authority,
+            position: self.position,
+            position_nft_account: self.position_nft_account,
+            base_vault: self.base_vault,
+            quote_vault: self.quote_vault,
+            base_mint: self.base_mint,
+            quote_mint: self.quote_mint,
+            damm_event_authority: Pubkey::new_unique(),
+            cp_amm_program: CP_AMM_PROGRAM_ID,
+            token_program_a: spl_token::ID,
+            token_program_b: spl_token::ID,
+            token_program: spl_token::ID,
+            day_history: None,
+            policy_group: None,
+            blocklist: None,
+            forfeiture_ata: None,
+            cranker_allowlist: allowlisted.then_some(self.cranker_allowlist),
+            distribution_hook_program: hooked.then_some(policy.distribution_hook),
+            creator_escrow: escrowed.then_some(self.creator_escrow),
+            creator_escrow_vault: escrowed.then_some(self.creator_escrow_vault),
+            distribution_hook_authority: hooked.then(|| hook_authority_address(&self.policy).0),
+            event_authority: self.event_authority,
+            program: honorary_quote_fee::ID,
+        }
+        .to_account_metas(None);
+        for index in investors {
+            let investor = &self.investors[*index];
+            metas.push(AccountMeta::new_readonly(investor.stream, false));
+            metas.push(AccountMeta::new(investor.quote_ata, false));
+        }
+        metas.extend_from_slice(hook_accounts);
+        let ix = Instruction {
+            program_id: honorary_quote_fee::ID,
+            accounts: metas,
+            data: instruction::CrankQuoteFeeDistribution { params }.data(),
+        };
+        self.svm.process(&[ix], &[self.cranker])
+    }
+
+    /// Deposits `amount` from `source`, a quote account owned by `depositor`, into the
+    /// treasury the policy records.
+    pub fn deposit_quote(&mut self, depositor: Pubkey, source: Pubkey, amount: u64) -> TxResult {
+        let ix = Instruction {
+            program_id: honorary_quote_fee::ID,
+            accounts: accounts::DepositQuoteForDistribution {
+                depositor,
+                policy: self.policy,
+                progress: self.progress,
+                depositor_quote_account: source,
+                quote_treasury: self.policy_account().quote_treasury,
+                token_program: spl_token::ID,
+                policy_group: None,
+                event_authority: self.event_authority,
+                program: honorary_quote_fee::ID,
+            }
+            .to_account_metas(None),
+            data: instruction::DepositQuoteForDistribution { amount }.data(),
+        };
+        self.svm.process(&[ix], &[depositor])
+    }
+
+    pub fn rescue_tokens(&mut self, source: Pubkey, destination: Pubkey, amount: u64) -> TxResult {
+        let ix = Instruction {
+            program_id: honorary_quote_fee::ID,
+            accounts: accounts::RescueTokens {
+                authority: self.authority,
+                policy: self.policy,
+                honorary_position: self.honorary_position,
+                progress: self.progress,
+                source,
+                destination,
+                token_program: spl_token::ID,
+                policy_group: None,
+                event_authority: self.event_authority,
+                program: honorary_quote_fee::ID,
+            }
+            .to_account_metas(None),
+            data: instruction::RescueTokens { amount }.data(),
+        };
+        self.svm.process(&[ix], &[self.authority])
+    }
+
+    pub fn force_close_day(&mut self, caller: Pubkey) -> TxResult {
+        let escrowed = self.policy_account().creator_escrow != Pubkey::default();
+        let ix = Instruction {
+            program_id: honorary_quote_fee::ID,
+            accounts: accounts::ForceCloseDay {
+                caller,
+                policy: self.policy,
+                honorary_position: self.honorary_position,
+                progress: self.progress,
+                quote_treasury: self.quote_treasury,
+                creator_quote_ata: self.creator_quote_ata,
+                token_program: spl_token::ID,
+                day_history: None,
+                creator_escrow: escrowed.then_some(self.creator_escrow),
+                creator_escrow_vault: escrowed.then_some(self.creator_escrow_vault),
+                event_authority: self.event_authority,
+                program: honorary_quote_fee::ID,
+            }
+            .to_account_metas(None),
+            data: instruction::ForceCloseDay {}.data(),
+        };
+        self.svm.process(&[ix], &[caller])
+    }
+
+    /// Adds `amount` to a token account, as a stray transfer into it would.
+    pub fn credit_tokens(&mut self, account: Pubkey, amount: u64) {
+        let mut state = self.svm.account(&account).unwrap().clone();
+        let mut token = spl_token::state::Account::unpack(&state.data).unwrap();
+        token.amount += amount;
+        spl_token::state::Account::pack(token, &mut state.data).unwrap();
+        self.svm.set_account(account, state);
+    }
+
+    pub fn token_balance(&self, account: &Pubkey) -> u64 {
+        fixtures::token_amount(self.svm.account(account).expect("missing token account"))
+    }
+
+    pub fn investor_balance(&self, index: usize) -> u64 {
+        self.token_balance(&self.investors[index].quote_ata)
+    }
+
+    pub fn policy_account(&self) -> Policy {
+        let data = &self.svm.account(&self.policy).unwrap().data;
+        Policy::try_deserialize(&mut data.as_slice()).unwrap()
+    }
+
+    pub fn creator_escrow_account(&self) -> CreatorEscrow {
+        let data = &self.svm.account(&self.creator_escrow).unwrap().data;
+        CreatorEscrow::try_deserialize(&mut data.as_slice()).unwrap()
+    }
+
+    pub fn progress_account(&self) -> DistributionProgress {
+        let data = &self.svm.account(&self.progress).unwrap().data;
+        DistributionProgress::try_deserialize(&mut data.as_slice()).unwrap()
+    }
+}
@@ -0,0 +1,200 @@
+//! `program_stubs` implementation backing the harness: sysvars, logs, return data and CPI.
+//!
+//! The stubs are process-wide, so all per-transaction state lives in thread locals and
+//! concurrently running tests do not observe each other.
+
+use std::{cell::RefCell, sync::Once};
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::{ProgramResult, SUCCESS},
+        instruction::Instruction,
+        program_error::ProgramError,
+        program_stubs::{set_syscall_stubs, SyscallStubs},
+    },
+};
+
+use super::{invoke_serialized, AccountState, TxOutcome};
+
+#[derive(Default)]
+struct TxContext {
+    clock: Clock,
+    compute_unit_limit: u64,
+    program_stack: Vec<Pubkey>,
+    outcome: TxOutcome,
+    /// First failed CPI; the runtime aborts the transaction there, whatever the caller does
+    /// with the error it is handed.
+    cpi_failure: Option<ProgramError>,
+}
+
+thread_local! {
+    static CONTEXT: RefCell<TxContext> = RefCell::new(TxContext::default());
+}
+
+pub fn install() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        set_syscall_stubs(Box::new(HarnessSyscalls));
+    });
+}
+
+pub fn begin_transaction(clock: Clock, compute_unit_limit: u64) {
+    CONTEXT.with(|context| {
+        *context.borrow_mut() = TxContext {
+            clock,
+            compute_unit_limit,
+            ..TxContext::default()
+        }
+    });
+}
+
+pub fn end_transaction() -> TxOutcome {
+    CONTEXT.with(|context| std::mem::take(&mut context.borrow_mut().outcome))
+}
+
+/// Takes the error of the first CPI that failed in the current instruction, if any.
+pub fn take_cpi_failure() -> Option<ProgramError> {
+    CONTEXT.with(|context| context.borrow_mut().cpi_failure.take())
+}
+
+pub fn push_program(program_id: Pubkey) {
+    CONTEXT.with(|context| context.borrow_mut().program_stack.push(program_id));
+}
+
+pub fn pop_program() {
+    CONTEXT.with(|context| context.borrow_mut().program_stack.pop());
+}
+
+pub fn record_event(event: &[u8]) {
+    CONTEXT.with(|context| context.borrow_mut().outcome.events.push(event.to_vec()));
+}
+
+fn current_program() -> Pubkey {
+    CONTEXT.with(|context| {
+        *context
+            .borrow()
+            .program_stack
+            .last()
+            .expect("syscall outside of a program invocation")
+    })
+}
+
+struct HarnessSyscalls;
+
+impl SyscallStubs for HarnessSyscalls {
+    fn sol_log(&self, message: &str) {
+        CONTEXT.with(|context| context.borrow_mut().outcome.logs.push(message.to_string()));
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = CONTEXT.with(|context| context.borrow().clock.clone());
+        // SAFETY: the caller passes a pointer to a `Clock`.
+        unsafe { *(var_addr as *mut Clock) = clock };
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        // SAFETY: the caller passes a pointer to a `Rent`.
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        SUCCESS
+    }
+
+    fn sol_remaining_compute_units(&self) -> u64 {
+        CONTEXT.with(|context| context.borrow().compute_unit_limit)
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        CONTEXT.with(|context| context.borrow().outcome.return_data.clone())
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        let program_id = current_program();
+        CONTEXT.with(|context| {
+            context.borrow_mut().outcome.return_data =
+                (!data.is_empty()).then(|| (program_id, data.to_vec()));
+        });
+    }
+
+    fn sol_get_stack_height(&self) -> u64 {
+        CONTEXT.with(|context| context.borrow().program_stack.len() as u64)
+    }
+
+    /// Runs the callee on a copy of the caller's accounts, then writes lamports, owner and
+    /// data of writable accounts back into the caller's `AccountInfo`s. A failure is
+    /// recorded so the transaction aborts even if the caller swallows the error.
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        let result = invoke_signed_inner(instruction, account_infos, signers_seeds);
+        if let Err(error) = &result {
+            CONTEXT.with(|context| {
+                context
+                    .borrow_mut()
+                    .cpi_failure
+                    .get_or_insert_with(|| error.clone());
+            });
+        }
+        result
+    }
+}
+
+fn invoke_signed_inner(
+    instruction: &Instruction,
+    account_infos: &[AccountInfo],
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let caller = current_program();
+    let pda_signers = signers_seeds
+        .iter()
+        .map(|seeds| Pubkey::create_program_address(seeds, &caller))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|_| ProgramError::InvalidSeeds)?;
+
+    let mut accounts = Vec::with_capacity(instruction.accounts.len());
+    for meta in instruction.accounts.iter() {
+        let info = account_infos
+            .iter()
+            .find(|info| *info.key == meta.pubkey)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        if meta.is_signer && !info.is_signer && !pda_signers.contains(info.key) {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if meta.is_writable && !info.is_writable {
+            return Err(ProgramError::InvalidArgument);
+        }
+        accounts.push((
+            meta.pubkey,
+            meta.is_signer,
+            meta.is_writable,
+            AccountState {
+                lamports: info.lamports(),
+                data: info.try_borrow_data()?.to_vec(),
+                owner: *info.owner,
+                executable: info.executable,
+            },
+        ));
+    }
+
+    let updated = invoke_serialized(&instruction.program_id, &accounts, &instruction.data)?;
+    for (key, is_writable, state) in updated {
+        if !is_writable {
+            continue;
+        }
+        let info = account_infos
+            .iter()
+            .find(|info| *info.key == key)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        **info.try_borrow_mut_lamports()? = state.lamports;
+        if *info.owner != state.owner {
+            info.assign(&state.owner);
+        }
+        info.resize(state.data.len())?;
+        info.try_borrow_mut_data()?.copy_from_slice(&state.data);
+    }
+    Ok(())
+}
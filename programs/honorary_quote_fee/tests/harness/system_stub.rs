@@ -0,0 +1,87 @@
+//! The handful of system program instructions Anchor's `init` constraint issues.
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{entrypoint::ProgramResult, program_error::ProgramError},
+    system_program,
+};
+
+const CREATE_ACCOUNT: u32 = 0;
+const ASSIGN: u32 = 1;
+const TRANSFER: u32 = 2;
+const ALLOCATE: u32 = 8;
+
+pub fn process(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let (tag, args) = data.split_at(4);
+    match u32::from_le_bytes(tag.try_into().unwrap()) {
+        CREATE_ACCOUNT => {
+            let [from, to, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            let lamports = read_u64(args, 0)?;
+            let space = read_u64(args, 8)?;
+            let owner = read_pubkey(args, 16)?;
+            if to.lamports() != 0 || !to.data_is_empty() || *to.owner != system_program::ID {
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+            transfer(from, to, lamports)?;
+            allocate(to, space)?;
+            assign(to, &owner)
+        }
+        ASSIGN => assign(first(accounts)?, &read_pubkey(args, 0)?),
+        TRANSFER => {
+            let [from, to, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            transfer(from, to, read_u64(args, 0)?)
+        }
+        ALLOCATE => allocate(first(accounts)?, read_u64(args, 0)?),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn first<'a, 'info>(
+    accounts: &'a [AccountInfo<'info>],
+) -> std::result::Result<&'a AccountInfo<'info>, ProgramError> {
+    accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)
+}
+
+fn read_u64(args: &[u8], offset: usize) -> std::result::Result<u64, ProgramError> {
+    args.get(offset..offset + 8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or(ProgramError::InvalidInstructionData)
+}
+
+fn read_pubkey(args: &[u8], offset: usize) -> std::result::Result<Pubkey, ProgramError> {
+    args.get(offset..offset + 32)
+        .map(|bytes| Pubkey::new_from_array(bytes.try_into().unwrap()))
+        .ok_or(ProgramError::InvalidInstructionData)
+}
+
+fn transfer(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+    if !from.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let remaining = from
+        .lamports()
+        .checked_sub(lamports)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    **from.try_borrow_mut_lamports()? = remaining;
+    **to.try_borrow_mut_lamports()? += lamports;
+    Ok(())
+}
+
+fn allocate(account: &AccountInfo, space: u64) -> ProgramResult {
+    if !account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    account.resize(space as usize)
+}
+
+fn assign(account: &AccountInfo, owner: &Pubkey) -> ProgramResult {
+    if !account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    account.assign(owner);
+    Ok(())
+}
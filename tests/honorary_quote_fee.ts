@@ -17,6 +17,20 @@ import {
 } from "@solana/spl-token";
 import { BN } from "bn.js";
 
+// Default linear eligibility curve (kind 0, no breakpoints)
+const linearEligibilityCurve = () => ({
+  kind: 0,
+  breakpointCount: 0,
+  breakpoints: Array.from({ length: 4 }, () => ({ lockedBps: 0, shareBps: 0 })),
+});
+
+// Every stream with a locked balance qualifies
+const noEligibilityRules = () => ({
+  minRemainingLockSeconds: new BN(0),
+  minStreamAgeSeconds: new BN(0),
+  minLockedAmount: new BN(0),
+});
+
 describe("Honorary Quote Fee - End-to-End Tests", () => {
   let env: TestEnvironment;
   let program: any;
@@ -44,10 +58,22 @@ describe("Honorary Quote Fee - End-to-End Tests", () => {
       // Step 1: Initialize Policy
       console.log("📋 Step 1: Initializing Policy");
       const policyParams = {
+        policyIndex: 0, // First policy on this pool
         investorFeeShareBps: 5000, // 50% to investors
         y0: 1000000, // Minimum locked amount threshold
         dailyCapQuote: 1000000, // Daily cap: 1 quote token
         minPayoutLamports: 1000, // Minimum payout: 0.000001 quote tokens
+        forceCloseGraceSeconds: 3600, // Anyone may force-close 1h after the day window
+        minPageIntervalSeconds: 0, // No spacing between pages
+        timelockSeconds: 86400, // Queued policy changes wait one day
+        forceCloseDestination: 0, // Unpaid investor quote stays in carry
+        feeCaptureMode: 1, // Claim honorary position fees only
+        eligibilityCurve: linearEligibilityCurve(),
+        eligibilityRules: noEligibilityRules(),
+        capReserveEnabled: false,
+        reserveRetentionBps: 0,
+        investorSnapshotRequired: false,
+        wholeTokenAmounts: false,
       };
 
       try {
@@ -65,6 +91,7 @@ describe("Honorary Quote Fee - End-to-End Tests", () => {
             dammProgram: CP_AMM_PROGRAM_ID,
             quoteMint: env.quoteMint,
             baseMint: env.baseMint,
+            vestingMint: env.quoteMint,
             quoteVault: env.quoteVault,
             baseVault: env.baseVault,
             creatorQuoteAta: env.creatorQuoteAta,
@@ -180,6 +207,10 @@ describe("Honorary Quote Fee - End-to-End Tests", () => {
           expectedPageCursor: 0,
           maxPageCursor: 10,
           isLastPage: true,
+          hookAccountCount: 0,
+          investorSnapshot: Array(32).fill(0),
+          remainingSnapshot: Array(32).fill(0),
+          maxInvestors: 0,
         };
 
         // Mock remaining accounts representing Streamflow vesting contracts and investor ATAs
@@ -217,11 +248,20 @@ describe("Honorary Quote Fee - End-to-End Tests", () => {
             quoteVault: env.quoteVault,
             baseMint: env.baseMint,
             quoteMint: env.quoteMint,
-            eventAuthority: SYSVAR_RENT_PUBKEY, // Mock event authority
+            dammEventAuthority: SYSVAR_RENT_PUBKEY, // Mock event authority
             cpAmmProgram: CP_AMM_PROGRAM_ID,
             tokenProgramA: TOKEN_PROGRAM_ID,
             tokenProgramB: TOKEN_PROGRAM_ID,
             tokenProgram: TOKEN_PROGRAM_ID,
+            dayHistory: null,
+            policyGroup: null,
+            blocklist: null,
+            forfeitureAta: null,
+            crankerAllowlist: null,
+            distributionHookProgram: null,
+            creatorEscrow: null,
+            creatorEscrowVault: null,
+            distributionHookAuthority: null,
           })
           .remainingAccounts(remainingAccounts)
           .signers([env.authority])
@@ -286,10 +326,22 @@ describe("Honorary Quote Fee - End-to-End Tests", () => {
       console.log("❌ Testing Invalid Policy Parameters");
 
       const invalidParams = {
+        policyIndex: 0,
         investorFeeShareBps: 15000, // Invalid: > 100%
         y0: 0, // Invalid: must be > 0
         dailyCapQuote: 1000000,
         minPayoutLamports: 1000,
+        forceCloseGraceSeconds: 3600,
+        minPageIntervalSeconds: 0,
+        timelockSeconds: 86400,
+        forceCloseDestination: 0,
+        feeCaptureMode: 1,
+        eligibilityCurve: linearEligibilityCurve(),
+        eligibilityRules: noEligibilityRules(),
+        capReserveEnabled: false,
+        reserveRetentionBps: 0,
+        investorSnapshotRequired: false,
+        wholeTokenAmounts: false,
       };
 
       try {
@@ -305,6 +357,7 @@ describe("Honorary Quote Fee - End-to-End Tests", () => {
             dammProgram: CP_AMM_PROGRAM_ID,
             quoteMint: env.quoteMint,
             baseMint: env.baseMint,
+            vestingMint: env.quoteMint,
             quoteVault: env.quoteVault,
             baseVault: env.baseVault,
             creatorQuoteAta: env.creatorQuoteAta,
@@ -364,6 +417,10 @@ describe("Honorary Quote Fee - End-to-End Tests", () => {
           expectedPageCursor: 0,
           maxPageCursor: 10,
           isLastPage: true,
+          hookAccountCount: 0,
+          investorSnapshot: Array(32).fill(0),
+          remainingSnapshot: Array(32).fill(0),
+          maxInvestors: 0,
         };
 
         const remainingAccounts = [
@@ -393,11 +450,20 @@ describe("Honorary Quote Fee - End-to-End Tests", () => {
               quoteVault: env.quoteVault,
               baseMint: env.baseMint,
               quoteMint: env.quoteMint,
-              eventAuthority: SYSVAR_RENT_PUBKEY,
+              dammEventAuthority: SYSVAR_RENT_PUBKEY,
               cpAmmProgram: CP_AMM_PROGRAM_ID,
               tokenProgramA: TOKEN_PROGRAM_ID,
               tokenProgramB: TOKEN_PROGRAM_ID,
               tokenProgram: TOKEN_PROGRAM_ID,
+              dayHistory: null,
+              policyGroup: null,
+              blocklist: null,
+              forfeitureAta: null,
+              crankerAllowlist: null,
+              distributionHookProgram: null,
+              creatorEscrow: null,
+              creatorEscrowVault: null,
+              distributionHookAuthority: null,
             })
             .remainingAccounts(remainingAccounts)
             .signers([env.authority])